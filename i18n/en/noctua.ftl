@@ -22,6 +22,8 @@ window-title = { $filename ->
 ## Menu entries
 menu-main = Menu
 menu-file-open = Open…
+menu-file-save = Save
+menu-file-save-as = Save As…
 menu-file-quit = Quit
 menu-view-zoom-in = Zoom In
 menu-view-zoom-out = Zoom Out
@@ -45,6 +47,8 @@ tooltip-rotate-cw = Rotate clockwise
 tooltip-flip-horizontal = Flip horizontally
 tooltip-flip-vertical = Flip vertically
 tooltip-info-panel = Toggle info panel
+tooltip-histogram-panel = Toggle histogram panel
+tooltip-save-as = Save as…
 
 
 ## Footer / Status bar
@@ -52,6 +56,8 @@ status-zoom-fit = Fit
 status-zoom-percent = { $percent }%
 status-doc-dimensions = { $width } × { $height }
 status-nav-position = { $current } / { $total }
+status-frame-position = Frame { $current } / { $total }
+status-page-position = Page { $pages } / { $total }
 status-separator =  |
 
 
@@ -79,6 +85,23 @@ error-unsupported-format = Unsupported file format
 error-no-image-loaded = No image loaded
 
 
+## Dialogs
+dialog-save-as-title = Save As
+dialog-overwrite-title = Replace File?
+dialog-overwrite-body = "{ $filename }" already exists. Do you want to replace it?
+dialog-replace = Replace
+dialog-cancel = Cancel
+dialog-password-title = Password Required
+dialog-password-body = "{ $filename }" is protected. Enter the password to open it.
+dialog-password-placeholder = Password
+dialog-password-incorrect = Incorrect password, please try again.
+dialog-password-remember = Remember for this session
+dialog-unlock = Unlock
+dialog-open-link-title = Open Link?
+dialog-open-link-body = The document links to { $uri }. Do you want to open it in your browser?
+dialog-open = Open
+
+
 ## Properties panel
 panel-properties = Properties
 panel-actions = Actions
@@ -86,10 +109,16 @@ panel-actions = Actions
 meta-section-file = File Information
 meta-section-exif = Camera Information
 meta-section-image = Image Information
+meta-section-pdf = Document Information
+meta-section-all = All Metadata ({ $count })
+meta-search-placeholder = Filter tags…
+meta-copy-all = Copy all
 
 ## File metadata
 meta-filename = Name
 meta-format = Format
+meta-extension = Extension
+meta-extension-mismatch = .{ $extension } does not match the content
 meta-dimensions = Dimensions
 meta-filesize = Size
 meta-colortype = Color Type
@@ -111,8 +140,66 @@ meta-iso = ISO { $iso }
 meta-focal = Focal Length
 meta-gps = GPS Location
 
+## PDF metadata
+meta-title = Title
+meta-author = Author
+meta-subject = Subject
+meta-keywords = Keywords
+meta-creator = Created With
+meta-producer = Converted With
+meta-created = Created
+meta-modified = Modified
+meta-pdf-version = PDF Version
+meta-security = Security
+meta-encrypted = Encrypted
+meta-not-encrypted = Not encrypted
+meta-permissions = Permissions
+meta-permissions-all = No restrictions
+meta-permissions-none = None
+meta-permission-print = Printing
+meta-permission-modify = Editing
+meta-permission-copy = Copying
+meta-permission-annotate = Annotating
+meta-permission-fill-forms = Filling forms
+meta-page-size = Page Size
+meta-page-size-pages = Page Size (pages { $pages })
+
+## Metadata editing
+meta-section-edit = Edit Metadata
+meta-description = Description
+meta-artist = Artist
+meta-copyright = Copyright
+meta-datetime-placeholder = YYYY-MM-DD HH:MM:SS
+meta-keywords-placeholder = owl; night; forest
+meta-gps-placeholder = Latitude, longitude
+meta-privacy = Privacy (applied when saving)
+meta-strip-all = Remove all metadata
+meta-strip-gps = Remove GPS location
+meta-preview = Preview Changes
+meta-no-changes = No changes
+meta-value-none = (none)
+meta-save = Save
+
+## Histogram panel
+panel-histogram = Histogram
+histogram-luma = Luminance
+histogram-red = Red
+histogram-green = Green
+histogram-blue = Blue
+histogram-mean-median = Mean { $mean } · Median { $median }
+histogram-clipped = Shadows clipped { $shadows } % · Highlights clipped { $highlights } %
+histogram-unique-colors = { $count } unique colors
+histogram-clipping-overlay = Show clipped pixels
+histogram-computing = Computing…
+
+## Pixel inspector
+inspector-position = Pixel { $x }, { $y }
+inspector-click-to-copy = Click to copy the hex value
+inspector-hint = Move the cursor over the image
+
 ## Action buttons
 action-set-wallpaper = Set as Wallpaper
+action-edit-metadata = Edit Metadata
 action-open-with = Open With…
 action-show-in-folder = Show in Folder
 
@@ -122,7 +209,30 @@ nav-panel-title = Pages
 nav-panel-loading = Loading { $current } / { $total }…
 
 
+## Text search
+search-placeholder = Search in document
+search-no-results = No matches
+search-searching = Searching…
+search-hit-position = { $current } of { $total }
+search-hit-page = Page { $page }
+
+
+## Navigation panel
+nav-panel-pages = Pages
+nav-panel-outline = Contents
+outline-untitled = Untitled
+
+
 ## Format panel
+straighten-section-title = Straighten
+straighten-section-subtitle = Rotate by a fine angle ([ and ] for 1°, with Shift for 0.1°)
+straighten-angle = { $angle }°
+straighten-auto-crop = Crop to fill
+straighten-quality = Interpolation
+straighten-quality-fast = Fast (nearest neighbor)
+straighten-quality-balanced = Balanced (bilinear)
+straighten-quality-best = Best (bicubic)
+straighten-reset = Reset Angle
 format-section-title = Paper Format
 format-section-subtitle = Select paper size for export
 orientation-section-title = Orientation
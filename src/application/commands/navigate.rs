@@ -34,12 +34,15 @@ impl NavigateCommand {
 
     /// Execute the navigate command.
     pub fn execute(&self, manager: &mut DocumentManager) -> DocResult<Option<PathBuf>> {
-        let path = match self.direction {
+        let moved = match self.direction {
             NavigationDirection::Next => manager.next_document(),
             NavigationDirection::Previous => manager.previous_document(),
         };
 
-        Ok(path)
+        match moved {
+            Some((path, result)) => result.map(|()| Some(path)),
+            None => Ok(None),
+        }
     }
 
     /// Check if navigation is possible.
@@ -2,27 +2,39 @@
 // src/application/commands/save_document.rs
 //
 // Save document command: export document to a file.
-// Reserved for future implementation - not yet used.
-
-#![allow(dead_code)]
 
 use std::path::Path;
 
 use crate::application::document_manager::DocumentManager;
+use crate::domain::document::core::content::DocumentContent;
 use crate::domain::document::core::document::DocResult;
-use crate::domain::document::operations::export::ExportFormat;
+use crate::domain::document::core::tags;
+use crate::domain::document::operations::container::{self, MetadataBlocks};
+use crate::domain::document::operations::export::{self, ExportFormat, ImageExportOptions};
+use crate::domain::document::operations::metadata_edit::{self, MetadataChange, MetadataEdit};
 
 /// Save document command.
+///
+/// Writes the current document state (including rotations, flips and crops)
+/// to disk through the export operations.
 pub struct SaveDocumentCommand {
-    /// Target format for export.
+    /// Target format for export (None = detect from path).
     format: Option<ExportFormat>,
+    /// Encoder options for the export.
+    options: ImageExportOptions,
+    /// Metadata changes written with the export.
+    metadata_edit: MetadataEdit,
 }
 
 impl SaveDocumentCommand {
     /// Create a new save document command with automatic format detection.
     #[must_use]
     pub fn new() -> Self {
-        Self { format: None }
+        Self {
+            format: None,
+            options: ImageExportOptions::default(),
+            metadata_edit: MetadataEdit::default(),
+        }
     }
 
     /// Create a save document command with a specific format.
@@ -30,30 +42,163 @@ impl SaveDocumentCommand {
     pub fn with_format(format: ExportFormat) -> Self {
         Self {
             format: Some(format),
+            options: ImageExportOptions::default(),
+            metadata_edit: MetadataEdit::default(),
         }
     }
 
+    /// Set the encoder options (quality, WebP mode, metadata).
+    #[must_use]
+    pub fn with_options(mut self, options: ImageExportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the metadata changes (edited fields, GPS stripping).
+    ///
+    /// Ignored when `preserve_metadata` is off, since all metadata is
+    /// stripped then.
+    #[must_use]
+    pub fn with_metadata_edit(mut self, edit: MetadataEdit) -> Self {
+        self.metadata_edit = edit;
+        self
+    }
+
+    /// Resolve the export format for the given target path.
+    ///
+    /// Uses the explicitly chosen format if set, otherwise detects it from
+    /// the file extension.
+    pub fn resolve_format(&self, path: &Path) -> DocResult<ExportFormat> {
+        self.format
+            .or_else(|| ExportFormat::from_path(path))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine export format"))
+    }
+
     /// Execute the save document command.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No document is currently loaded
+    /// - The export format cannot be determined or is not supported
+    /// - Encoding or writing the file fails
     pub fn execute(&self, manager: &DocumentManager, path: &Path) -> DocResult<()> {
-        let _document = manager
+        let data = self.encode(manager, path)?;
+        log::info!("Saving document to {}", path.display());
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Compare the metadata of the current file with what a save to `path`
+    /// would write, without writing anything.
+    ///
+    /// The file is encoded in memory the same way [`Self::execute`] does, so
+    /// the listing also covers metadata the target format cannot hold.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no source file, the file cannot
+    /// be read, or encoding fails.
+    pub fn preview_metadata(
+        &self,
+        manager: &DocumentManager,
+        path: &Path,
+    ) -> DocResult<Vec<MetadataChange>> {
+        let source = manager
+            .current_path()
+            .ok_or_else(|| anyhow::anyhow!("No document loaded"))?;
+        let before = tags::read_tags(&std::fs::read(source)?);
+        let after = tags::read_tags(&self.encode(manager, path)?);
+        Ok(metadata_edit::diff_tags(&before, &after))
+    }
+}
+
+impl SaveDocumentCommand {
+    /// Metadata to write: the source blocks with the edit applied, or only
+    /// the orientation when metadata is stripped.
+    fn output_metadata(
+        &self,
+        mut blocks: MetadataBlocks,
+        orientation: u16,
+    ) -> DocResult<MetadataBlocks> {
+        if !self.options.preserve_metadata {
+            return Ok(MetadataBlocks {
+                exif: (orientation != 1).then(|| container::exif_with_orientation(orientation)),
+                ..MetadataBlocks::default()
+            });
+        }
+        self.metadata_edit.apply(&mut blocks)?;
+        Ok(blocks)
+    }
+
+    /// Encode the file a save to `path` writes.
+    ///
+    /// JPEGs that were only rotated or flipped are written losslessly: the
+    /// source file is copied, its EXIF Orientation tag is updated and the
+    /// metadata segments are rewritten. Everything else is re-encoded from
+    /// the rendered image.
+    fn encode(&self, manager: &DocumentManager, path: &Path) -> DocResult<Vec<u8>> {
+        let document = manager
             .current_document()
             .ok_or_else(|| anyhow::anyhow!("No document loaded"))?;
 
-        // Detect format from path or use specified format
-        let format = self
-            .format
-            .or_else(|| ExportFormat::from_path(path))
-            .ok_or_else(|| anyhow::anyhow!("Could not determine export format"))?;
+        let format = self.resolve_format(path)?;
+
+        if format == ExportFormat::Jpeg
+            && let Some(source) = manager.current_path()
+            && let Some(orientation) = document
+                .source_transform()
+                .and_then(|transform| transform.exif_orientation())
+        {
+            match self.jpeg_lossless(source, orientation) {
+                Ok(data) => return Ok(data),
+                Err(e) => log::info!("Lossless JPEG save not possible, re-encoding: {e}"),
+            }
+        }
 
-        // TODO: Implement actual save logic
-        // This would involve:
-        // 1. Getting the rendered image from the document
-        // 2. Applying any necessary transformations
-        // 3. Exporting to the target format
+        let mut metadata = match (document, manager.current_path()) {
+            (DocumentContent::Raster(_), Some(source)) if self.options.preserve_metadata => {
+                MetadataBlocks::from_file(source).unwrap_or_else(|e| {
+                    log::warn!("Could not read metadata from {}: {e}", source.display());
+                    MetadataBlocks::default()
+                })
+            }
+            _ => MetadataBlocks::default(),
+        };
 
-        log::info!("Save to {} as {:?}", path.display(), format);
+        // The rendered pixels already show the final orientation.
+        if let Some(exif) = &metadata.exif {
+            metadata.exif = container::exif_set_orientation(exif, 1).ok();
+        }
+        let metadata = self.output_metadata(metadata, 1)?;
+
+        log::info!("Encoding document as {format:?}");
+        export::encode_image(document.rendered_image(), format, &self.options, &metadata)
+    }
+
+    /// Copy a JPEG with a new EXIF Orientation tag and the metadata changes,
+    /// leaving the compressed image data untouched.
+    ///
+    /// Stripping the metadata keeps only the orientation and the segments
+    /// needed to show the image.
+    fn jpeg_lossless(&self, source: &Path, orientation: u16) -> DocResult<Vec<u8>> {
+        let mut data = std::fs::read(source)?;
+        if !self.options.preserve_metadata {
+            container::jpeg_strip_metadata(&mut data)?;
+        }
+        container::jpeg_set_orientation(&mut data, orientation)?;
+
+        if self.options.preserve_metadata && !self.metadata_edit.is_empty() {
+            let blocks = self.output_metadata(container::jpeg_metadata(&data)?, orientation)?;
+            container::jpeg_replace_metadata(&mut data, &blocks, true)?;
+            if let Some(iim) = container::jpeg_iptc(&data) {
+                let iim = self.metadata_edit.apply_iptc(iim)?;
+                container::jpeg_set_iptc(&mut data, &iim)?;
+            }
+        }
 
-        Err(anyhow::anyhow!("Save operation not yet implemented"))
+        log::info!("Copying JPEG losslessly with EXIF orientation {orientation}");
+        Ok(data)
     }
 }
 
@@ -62,3 +207,190 @@ impl Default for SaveDocumentCommand {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn test_resolve_format() {
+        let cmd = SaveDocumentCommand::new();
+        assert_eq!(
+            cmd.resolve_format(Path::new("photo.jpg")).ok(),
+            Some(ExportFormat::Jpeg)
+        );
+        assert!(cmd.resolve_format(Path::new("photo")).is_err());
+
+        let cmd = SaveDocumentCommand::with_format(ExportFormat::Png);
+        assert_eq!(
+            cmd.resolve_format(Path::new("photo.jpg")).ok(),
+            Some(ExportFormat::Png)
+        );
+    }
+
+    #[test]
+    fn test_execute_without_document() {
+        let manager = DocumentManager::new();
+        let cmd = SaveDocumentCommand::new();
+        assert!(cmd.execute(&manager, Path::new("out.png")).is_err());
+    }
+
+    #[test]
+    fn test_jpeg_rotation_is_lossless() {
+        use image::metadata::Orientation;
+        use image::{DynamicImage, ImageDecoder, ImageReader, RgbImage};
+
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let source = dir.join("photo.jpg");
+        let target = dir.join("rotated.jpg");
+
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(48, 32, |x, y| {
+            image::Rgb([(x * 5) as u8, (y * 7) as u8, 0])
+        }));
+        img.save(&source).unwrap();
+
+        let mut manager = DocumentManager::new();
+        manager.open_document(&source).unwrap();
+        let document = manager.current_document_mut().unwrap();
+        document.flip_horizontal();
+        document.rotate_cw();
+
+        // Mirroring and then turning clockwise equals turning
+        // counter-clockwise and then mirroring.
+        let mut expected = ImageReader::open(&source).unwrap().decode().unwrap();
+        expected.apply_orientation(Orientation::Rotate270FlipH);
+        assert_eq!(document.rendered_image().to_rgba8(), expected.to_rgba8());
+
+        SaveDocumentCommand::new()
+            .execute(&manager, &target)
+            .unwrap();
+
+        let mut decoder = ImageReader::open(&target).unwrap().into_decoder().unwrap();
+        assert_eq!(decoder.orientation().unwrap(), Orientation::Rotate270FlipH);
+
+        let original = std::fs::read(&source).unwrap();
+        let saved = std::fs::read(&target).unwrap();
+        assert!(saved.ends_with(&original[original.len() / 2..]));
+    }
+
+    #[test]
+    fn test_strip_metadata_lossless() {
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let source = dir.join("photo.jpg");
+        let target = dir.join("stripped.jpg");
+        std::fs::write(&source, test_support::mpo(16, 16)).unwrap();
+
+        let mut manager = DocumentManager::new();
+        manager.open_document(&source).unwrap();
+        manager.current_document_mut().unwrap().rotate_cw();
+        let options = ImageExportOptions {
+            preserve_metadata: false,
+            ..ImageExportOptions::default()
+        };
+        SaveDocumentCommand::new()
+            .with_options(options)
+            .execute(&manager, &target)
+            .unwrap();
+
+        // Only the orientation is left; the second image and its GPS
+        // location are gone.
+        let saved = std::fs::read(&target).unwrap();
+        let mut expected = test_support::jpeg(16, 16);
+        container::jpeg_set_orientation(&mut expected, 6).unwrap();
+        assert_eq!(saved, expected);
+    }
+
+    #[test]
+    fn test_metadata_edit_on_save() {
+        use crate::domain::document::operations::metadata_edit::MetadataField;
+        use image::{DynamicImage, RgbImage};
+
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let source = dir.join("photo.jpg");
+        let target = dir.join("edited.jpg");
+        DynamicImage::ImageRgb8(RgbImage::new(16, 16))
+            .save(&source)
+            .unwrap();
+
+        let mut manager = DocumentManager::new();
+        manager.open_document(&source).unwrap();
+        let mut edit = MetadataEdit::default();
+        edit.fields
+            .insert(MetadataField::Artist, "Jane Doe".to_string());
+        let cmd = SaveDocumentCommand::new().with_metadata_edit(edit);
+
+        // The dry run lists the change without writing anything.
+        let changes = cmd.preview_metadata(&manager, &target).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].name, "Artist");
+        assert_eq!(changes[0].old, None);
+        assert!(!target.exists());
+
+        cmd.execute(&manager, &target).unwrap();
+        let blocks = container::jpeg_metadata(&std::fs::read(&target).unwrap()).unwrap();
+        assert_eq!(
+            metadata_edit::read_fields(&blocks).get(&MetadataField::Artist),
+            Some(&"Jane Doe".to_string())
+        );
+    }
+
+    #[test]
+    fn test_preview_matches_saved_file() {
+        use crate::domain::document::operations::metadata_edit::MetadataField;
+
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let source = dir.join("photo.jpg");
+
+        // EXIF, XMP and IPTC, each with the artist.
+        let mut data = test_support::jpeg(16, 16);
+        let xmp = br#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF>
+  <rdf:Description rdf:about="" xmp:Rating="3">
+   <dc:creator><rdf:Seq><rdf:li>John Roe</rdf:li></rdf:Seq></dc:creator>
+  </rdf:Description>
+ </rdf:RDF></x:xmpmeta>"#;
+        let blocks = MetadataBlocks {
+            exif: Some(container::exif_with_orientation(1)),
+            xmp: Some(xmp.to_vec()),
+            ..MetadataBlocks::default()
+        };
+        container::jpeg_replace_metadata(&mut data, &blocks, true).unwrap();
+        let iptc = b"\x1c\x02\x50\x00\x08John Roe";
+        let mut resources = b"Photoshop 3.0\08BIM\x04\x04\0\0".to_vec();
+        resources.extend_from_slice(&(iptc.len() as u32).to_be_bytes());
+        resources.extend_from_slice(iptc);
+        data.splice(2..2, test_support::jpeg_segment(0xED, &resources));
+        std::fs::write(&source, &data).unwrap();
+
+        let mut manager = DocumentManager::new();
+        manager.open_document(&source).unwrap();
+        manager.current_document_mut().unwrap().rotate_cw();
+        let mut edit = MetadataEdit::default();
+        edit.fields
+            .insert(MetadataField::Artist, "Jane Doe".to_string());
+        let cmd = SaveDocumentCommand::new().with_metadata_edit(edit);
+
+        // The JPEG is copied losslessly, the PNG is re-encoded without the
+        // IPTC data.
+        let before = tags::read_tags(&data);
+        for name in ["rotated.jpg", "rotated.png"] {
+            let target = dir.join(name);
+            let preview = cmd.preview_metadata(&manager, &target).unwrap();
+            assert!(!target.exists());
+
+            cmd.execute(&manager, &target).unwrap();
+            let after = tags::read_tags(&std::fs::read(&target).unwrap());
+            assert_eq!(preview, metadata_edit::diff_tags(&before, &after), "{name}");
+            assert!(
+                preview
+                    .iter()
+                    .any(|change| change.new.as_deref() == Some("Jane Doe")),
+                "{name}"
+            );
+        }
+    }
+}
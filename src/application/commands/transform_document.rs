@@ -4,11 +4,13 @@
 // Transform document command: rotate, flip, and other transformations.
 
 use crate::application::document_manager::DocumentManager;
-use crate::domain::document::core::document::{DocResult, Rotation};
+use crate::domain::document::core::document::{
+    DocResult, InterpolationQuality, Rotation, Transformable,
+};
 use crate::domain::document::operations::transform;
 
 /// Transformation operation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(dead_code)]
 pub enum TransformOperation {
     /// Rotate clockwise by 90 degrees.
@@ -21,6 +23,17 @@ pub enum TransformOperation {
     FlipVertical,
     /// Rotate to a specific angle.
     RotateTo(Rotation),
+    /// Straighten by an arbitrary angle in degrees (replaces the previous one).
+    RotateFine(f32),
+    /// Straighten by an arbitrary angle, resampling raster images on a
+    /// downscaled copy only (finished with a `FineRotationJob`).
+    PreviewFineRotation(f32),
+    /// Remove the fine rotation.
+    ResetFineRotation,
+    /// Change the sampling used for fine rotation.
+    SetInterpolation(InterpolationQuality),
+    /// Crop fine rotations to the largest rectangle without empty corners.
+    SetAutoCrop(bool),
 }
 
 /// Transform document command.
@@ -60,6 +73,21 @@ impl TransformDocumentCommand {
             TransformOperation::RotateTo(rotation) => {
                 transform::rotate_document_to(document, rotation)?;
             }
+            TransformOperation::RotateFine(angle) => {
+                transform::rotate_document_fine(document, angle)?;
+            }
+            TransformOperation::PreviewFineRotation(angle) => {
+                document.preview_fine_rotation(angle);
+            }
+            TransformOperation::ResetFineRotation => {
+                document.reset_fine_rotation();
+            }
+            TransformOperation::SetInterpolation(quality) => {
+                document.set_interpolation_quality(quality);
+            }
+            TransformOperation::SetAutoCrop(enabled) => {
+                document.set_auto_crop(enabled);
+            }
         }
 
         Ok(())
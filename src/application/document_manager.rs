@@ -3,12 +3,14 @@
 //
 // Document manager: orchestrates document lifecycle and navigation.
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use crate::domain::document::collection::DocumentCollection;
 use crate::domain::document::core::content::DocumentContent;
 use crate::domain::document::core::document::DocResult;
 use crate::domain::document::core::metadata::DocumentMeta;
+use crate::domain::document::core::page::PageLayout;
 use crate::infrastructure::filesystem::file_ops;
 use crate::infrastructure::loaders::DocumentLoaderFactory;
 
@@ -23,6 +25,10 @@ pub struct DocumentManager {
     current_metadata: Option<DocumentMeta>,
     /// Document loader factory.
     loader: DocumentLoaderFactory,
+    /// Passwords remembered for this session, keyed by file path.
+    passwords: HashMap<PathBuf, String>,
+    /// Page arrangement for documents that support it.
+    page_layout: PageLayout,
 }
 
 impl DocumentManager {
@@ -33,14 +39,36 @@ impl DocumentManager {
             collection: DocumentCollection::new(),
             current_metadata: None,
             loader: DocumentLoaderFactory::new(),
+            passwords: HashMap::new(),
+            page_layout: PageLayout::default(),
         }
     }
 
     /// Open a document from a file path or directory.
     ///
     /// If a directory is provided, opens the first supported file found.
-    /// Also scans the parent folder for navigation.
+    /// Also scans the parent folder for navigation. Encrypted documents are
+    /// unlocked with a password remembered for this session, if any.
+    ///
+    /// # Errors
+    ///
+    /// Fails with a `PasswordError` (see `downcast_ref`) if the document is
+    /// encrypted and no remembered password unlocks it.
     pub fn open_document(&mut self, path: &Path) -> DocResult<()> {
+        self.open_document_with_password(path, None, false)
+    }
+
+    /// Open a document, unlocking it with `password` if it is encrypted.
+    ///
+    /// Without a password, a remembered one is used. With `remember`, the
+    /// password is kept in memory for the rest of the session once it
+    /// unlocked the document.
+    pub fn open_document_with_password(
+        &mut self,
+        path: &Path,
+        password: Option<&str>,
+        remember: bool,
+    ) -> DocResult<()> {
         // Determine the actual file to open
         let file_path = if path.is_dir() {
             // Scan directory and find first supported file
@@ -56,7 +84,16 @@ impl DocumentManager {
         };
 
         // Load the document
-        let document = self.loader.load(&file_path)?;
+        let password = password
+            .map(str::to_string)
+            .or_else(|| self.passwords.get(&file_path).cloned());
+        let document = self
+            .loader
+            .load_with_password(&file_path, password.as_deref())?;
+
+        if remember && let Some(password) = password {
+            self.passwords.insert(file_path.clone(), password);
+        }
 
         // Extract metadata
         let metadata = self.extract_metadata(&file_path, &document);
@@ -73,9 +110,14 @@ impl DocumentManager {
             }
         }
 
-        // Generate thumbnails for multi-page documents (PDF)
         let mut document = document;
-        if document.is_multi_page() {
+        if let Err(e) = document.set_page_layout(self.page_layout) {
+            log::warn!("Failed to apply page layout: {e}");
+        }
+
+        // Generate thumbnails for multi-page documents (PDF, TIFF). Comic
+        // books make them in the background as they come into view.
+        if document.is_multi_page() && !document.thumbnails_on_demand() {
             log::info!("Generating thumbnails for multi-page document...");
             if let Err(e) = document.generate_thumbnails() {
                 log::warn!("Failed to generate thumbnails: {e}");
@@ -110,6 +152,22 @@ impl DocumentManager {
             .get_thumbnail_handle(page)
     }
 
+    /// Page arrangement used for documents that support it.
+    #[must_use]
+    pub fn page_layout(&self) -> PageLayout {
+        self.page_layout
+    }
+
+    /// Change the page arrangement of the current document and
+    /// of documents opened later.
+    pub fn set_page_layout(&mut self, layout: PageLayout) -> DocResult<()> {
+        self.page_layout = layout;
+        match self.collection.current_document_mut() {
+            Some(document) => document.set_page_layout(layout),
+            None => Ok(()),
+        }
+    }
+
     /// Get the current document path.
     #[must_use]
     pub fn current_path(&self) -> Option<&Path> {
@@ -136,8 +194,9 @@ impl DocumentManager {
 
     /// Navigate to the next document in the folder.
     ///
-    /// Wraps around to the first document when at the end.
-    pub fn next_document(&mut self) -> Option<PathBuf> {
+    /// Wraps around to the first document when at the end. Returns the path
+    /// moved to and the result of opening it, or `None` without a folder.
+    pub fn next_document(&mut self) -> Option<(PathBuf, DocResult<()>)> {
         // Use DocumentCollection navigation
         if self.collection.has_next() {
             self.collection.next();
@@ -148,18 +207,14 @@ impl DocumentManager {
             return None;
         }
 
-        let next_path = self.collection.current_path()?.clone();
-        if self.open_document(&next_path).is_ok() {
-            Some(next_path)
-        } else {
-            None
-        }
+        self.open_current()
     }
 
     /// Navigate to the previous document in the folder.
     ///
-    /// Wraps around to the last document when at the beginning.
-    pub fn previous_document(&mut self) -> Option<PathBuf> {
+    /// Wraps around to the last document when at the beginning. Returns the
+    /// path moved to and the result of opening it, or `None` without a folder.
+    pub fn previous_document(&mut self) -> Option<(PathBuf, DocResult<()>)> {
         // Use DocumentCollection navigation
         if self.collection.has_previous() {
             self.collection.previous();
@@ -171,16 +226,34 @@ impl DocumentManager {
             return None;
         }
 
-        let prev_path = self.collection.current_path()?.clone();
-        if self.open_document(&prev_path).is_ok() {
-            Some(prev_path)
-        } else {
-            None
+        self.open_current()
+    }
+
+    /// Open the document at the current collection index.
+    ///
+    /// If it fails to open, the previous document is closed so that what is
+    /// shown matches the collection index; navigating on continues from there.
+    fn open_current(&mut self) -> Option<(PathBuf, DocResult<()>)> {
+        let path = self.collection.current_path()?.clone();
+        let result = self.open_document(&path);
+        if result.is_err() {
+            self.close_document();
         }
+        Some((path, result))
+    }
+
+    /// Re-read metadata for the current document (e.g. after it was saved).
+    pub fn refresh_metadata(&mut self) {
+        let (Some(path), Some(document)) = (
+            self.collection.current_path(),
+            self.collection.current_document(),
+        ) else {
+            return;
+        };
+        self.current_metadata = Some(document.extract_meta(path));
     }
 
     /// Close the current document.
-    #[allow(dead_code)]
     pub fn close_document(&mut self) {
         self.collection.clear_current_document();
         self.current_metadata = None;
@@ -214,3 +287,36 @@ impl Default for DocumentManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use image::{DynamicImage, RgbImage};
+
+    #[test]
+    fn test_navigation_reports_open_errors() {
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        DynamicImage::ImageRgb8(RgbImage::new(2, 2))
+            .save(dir.join("a.png"))
+            .unwrap();
+        std::fs::write(dir.join("b.png"), b"not an image").unwrap();
+
+        let mut manager = DocumentManager::new();
+        manager.open_document(&dir.join("a.png")).unwrap();
+
+        let (path, result) = manager.next_document().unwrap();
+        assert_eq!(path, dir.join("b.png"));
+        assert!(result.is_err());
+        // The previous document is not left on screen for the new index.
+        assert!(manager.current_document().is_none());
+        assert_eq!(manager.current_path(), Some(path.as_path()));
+
+        // Navigation continues past the broken file.
+        let (path, result) = manager.next_document().unwrap();
+        assert_eq!(path, dir.join("a.png"));
+        assert!(result.is_ok());
+        assert!(manager.current_document().is_some());
+    }
+}
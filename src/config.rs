@@ -6,6 +6,8 @@
 use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
 use std::path::PathBuf;
 
+use crate::domain::document::operations::render::FontFamilies;
+
 /// Global configuration for the application.
 #[derive(Debug, Clone, CosmicConfigEntry, PartialEq)]
 #[version = 1]
@@ -26,10 +28,40 @@ pub struct AppConfig {
     pub max_scale: f32,
     /// Show 3x3 grid during crop selection.
     pub crop_show_grid: bool,
+    /// Quality (1-100) for lossy formats when saving.
+    pub export_quality: u8,
+    /// Save WebP files losslessly instead of using `export_quality`.
+    pub export_webp_lossless: bool,
+    /// Copy EXIF, ICC profile and XMP from the source file when saving.
+    pub export_preserve_metadata: bool,
+    /// Remove the GPS location from the metadata when saving.
+    pub export_strip_gps: bool,
+    /// Crop fine rotations to the largest rectangle without empty corners.
+    pub rotation_auto_crop: bool,
+    /// Font family for SVG text without a `font-family` attribute.
+    ///
+    /// The SVG font settings are read once at startup; changes apply after
+    /// a restart.
+    pub svg_font_family: String,
+    /// Font family for the generic `sans-serif` family in SVG text.
+    pub svg_sans_serif_family: String,
+    /// Font family for the generic `serif` family in SVG text.
+    pub svg_serif_family: String,
+    /// Font family for the generic `monospace` family in SVG text.
+    pub svg_monospace_family: String,
+    /// Show multi-page documents as two-page spreads.
+    pub two_page_spread: bool,
+    /// Show the first page of a spread layout alone.
+    pub cover_alone: bool,
+    /// Read spreads from right to left (manga).
+    pub right_to_left: bool,
+    /// Show PDF pages below each other in a continuous scroll view.
+    pub continuous_scroll: bool,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
+        let fonts = FontFamilies::default();
         Self {
             default_image_dir: dirs::picture_dir().or_else(dirs::home_dir),
             nav_bar_visible: false,
@@ -39,6 +71,19 @@ impl Default for AppConfig {
             min_scale: 0.1,
             max_scale: 8.0,
             crop_show_grid: true,
+            export_quality: 90,
+            export_webp_lossless: false,
+            export_preserve_metadata: true,
+            export_strip_gps: false,
+            rotation_auto_crop: false,
+            svg_font_family: fonts.default,
+            svg_sans_serif_family: fonts.sans_serif,
+            svg_serif_family: fonts.serif,
+            svg_monospace_family: fonts.monospace,
+            two_page_spread: false,
+            cover_alone: true,
+            right_to_left: false,
+            continuous_scroll: false,
         }
     }
 }
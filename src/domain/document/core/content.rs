@@ -4,21 +4,32 @@
 // Type-erased document content enum.
 
 use std::fmt;
+use std::ops::Range;
 use std::path::Path;
+use std::time::Duration;
 
 use cosmic::iced_renderer::graphics::image::image_rs::ImageFormat as CosmicImageFormat;
 use cosmic::widget::image::Handle as ImageHandle;
+use image::DynamicImage;
 
 use super::document::{
     DocResult, DocumentInfo, FlipDirection, InterpolationQuality, MultiPage, MultiPageThumbnails,
-    RenderOutput, Renderable, Rotation, RotationMode, Transformable, TransformState,
+    RenderOutput, Renderable, Rotation, RotationMode, SearchableText, Transformable,
+    TransformState,
 };
+use super::format::{self, DetectedFormat, FormatMismatch};
+use super::page::{LinkTarget, OutlineEntry, PageLayout};
 
+use crate::domain::document::operations::render::{PageRenderJob, RenderedPage, ThumbnailJob};
+use crate::domain::document::operations::search::{SearchHit, SearchJob, TextRect};
+use crate::domain::document::operations::transform::{FineRotationJob, RotatedImage};
 use crate::domain::document::types::raster::RasterDocument;
 #[cfg(feature = "vector")]
 use crate::domain::document::types::vector::VectorDocument;
 #[cfg(feature = "portable")]
 use crate::domain::document::types::portable::PortableDocument;
+#[cfg(feature = "comic")]
+use crate::domain::document::types::comic::ComicDocument;
 
 // ============================================================================
 // Document Kind
@@ -30,11 +41,24 @@ pub enum DocumentKind {
     Raster,
     Vector,
     Portable,
+    Comic,
 }
 
 impl DocumentKind {
-    /// Detect document kind from file path.
+    /// Detect document kind from file content, falling back to the extension
+    /// for formats without magic bytes (e.g. TGA).
+    #[must_use]
+    pub fn detect(path: &Path) -> Option<Self> {
+        Self::from_content(path).or_else(|| Self::from_path(path))
+    }
+
+    /// Detect document kind from the leading bytes of the file.
     #[must_use]
+    pub fn from_content(path: &Path) -> Option<Self> {
+        DetectedFormat::from_file(path)?.kind()
+    }
+
+    /// Detect document kind from file path.
     pub fn from_path(path: &Path) -> Option<Self> {
         let ext = path.extension()?.to_str()?.to_lowercase();
 
@@ -50,6 +74,25 @@ impl DocumentKind {
             return Some(Self::Portable);
         }
 
+        // Comic book archives (by extension only: ZIP is a common container)
+        #[cfg(feature = "comic")]
+        if ext == "cbz" || ext == "zip" {
+            return Some(Self::Comic);
+        }
+
+        // Raster formats with their own decoders
+        #[cfg(feature = "heif")]
+        if matches!(ext.as_str(), "heic" | "heif" | "hif") {
+            return Some(Self::Raster);
+        }
+        #[cfg(feature = "jxl")]
+        if ext == "jxl" {
+            return Some(Self::Raster);
+        }
+        if format::is_camera_raw(path) {
+            return Some(Self::Raster);
+        }
+
         // Raster: Check via cosmic/image-rs
         if CosmicImageFormat::from_path(path).is_ok() {
             return Some(Self::Raster);
@@ -65,6 +108,7 @@ impl fmt::Display for DocumentKind {
             Self::Raster => write!(f, "Raster"),
             Self::Vector => write!(f, "Vector"),
             Self::Portable => write!(f, "Portable"),
+            Self::Comic => write!(f, "Comic"),
         }
     }
 }
@@ -84,6 +128,8 @@ pub enum DocumentContent {
     Vector(VectorDocument),
     #[cfg(feature = "portable")]
     Portable(PortableDocument),
+    #[cfg(feature = "comic")]
+    Comic(ComicDocument),
 }
 
 impl fmt::Debug for DocumentContent {
@@ -94,6 +140,8 @@ impl fmt::Debug for DocumentContent {
             Self::Vector(_) => write!(f, "DocumentContent::Vector(...)"),
             #[cfg(feature = "portable")]
             Self::Portable(_) => write!(f, "DocumentContent::Portable(...)"),
+            #[cfg(feature = "comic")]
+            Self::Comic(_) => write!(f, "DocumentContent::Comic(...)"),
         }
     }
 }
@@ -110,6 +158,8 @@ impl Renderable for DocumentContent {
             Self::Vector(doc) => doc.render(scale),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.render(scale),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.render(scale),
         }
     }
 
@@ -120,6 +170,8 @@ impl Renderable for DocumentContent {
             Self::Vector(doc) => doc.info(),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.info(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.info(),
         }
     }
 }
@@ -132,6 +184,8 @@ impl Transformable for DocumentContent {
             Self::Vector(doc) => doc.rotate(rotation),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.rotate(rotation),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.rotate(rotation),
         }
     }
 
@@ -142,6 +196,8 @@ impl Transformable for DocumentContent {
             Self::Vector(doc) => doc.flip(direction),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.flip(direction),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.flip(direction),
         }
     }
 
@@ -152,6 +208,8 @@ impl Transformable for DocumentContent {
             Self::Vector(doc) => doc.transform_state(),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.transform_state(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.transform_state(),
         }
     }
 
@@ -162,6 +220,8 @@ impl Transformable for DocumentContent {
             Self::Vector(doc) => doc.rotate_fine(angle_degrees),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.rotate_fine(angle_degrees),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.rotate_fine(angle_degrees),
         }
     }
 
@@ -172,6 +232,20 @@ impl Transformable for DocumentContent {
             Self::Vector(doc) => doc.reset_fine_rotation(),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.reset_fine_rotation(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.reset_fine_rotation(),
+        }
+    }
+
+    fn fine_rotation(&self) -> f32 {
+        match self {
+            Self::Raster(doc) => doc.fine_rotation(),
+            #[cfg(feature = "vector")]
+            Self::Vector(doc) => doc.fine_rotation(),
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.fine_rotation(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.fine_rotation(),
         }
     }
 
@@ -182,6 +256,20 @@ impl Transformable for DocumentContent {
             Self::Vector(doc) => doc.set_interpolation_quality(quality),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.set_interpolation_quality(quality),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.set_interpolation_quality(quality),
+        }
+    }
+
+    fn set_auto_crop(&mut self, enabled: bool) {
+        match self {
+            Self::Raster(doc) => doc.set_auto_crop(enabled),
+            #[cfg(feature = "vector")]
+            Self::Vector(doc) => doc.set_auto_crop(enabled),
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.set_auto_crop(enabled),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.set_auto_crop(enabled),
         }
     }
 }
@@ -193,7 +281,7 @@ impl Transformable for DocumentContent {
 impl DocumentContent {
     /// Rotate document 90 degrees clockwise.
     pub fn rotate_cw(&mut self) {
-        let new_rotation_mode = self.transform_state().rotation.rotate_cw();
+        let new_rotation_mode = self.transform_state().rotated_cw();
         match new_rotation_mode {
             RotationMode::Standard(rot) => self.rotate(rot),
             RotationMode::Fine(deg) => {
@@ -212,7 +300,7 @@ impl DocumentContent {
 
     /// Rotate document 90 degrees counter-clockwise.
     pub fn rotate_ccw(&mut self) {
-        let new_rotation_mode = self.transform_state().rotation.rotate_ccw();
+        let new_rotation_mode = self.transform_state().rotated_ccw();
         match new_rotation_mode {
             RotationMode::Standard(rot) => self.rotate(rot),
             RotationMode::Fine(deg) => {
@@ -239,6 +327,23 @@ impl DocumentContent {
         self.flip(FlipDirection::Vertical);
     }
 
+    /// Get the transformation relative to the unmodified source file.
+    ///
+    /// Only raster documents whose pixels were not edited beyond 90°
+    /// rotations and flips report one.
+    #[must_use]
+    pub fn source_transform(&self) -> Option<TransformState> {
+        match self {
+            Self::Raster(doc) => doc.source_transform(),
+            #[cfg(feature = "vector")]
+            Self::Vector(_) => None,
+            #[cfg(feature = "portable")]
+            Self::Portable(_) => None,
+            #[cfg(feature = "comic")]
+            Self::Comic(_) => None,
+        }
+    }
+
     /// Get the document kind.
     #[must_use]
     pub fn kind(&self) -> DocumentKind {
@@ -248,21 +353,68 @@ impl DocumentContent {
             Self::Vector(_) => DocumentKind::Vector,
             #[cfg(feature = "portable")]
             Self::Portable(_) => DocumentKind::Portable,
+            #[cfg(feature = "comic")]
+            Self::Comic(_) => DocumentKind::Comic,
         }
     }
 
     /// Check if document supports multiple pages.
     #[must_use]
     pub fn is_multi_page(&self) -> bool {
-        matches!(self, Self::Portable(_))
+        match self {
+            Self::Raster(doc) => doc.page_count() > 1,
+            #[cfg(feature = "portable")]
+            Self::Portable(_) => true,
+            #[cfg(feature = "comic")]
+            Self::Comic(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Check if the document is an animation (its frames are its pages).
+    #[must_use]
+    pub fn is_animated(&self) -> bool {
+        match self {
+            Self::Raster(doc) => doc.is_animated(),
+            _ => false,
+        }
+    }
+
+    /// How long the current animation frame is shown (None if not animated).
+    #[must_use]
+    pub fn frame_delay(&self) -> Option<Duration> {
+        match self {
+            Self::Raster(doc) => doc.frame_delay(),
+            _ => None,
+        }
+    }
+
+    /// Show an animation frame during playback (see
+    /// `RasterDocument::show_frame`).
+    pub fn show_frame(&mut self, frame: usize) -> DocResult<()> {
+        match self {
+            Self::Raster(doc) => doc.show_frame(frame),
+            _ => self.go_to_page(frame),
+        }
+    }
+
+    /// Copy the pixels of an animation frame shown during playback into the
+    /// document.
+    pub fn settle_frame(&mut self) {
+        if let Self::Raster(doc) = self {
+            doc.settle_frame();
+        }
     }
 
     /// Get total page count (returns 1 for single-page documents).
     #[must_use]
     pub fn page_count(&self) -> usize {
         match self {
+            Self::Raster(doc) => doc.page_count(),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.page_count(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.page_count(),
             _ => 1,
         }
     }
@@ -271,8 +423,11 @@ impl DocumentContent {
     #[must_use]
     pub fn current_page(&self) -> usize {
         match self {
+            Self::Raster(doc) => doc.current_page(),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.current_page(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.current_page(),
             _ => 0,
         }
     }
@@ -280,17 +435,80 @@ impl DocumentContent {
     /// Navigate to a specific page (no-op for single-page documents).
     pub fn go_to_page(&mut self, page: usize) -> DocResult<()> {
         match self {
+            Self::Raster(doc) => doc.go_to_page(page),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.go_to_page(page),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.go_to_page(page),
             _ => Ok(()),
         }
     }
 
+    /// Page arrangement (None for documents without a layout).
+    #[must_use]
+    pub fn page_layout(&self) -> Option<PageLayout> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => Some(doc.layout()),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => Some(doc.layout()),
+            _ => None,
+        }
+    }
+
+    /// Change the page arrangement (no-op for documents without a layout).
+    pub fn set_page_layout(&mut self, layout: PageLayout) -> DocResult<()> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.set_layout(layout),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.set_layout(layout),
+            _ => {
+                let _ = layout;
+                Ok(())
+            }
+        }
+    }
+
+    /// Pages on screen, in reading order.
+    #[must_use]
+    pub fn visible_pages(&self) -> Range<usize> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.visible_pages(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.visible_pages(),
+            _ => {
+                let page = self.current_page();
+                page..page + 1
+            }
+        }
+    }
+
+    /// First page of the next view (None on the last one).
+    #[must_use]
+    pub fn next_page(&self) -> Option<usize> {
+        self.page_layout()
+            .unwrap_or_default()
+            .next_page(self.current_page(), self.page_count())
+    }
+
+    /// First page of the previous view (None on the first one).
+    #[must_use]
+    pub fn prev_page(&self) -> Option<usize> {
+        self.page_layout()
+            .unwrap_or_default()
+            .prev_page(self.current_page(), self.page_count())
+    }
+
     /// Get thumbnail for a specific page (mutable access for trait compatibility).
     pub fn get_thumbnail(&mut self, page: usize) -> DocResult<Option<ImageHandle>> {
         match self {
+            Self::Raster(doc) => doc.get_thumbnail(page),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.get_thumbnail(page),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.get_thumbnail(page),
             _ => Ok(None),
         }
     }
@@ -300,8 +518,11 @@ impl DocumentContent {
     #[must_use]
     pub fn get_thumbnail_handle(&self, page: usize) -> Option<ImageHandle> {
         match self {
+            Self::Raster(doc) => doc.get_thumbnail_handle(page),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.get_thumbnail_handle(page),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.get_thumbnail_handle(page),
             _ => None,
         }
     }
@@ -310,8 +531,11 @@ impl DocumentContent {
     #[must_use]
     pub fn thumbnails_ready(&self) -> bool {
         match self {
+            Self::Raster(doc) => doc.thumbnails_ready(),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.thumbnails_ready(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.thumbnails_ready(),
             _ => false,
         }
     }
@@ -320,8 +544,11 @@ impl DocumentContent {
     #[must_use]
     pub fn thumbnails_loaded(&self) -> usize {
         match self {
+            Self::Raster(doc) => RasterDocument::thumbnails_loaded(doc),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => PortableDocument::thumbnails_loaded(doc),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => ComicDocument::thumbnails_loaded(doc),
             _ => 0,
         }
     }
@@ -330,8 +557,11 @@ impl DocumentContent {
     #[must_use]
     pub fn all_thumbnails_loaded(&self) -> bool {
         match self {
+            Self::Raster(doc) => MultiPageThumbnails::thumbnails_loaded(doc),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => MultiPageThumbnails::thumbnails_loaded(doc),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => MultiPageThumbnails::thumbnails_loaded(doc),
             _ => false,
         }
     }
@@ -339,8 +569,11 @@ impl DocumentContent {
     /// Generate thumbnail for a specific page.
     pub fn generate_thumbnail_page(&mut self, page: usize) -> DocResult<()> {
         match self {
+            Self::Raster(doc) => MultiPageThumbnails::generate_thumbnail_page(doc, page),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => MultiPageThumbnails::generate_thumbnail_page(doc, page),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => MultiPageThumbnails::generate_thumbnail_page(doc, page),
             _ => Ok(()),
         }
     }
@@ -348,12 +581,128 @@ impl DocumentContent {
     /// Generate all thumbnails.
     pub fn generate_thumbnails(&mut self) -> DocResult<()> {
         match self {
+            Self::Raster(doc) => MultiPageThumbnails::generate_all_thumbnails(doc),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => MultiPageThumbnails::generate_all_thumbnails(doc),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => MultiPageThumbnails::generate_all_thumbnails(doc),
             _ => Ok(()),
         }
     }
 
+    /// Check if thumbnails are made on demand for the pages in view
+    /// (`thumbnail_job`) instead of all at once when the document is opened.
+    #[must_use]
+    pub fn thumbnails_on_demand(&self) -> bool {
+        match self {
+            #[cfg(feature = "comic")]
+            Self::Comic(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Prepare background thumbnails for the pages in `pages` that have none
+    /// yet (None if there are none, or the document makes them at open).
+    #[must_use]
+    pub fn thumbnail_job(&self, pages: Range<usize>) -> Option<ThumbnailJob> {
+        match self {
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.thumbnail_job(pages),
+            _ => {
+                let _ = pages;
+                None
+            }
+        }
+    }
+
+    /// Run a `ThumbnailJob`. Does not need the document, so it can be called
+    /// from a worker thread.
+    pub fn run_thumbnail_job(job: &ThumbnailJob) -> DocResult<Vec<(usize, ImageHandle)>> {
+        #[cfg(feature = "comic")]
+        return ComicDocument::run_thumbnail_job(job);
+
+        #[cfg(not(feature = "comic"))]
+        Err(anyhow::anyhow!("No page archive in {}", job.path.display()))
+    }
+
+    /// Store thumbnails made by `run_thumbnail_job`.
+    pub fn set_thumbnails(&mut self, thumbnails: Vec<(usize, ImageHandle)>) {
+        match self {
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.set_thumbnails(thumbnails),
+            _ => {
+                let _ = thumbnails;
+            }
+        }
+    }
+
+    /// Document outline (empty for documents without one).
+    #[must_use]
+    pub fn outline(&self) -> &[OutlineEntry] {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.outline(),
+            _ => &[],
+        }
+    }
+
+    /// Check if the document has a searchable text layer.
+    #[must_use]
+    pub fn supports_text_search(&self) -> bool {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Prepare a background search for `query` (None for documents without
+    /// text).
+    #[must_use]
+    pub fn search_job(&self, query: &str) -> Option<SearchJob> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => Some(doc.search_job(query)),
+            _ => {
+                let _ = query;
+                None
+            }
+        }
+    }
+
+    /// Run a `SearchJob`. Does not need the document, so it can be called
+    /// from a worker thread.
+    pub fn run_search_job(job: &SearchJob) -> DocResult<Vec<SearchHit>> {
+        #[cfg(feature = "portable")]
+        return PortableDocument::run_search_job(job);
+
+        #[cfg(not(feature = "portable"))]
+        Err(anyhow::anyhow!("No text layer in {}", job.path.display()))
+    }
+
+    /// Bounds of a search hit on the displayed page (None if not visible).
+    #[must_use]
+    pub fn search_hit_bounds(&self, hit: &SearchHit) -> Option<TextRect> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.hit_bounds(hit),
+            _ => {
+                let _ = hit;
+                None
+            }
+        }
+    }
+
+    /// Links on the displayed page with their bounds and targets.
+    #[must_use]
+    pub fn links(&self) -> Vec<(TextRect, LinkTarget)> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.links(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Get the current rendered image handle.
     #[must_use]
     pub fn handle(&self) -> Option<ImageHandle> {
@@ -363,6 +712,24 @@ impl DocumentContent {
             Self::Vector(doc) => Some(doc.handle()),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => Some(doc.handle()),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => Some(doc.handle()),
+        }
+    }
+
+    /// Get the current image with all transformations and crops applied.
+    ///
+    /// This is what the user sees on the canvas and what gets written on save.
+    #[must_use]
+    pub fn rendered_image(&self) -> &DynamicImage {
+        match self {
+            Self::Raster(doc) => doc.image(),
+            #[cfg(feature = "vector")]
+            Self::Vector(doc) => &doc.rendered,
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => &doc.rendered,
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.image(),
         }
     }
 
@@ -375,6 +742,183 @@ impl DocumentContent {
             Self::Vector(doc) => doc.dimensions(),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.dimensions(),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.dimensions(),
+        }
+    }
+
+    /// Raster pixels per logical pixel of the current render.
+    ///
+    /// Only documents rendered at zoom-dependent resolution (PDF) and
+    /// previewed fine rotations differ from 1.0.
+    #[must_use]
+    pub fn pixel_density(&self) -> f32 {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.pixel_density(),
+            _ => self
+                .raster_view()
+                .map_or(1.0, RasterDocument::pixel_density),
+        }
+    }
+
+    /// Straighten the document, previewing raster images on a downscaled
+    /// copy (see `RasterDocument::preview_fine_rotation`).
+    ///
+    /// Returns whether a `fine_rotation_job()` has to finish the rotation;
+    /// other documents are rotated right away.
+    pub fn preview_fine_rotation(&mut self, angle_degrees: f32) -> bool {
+        match self.raster_view_mut() {
+            Some(doc) => doc.preview_fine_rotation(angle_degrees),
+            None => {
+                self.rotate_fine(angle_degrees);
+                false
+            }
+        }
+    }
+
+    /// Prepare the full-resolution resampling of a raster fine rotation.
+    #[must_use]
+    pub fn fine_rotation_job(&self) -> Option<FineRotationJob> {
+        self.raster_view()?.fine_rotation_job()
+    }
+
+    /// Take the result of a `FineRotationJob` into the document.
+    ///
+    /// Returns `false` if the result is outdated and was dropped.
+    pub fn apply_fine_rotation(&mut self, rotated: RotatedImage) -> bool {
+        self.raster_view_mut()
+            .is_some_and(|doc| doc.apply_fine_rotation(rotated))
+    }
+
+    /// Whether a previewed fine rotation still waits for its full-resolution
+    /// pixels.
+    #[must_use]
+    pub fn fine_rotation_pending(&self) -> bool {
+        self.raster_view()
+            .is_some_and(RasterDocument::fine_rotation_pending)
+    }
+
+    /// Resample a previewed fine rotation right away.
+    pub fn settle_fine_rotation(&mut self) {
+        if let Some(doc) = self.raster_view_mut() {
+            doc.settle_fine_rotation();
+        }
+    }
+
+    /// The raster image on screen (the page or spread of a comic book).
+    fn raster_view(&self) -> Option<&RasterDocument> {
+        match self {
+            Self::Raster(doc) => Some(doc),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => Some(doc.view()),
+            _ => None,
+        }
+    }
+
+    /// Mutable access to the raster image on screen.
+    fn raster_view_mut(&mut self) -> Option<&mut RasterDocument> {
+        match self {
+            Self::Raster(doc) => Some(doc),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => Some(doc.view_mut()),
+            _ => None,
+        }
+    }
+
+    /// Prepare a background re-render of the current page for the given zoom.
+    ///
+    /// Returns `None` if the document does not need one.
+    #[must_use]
+    pub fn render_job(&self, zoom: f64) -> Option<PageRenderJob> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.render_job(zoom),
+            _ => {
+                let _ = zoom;
+                None
+            }
+        }
+    }
+
+    /// Whether the document can be shown as a continuous strip of pages.
+    #[must_use]
+    pub fn supports_continuous_view(&self) -> bool {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Size of each page at 100% zoom as shown in the continuous view.
+    #[must_use]
+    pub fn page_sizes(&self) -> Vec<(f64, f64)> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.page_sizes(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Rendered page for the continuous view (None until rendered).
+    #[must_use]
+    pub fn page_raster(&self, page: usize) -> Option<ImageHandle> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.page_raster(page),
+            _ => {
+                let _ = page;
+                None
+            }
+        }
+    }
+
+    /// Prepare background renders of `pages` for the continuous view.
+    #[must_use]
+    pub fn page_render_jobs(&self, pages: Range<usize>, zoom: f64) -> Vec<PageRenderJob> {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.page_render_jobs(pages, zoom),
+            _ => {
+                let _ = (pages, zoom);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Forget the continuous view rasters of pages outside `pages`.
+    pub fn retain_page_rasters(&mut self, pages: Range<usize>) {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.retain_page_rasters(pages),
+            _ => {
+                let _ = pages;
+            }
+        }
+    }
+
+    /// Run a `PageRenderJob`. Does not need the document, so it can be
+    /// called from a worker thread.
+    pub fn run_render_job(job: PageRenderJob) -> DocResult<RenderedPage> {
+        #[cfg(feature = "portable")]
+        return PortableDocument::run_render_job(job);
+
+        #[cfg(not(feature = "portable"))]
+        Err(anyhow::anyhow!("No renderer for {}", job.path.display()))
+    }
+
+    /// Replace the current page raster with a background render.
+    ///
+    /// Returns `false` if the render is outdated and was dropped.
+    pub fn apply_rendered_page(&mut self, page: RenderedPage) -> bool {
+        match self {
+            #[cfg(feature = "portable")]
+            Self::Portable(doc) => doc.apply_rendered_page(page),
+            _ => {
+                let _ = page;
+                false
+            }
         }
     }
 
@@ -386,18 +930,31 @@ impl DocumentContent {
             Self::Vector(doc) => doc.crop(x, y, width, height).map_err(|e| anyhow::anyhow!(e)),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.crop(x, y, width, height).map_err(|e| anyhow::anyhow!(e)),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.crop(x, y, width, height).map_err(|e| anyhow::anyhow!(e)),
         }
     }
 
     /// Extract document metadata (basic info and EXIF if available).
+    ///
+    /// Files whose extension contradicts their content report the detected
+    /// format and the mismatch.
     #[must_use]
     pub fn extract_meta(&self, path: &Path) -> crate::domain::document::core::metadata::DocumentMeta {
-        match self {
+        let mut meta = match self {
             Self::Raster(doc) => doc.extract_meta(path),
             #[cfg(feature = "vector")]
             Self::Vector(doc) => doc.extract_meta(path),
             #[cfg(feature = "portable")]
             Self::Portable(doc) => doc.extract_meta(path),
+            #[cfg(feature = "comic")]
+            Self::Comic(doc) => doc.extract_meta(path),
+        };
+
+        if let Some(mismatch) = FormatMismatch::check(path) {
+            meta.basic.format = mismatch.detected.name();
+            meta.basic.format_mismatch = Some(mismatch);
         }
+        meta
     }
 }
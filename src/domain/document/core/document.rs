@@ -5,6 +5,9 @@
 
 use cosmic::widget::image::Handle as ImageHandle;
 
+use super::page::OutlineEntry;
+use crate::domain::document::operations::search::{SearchHit, TextRect};
+
 // ============================================================================
 // Type Definitions
 // ============================================================================
@@ -12,6 +15,29 @@ use cosmic::widget::image::Handle as ImageHandle;
 /// Result type alias for document operations.
 pub type DocResult<T> = anyhow::Result<T>;
 
+/// Error for encrypted documents that could not be unlocked.
+///
+/// Returned inside a `DocResult` error; use `downcast_ref` to tell it apart
+/// from documents that failed to load for other reasons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordError {
+    /// The document is encrypted and no password was given.
+    Required,
+    /// The given password does not unlock the document.
+    Incorrect,
+}
+
+impl std::fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Required => write!(f, "Document is password protected"),
+            Self::Incorrect => write!(f, "Incorrect password"),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
+
 /// Rotation state for documents.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Rotation {
@@ -126,7 +152,6 @@ impl RotationMode {
 
 /// Interpolation quality for fine rotation and resizing operations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-#[allow(dead_code)]
 pub enum InterpolationQuality {
     /// Fast, nearest neighbor interpolation.
     Fast,
@@ -147,6 +172,9 @@ pub enum FlipDirection {
 }
 
 /// Current transformation state of a document.
+///
+/// The displayed image is the source rotated by `rotation` and then
+/// mirrored by `flip_h` / `flip_v`.
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct TransformState {
     /// Current rotation mode (standard 90° or fine rotation).
@@ -157,6 +185,112 @@ pub struct TransformState {
     pub flip_v: bool,
 }
 
+impl TransformState {
+    /// Check if the image is mirrored (exactly one flip applied).
+    #[must_use]
+    pub fn is_mirrored(&self) -> bool {
+        self.flip_h != self.flip_v
+    }
+
+    /// Rotation mode after turning the displayed image 90° clockwise.
+    ///
+    /// Since flips are applied after the rotation, a mirrored image has to
+    /// rotate its source the other way round.
+    #[must_use]
+    pub fn rotated_cw(&self) -> RotationMode {
+        if self.is_mirrored() {
+            self.rotation.rotate_ccw()
+        } else {
+            self.rotation.rotate_cw()
+        }
+    }
+
+    /// Rotation mode after turning the displayed image 90° counter-clockwise.
+    #[must_use]
+    pub fn rotated_ccw(&self) -> RotationMode {
+        if self.is_mirrored() {
+            self.rotation.rotate_cw()
+        } else {
+            self.rotation.rotate_ccw()
+        }
+    }
+
+    /// Add a fine rotation of the displayed image (degrees, clockwise).
+    ///
+    /// Like `rotated_cw()`, a mirrored image turns its source the other way.
+    #[must_use]
+    pub fn with_fine_rotation(self, angle_degrees: f32) -> Self {
+        if angle_degrees == 0.0 {
+            return self;
+        }
+
+        let angle = if self.is_mirrored() {
+            -angle_degrees
+        } else {
+            angle_degrees
+        };
+        let degrees = self.rotation.to_degrees() + angle;
+
+        Self {
+            rotation: RotationMode::Fine(degrees.rem_euclid(360.0)),
+            ..self
+        }
+    }
+
+    /// Build the state that displays an image with the given EXIF
+    /// Orientation value (1-8) upright.
+    ///
+    /// Unknown values map to the identity.
+    #[must_use]
+    pub fn from_exif_orientation(orientation: u16) -> Self {
+        let (rotation, flip_h, flip_v) = match orientation {
+            2 => (Rotation::None, true, false),
+            3 => (Rotation::Cw180, false, false),
+            4 => (Rotation::None, false, true),
+            5 => (Rotation::Cw90, true, false),
+            6 => (Rotation::Cw90, false, false),
+            7 => (Rotation::Cw270, true, false),
+            8 => (Rotation::Cw270, false, false),
+            _ => (Rotation::None, false, false),
+        };
+
+        Self {
+            rotation: RotationMode::Standard(rotation),
+            flip_h,
+            flip_v,
+        }
+    }
+
+    /// Get the EXIF Orientation value (1-8) equivalent to this state.
+    ///
+    /// Returns `None` for fine rotations, which have no EXIF equivalent.
+    #[must_use]
+    pub fn exif_orientation(&self) -> Option<u16> {
+        let RotationMode::Standard(rotation) = self.rotation else {
+            return None;
+        };
+
+        // A vertical flip equals a horizontal flip after a 180° turn.
+        let (rotation, mirrored) = match (self.flip_h, self.flip_v) {
+            (false, false) => (rotation, false),
+            (true, false) => (rotation, true),
+            (false, true) => (rotation.rotate_cw().rotate_cw(), true),
+            (true, true) => (rotation.rotate_cw().rotate_cw(), false),
+        };
+
+        Some(match (rotation, mirrored) {
+            (Rotation::None, false) => 1,
+            (Rotation::None, true) => 2,
+            (Rotation::Cw180, false) => 3,
+            (Rotation::Cw180, true) => 4,
+            (Rotation::Cw90, true) => 5,
+            (Rotation::Cw90, false) => 6,
+            (Rotation::Cw270, true) => 7,
+            (Rotation::Cw270, false) => 8,
+        })
+    }
+}
+
 /// Output of a render operation.
 #[derive(Debug, Clone)]
 pub struct RenderOutput {
@@ -203,7 +337,10 @@ pub trait Transformable {
     /// Get the current transformation state.
     fn transform_state(&self) -> TransformState;
 
-    /// Apply fine-grained rotation in degrees (0.0 - 360.0).
+    /// Apply fine-grained rotation in degrees (clockwise, -180.0 - 180.0).
+    ///
+    /// The angle turns the displayed image on top of the 90° rotation and
+    /// flips. It replaces the previous fine rotation instead of adding to it.
     fn rotate_fine(&mut self, _angle_degrees: f32) {
         // Default: no-op (not all formats support fine rotation)
     }
@@ -213,10 +350,20 @@ pub trait Transformable {
         // Default: no-op
     }
 
+    /// Get the current fine rotation in degrees (0.0 if none).
+    fn fine_rotation(&self) -> f32 {
+        0.0
+    }
+
     /// Set interpolation quality for transformations.
     fn set_interpolation_quality(&mut self, _quality: InterpolationQuality) {
         // Default: no-op
     }
+
+    /// Crop fine rotations to the largest rectangle without empty corners.
+    fn set_auto_crop(&mut self, _enabled: bool) {
+        // Default: no-op
+    }
 }
 
 /// Trait for documents with multiple pages.
@@ -229,6 +376,11 @@ pub trait MultiPage {
 
     /// Navigate to a specific page.
     fn go_to_page(&mut self, page: usize) -> DocResult<()>;
+
+    /// Get the document outline (table of contents), empty if there is none.
+    fn outline(&self) -> &[OutlineEntry] {
+        &[]
+    }
 }
 
 /// Trait for multi-page documents that support thumbnail generation.
@@ -248,3 +400,130 @@ pub trait MultiPageThumbnails: MultiPage {
     /// Generate all thumbnails.
     fn generate_all_thumbnails(&mut self) -> DocResult<()>;
 }
+
+/// Trait for multi-page documents with a searchable text layer.
+///
+/// Searching itself runs on a worker thread through a `SearchJob`.
+pub trait SearchableText: MultiPage {
+    /// Bounds of a hit on the displayed page, in the logical pixels of
+    /// `Renderable::info()`, with the current rotation and flips applied.
+    ///
+    /// Returns `None` if the hit is not on the current page.
+    fn hit_bounds(&self, hit: &SearchHit) -> Option<TextRect>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(rotation: Rotation, flip_h: bool, flip_v: bool) -> TransformState {
+        TransformState {
+            rotation: RotationMode::Standard(rotation),
+            flip_h,
+            flip_v,
+        }
+    }
+
+    #[test]
+    fn test_exif_orientation() {
+        assert_eq!(TransformState::default().exif_orientation(), Some(1));
+        assert_eq!(
+            state(Rotation::None, true, false).exif_orientation(),
+            Some(2)
+        );
+        assert_eq!(
+            state(Rotation::Cw180, false, false).exif_orientation(),
+            Some(3)
+        );
+        assert_eq!(
+            state(Rotation::None, false, true).exif_orientation(),
+            Some(4)
+        );
+        assert_eq!(
+            state(Rotation::Cw90, true, false).exif_orientation(),
+            Some(5)
+        );
+        assert_eq!(
+            state(Rotation::Cw90, false, false).exif_orientation(),
+            Some(6)
+        );
+        assert_eq!(
+            state(Rotation::Cw270, true, false).exif_orientation(),
+            Some(7)
+        );
+        assert_eq!(
+            state(Rotation::Cw270, false, false).exif_orientation(),
+            Some(8)
+        );
+        assert_eq!(
+            state(Rotation::Cw90, true, true).exif_orientation(),
+            Some(8)
+        );
+
+        let fine = TransformState {
+            rotation: RotationMode::Fine(12.5),
+            ..TransformState::default()
+        };
+        assert_eq!(fine.exif_orientation(), None);
+    }
+
+    #[test]
+    fn test_from_exif_orientation() {
+        let expected = [
+            (1, state(Rotation::None, false, false)),
+            (2, state(Rotation::None, true, false)),
+            (3, state(Rotation::Cw180, false, false)),
+            (4, state(Rotation::None, false, true)),
+            (5, state(Rotation::Cw90, true, false)),
+            (6, state(Rotation::Cw90, false, false)),
+            (7, state(Rotation::Cw270, true, false)),
+            (8, state(Rotation::Cw270, false, false)),
+        ];
+
+        for (orientation, transform) in expected {
+            let parsed = TransformState::from_exif_orientation(orientation);
+            assert_eq!(parsed, transform, "orientation {orientation}");
+            assert_eq!(parsed.exif_orientation(), Some(orientation));
+        }
+
+        assert_eq!(
+            TransformState::from_exif_orientation(0),
+            TransformState::default()
+        );
+        assert_eq!(
+            TransformState::from_exif_orientation(9),
+            TransformState::default()
+        );
+    }
+
+    #[test]
+    fn test_rotate_mirrored() {
+        let mirrored = state(Rotation::None, true, false);
+        assert_eq!(
+            mirrored.rotated_cw(),
+            RotationMode::Standard(Rotation::Cw270)
+        );
+        assert_eq!(
+            mirrored.rotated_ccw(),
+            RotationMode::Standard(Rotation::Cw90)
+        );
+
+        let both = state(Rotation::None, true, true);
+        assert_eq!(both.rotated_cw(), RotationMode::Standard(Rotation::Cw90));
+    }
+
+    #[test]
+    fn test_password_error_survives_context() {
+        use anyhow::Context;
+
+        let result: DocResult<()> = Err(PasswordError::Incorrect.into());
+        let error = result.context("Failed to load PDF document").unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<PasswordError>(),
+            Some(&PasswordError::Incorrect)
+        );
+
+        let error = anyhow::anyhow!("Failed to parse PDF");
+        assert!(error.downcast_ref::<PasswordError>().is_none());
+    }
+}
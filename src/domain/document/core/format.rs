@@ -0,0 +1,407 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/domain/document/core/format.rs
+//
+// Document format detection from file content (magic bytes).
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use image::ImageFormat;
+
+use super::content::DocumentKind;
+
+/// Number of leading bytes read for format detection.
+const SNIFF_LEN: u64 = 4096;
+
+/// How far into the file the `%PDF-` header may start (as accepted by
+/// common PDF readers).
+const PDF_HEADER_WINDOW: usize = 1024;
+
+/// Signature of a gzip stream.
+const GZIP_SIGNATURE: &[u8] = &[0x1f, 0x8b];
+
+/// Signature box of the JPEG XL container format.
+const JXL_CONTAINER_SIGNATURE: &[u8] = b"\0\0\0\x0cJXL \r\n\x87\n";
+
+/// Signature of a bare JPEG XL codestream.
+const JXL_CODESTREAM_SIGNATURE: &[u8] = &[0xff, 0x0a];
+
+/// `ftyp` brands of AVIF images (checked before the generic HEIF brands,
+/// since AVIF files list those too).
+const AVIF_BRANDS: &[&[u8; 4]] = &[b"avif", b"avis"];
+
+/// `ftyp` brands of HEIF images.
+const HEIF_BRANDS: &[&[u8; 4]] = &[
+    b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx", b"mif1", b"msf1",
+];
+
+/// Extensions of TIFF-based camera RAW formats.
+const CAMERA_RAW_EXTENSIONS: &[&str] = &[
+    "dng", "cr2", "nef", "nrw", "arw", "srf", "sr2", "pef", "srw",
+];
+
+/// Format recognized from the content of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedFormat {
+    /// Raster image the `image` crate can decode.
+    Raster(ImageFormat),
+    /// SVG document.
+    Svg,
+    /// Gzip-compressed SVG document.
+    Svgz,
+    /// PDF document.
+    Pdf,
+    /// HEIF/HEIC image (decoded with libheif, `heif` feature).
+    Heif,
+    /// JPEG XL image (decoded with jxl-oxide, `jxl` feature).
+    Jxl,
+}
+
+impl DetectedFormat {
+    /// Recognize the format from the leading bytes of a file.
+    #[must_use]
+    pub fn sniff(header: &[u8]) -> Option<Self> {
+        // Gzip is only used for compressed SVG among the supported formats,
+        // but other gzip files must not be taken for one.
+        if header.starts_with(GZIP_SIGNATURE) {
+            return gzip_is_svg(header).then_some(Self::Svgz);
+        }
+
+        if let Some(brands) = ftyp_brands(header) {
+            let has_brand = |list: &[&[u8; 4]]| brands.iter().any(|brand| list.contains(&brand));
+            if has_brand(AVIF_BRANDS) {
+                return Some(Self::Raster(ImageFormat::Avif));
+            }
+            if has_brand(HEIF_BRANDS) {
+                return Some(Self::Heif);
+            }
+        }
+
+        if header.starts_with(JXL_CONTAINER_SIGNATURE)
+            || header.starts_with(JXL_CODESTREAM_SIGNATURE)
+        {
+            return Some(Self::Jxl);
+        }
+
+        if let Ok(format) = image::guess_format(header) {
+            return Some(Self::Raster(format));
+        }
+
+        if is_svg(header) {
+            return Some(Self::Svg);
+        }
+
+        // Searched last: images and SVG text may mention the PDF header in
+        // their metadata or comments.
+        let pdf_window = &header[..header.len().min(PDF_HEADER_WINDOW)];
+        pdf_window
+            .windows(5)
+            .any(|w| w == b"%PDF-")
+            .then_some(Self::Pdf)
+    }
+
+    /// Read the start of the file at `path` and recognize its format.
+    #[must_use]
+    pub fn from_file(path: &Path) -> Option<Self> {
+        let mut header = Vec::new();
+        File::open(path)
+            .ok()?
+            .take(SNIFF_LEN)
+            .read_to_end(&mut header)
+            .ok()?;
+        Self::sniff(&header)
+    }
+
+    /// Document kind for this format.
+    /// Returns None if support for it is not compiled in.
+    #[must_use]
+    pub fn kind(self) -> Option<DocumentKind> {
+        match self {
+            Self::Raster(format) => format.reading_enabled().then_some(DocumentKind::Raster),
+            #[cfg(feature = "heif")]
+            Self::Heif => Some(DocumentKind::Raster),
+            #[cfg(feature = "jxl")]
+            Self::Jxl => Some(DocumentKind::Raster),
+            #[cfg(feature = "vector")]
+            Self::Svg | Self::Svgz => Some(DocumentKind::Vector),
+            #[cfg(feature = "portable")]
+            Self::Pdf => Some(DocumentKind::Portable),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The image-rs format, for formats decoded by image-rs.
+    #[must_use]
+    pub fn image_format(self) -> Option<ImageFormat> {
+        match self {
+            Self::Raster(format) => Some(format),
+            _ => None,
+        }
+    }
+
+    /// Format name for display (e.g., "PNG", "SVG").
+    #[must_use]
+    pub fn name(self) -> String {
+        match self {
+            Self::Raster(format) => format!("{format:?}").to_uppercase(),
+            Self::Svg => "SVG".to_string(),
+            Self::Svgz => "SVGZ".to_string(),
+            Self::Pdf => "PDF".to_string(),
+            Self::Heif => "HEIF".to_string(),
+            Self::Jxl => "JPEG XL".to_string(),
+        }
+    }
+
+    /// Is `ext` a file extension of this format?
+    #[must_use]
+    pub fn matches_extension(self, ext: &str) -> bool {
+        match self {
+            // Camera RAW files are TIFF files with their own extension.
+            Self::Raster(ImageFormat::Tiff) if is_camera_raw_extension(ext) => true,
+            Self::Raster(format) => ImageFormat::from_extension(ext) == Some(format),
+            Self::Svg => ext.eq_ignore_ascii_case("svg"),
+            Self::Svgz => ext.eq_ignore_ascii_case("svgz"),
+            Self::Pdf => ext.eq_ignore_ascii_case("pdf"),
+            Self::Heif => ["heic", "heif", "hif"]
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known)),
+            Self::Jxl => ext.eq_ignore_ascii_case("jxl"),
+        }
+    }
+}
+
+/// Is the file at `path` named like a TIFF-based camera RAW file?
+#[must_use]
+pub fn is_camera_raw(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(is_camera_raw_extension)
+}
+
+fn is_camera_raw_extension(ext: &str) -> bool {
+    CAMERA_RAW_EXTENSIONS
+        .iter()
+        .any(|known| ext.eq_ignore_ascii_case(known))
+}
+
+/// Major and compatible brands of an ISO base media file (HEIF, AVIF), read
+/// from the leading `ftyp` box.
+fn ftyp_brands(header: &[u8]) -> Option<Vec<&[u8; 4]>> {
+    let size = u32::from_be_bytes(header.get(..4)?.try_into().ok()?) as usize;
+    if header.get(4..8)? != b"ftyp" {
+        return None;
+    }
+
+    // Major brand, minor version, then the compatible brands.
+    let body = header.get(8..size.min(header.len()))?;
+    let major = body.get(..4)?.try_into().ok()?;
+    let compatible = body.get(8..).unwrap_or_default().chunks_exact(4);
+    Some(
+        std::iter::once(major)
+            .chain(compatible.filter_map(|brand| brand.try_into().ok()))
+            .collect(),
+    )
+}
+
+/// Does the header look like an SVG document?
+///
+/// Accepts an optional byte order mark, XML prolog, comments and doctype
+/// before the `<svg` root element.
+fn is_svg(header: &[u8]) -> bool {
+    let text = header.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(header);
+    text.trim_ascii_start().starts_with(b"<") && text.windows(4).any(|w| w == b"<svg")
+}
+
+/// Check whether the gzip stream starting with `header` holds an SVG document.
+#[cfg(feature = "vector")]
+fn gzip_is_svg(header: &[u8]) -> bool {
+    let mut text = Vec::new();
+    // The stream is cut off at the end of the header, so reading fails after
+    // the decompressed prefix; the prefix is all that is needed.
+    let _ = flate2::read::GzDecoder::new(header)
+        .take(SNIFF_LEN)
+        .read_to_end(&mut text);
+    is_svg(&text)
+}
+
+/// Without SVG support, compressed SVG is never opened, so any gzip stream
+/// may stand for one.
+#[cfg(not(feature = "vector"))]
+fn gzip_is_svg(_header: &[u8]) -> bool {
+    true
+}
+
+/// File extension that contradicts the content of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatMismatch {
+    /// Extension of the file name, as written.
+    pub extension: String,
+    /// Format found in the content.
+    pub detected: DetectedFormat,
+}
+
+impl FormatMismatch {
+    /// Compare the extension of `path` with the content of the file.
+    ///
+    /// Returns None if they agree, the file has no extension, or the content
+    /// format is not recognized.
+    #[must_use]
+    pub fn check(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        let detected = DetectedFormat::from_file(path)?;
+
+        (!detected.matches_extension(extension)).then(|| Self {
+            extension: extension.to_string(),
+            detected,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const JPEG: &[u8] = b"\xFF\xD8\xFF\xE0\0\x10JFIF\0";
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(
+            DetectedFormat::sniff(PNG),
+            Some(DetectedFormat::Raster(ImageFormat::Png))
+        );
+        assert_eq!(
+            DetectedFormat::sniff(JPEG),
+            Some(DetectedFormat::Raster(ImageFormat::Jpeg))
+        );
+        assert_eq!(
+            DetectedFormat::sniff(b"%PDF-1.7\n%\xE2\xE3\xCF\xD3"),
+            Some(DetectedFormat::Pdf)
+        );
+        // Some writers put junk before the PDF header.
+        assert_eq!(
+            DetectedFormat::sniff(b"\r\n\r\n%PDF-1.4"),
+            Some(DetectedFormat::Pdf)
+        );
+        assert_eq!(
+            DetectedFormat::sniff(&gzip(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>")),
+            Some(DetectedFormat::Svgz)
+        );
+        // Only the start of a large file is read.
+        let rects: String = (0..4000)
+            .map(|i| format!("<rect x=\"{}\"/>", i * 7919 % 10007))
+            .collect();
+        let large = gzip(format!("<svg>{rects}</svg>").as_bytes());
+        assert!(large.len() > SNIFF_LEN as usize);
+        assert_eq!(
+            DetectedFormat::sniff(&large[..SNIFF_LEN as usize]),
+            Some(DetectedFormat::Svgz)
+        );
+        // Other gzip files are not compressed SVG.
+        assert_eq!(DetectedFormat::sniff(&gzip(b"log line\n")), None);
+        assert_eq!(
+            DetectedFormat::sniff(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            Some(DetectedFormat::Svg)
+        );
+        assert_eq!(
+            DetectedFormat::sniff(
+                b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<!-- logo -->\n<svg width=\"4\"/>"
+            ),
+            Some(DetectedFormat::Svg)
+        );
+
+        assert_eq!(
+            DetectedFormat::sniff(b"\0\0\0\x18ftypheic\0\0\0\0mif1heic"),
+            Some(DetectedFormat::Heif)
+        );
+        // AVIF files list the generic HEIF brands as well.
+        assert_eq!(
+            DetectedFormat::sniff(b"\0\0\0\x1cftypmif1\0\0\0\0mif1avifmiaf"),
+            Some(DetectedFormat::Raster(ImageFormat::Avif))
+        );
+        assert_eq!(
+            DetectedFormat::sniff(b"\0\0\0\x0cJXL \r\n\x87\n\0\0\0\x14ftypjxl "),
+            Some(DetectedFormat::Jxl)
+        );
+        assert_eq!(
+            DetectedFormat::sniff(b"\xff\x0a\xfa\x1f"),
+            Some(DetectedFormat::Jxl)
+        );
+
+        // Files that mention the PDF header near their start.
+        let mut png = PNG.to_vec();
+        png.extend_from_slice(b"\0\0\0\x0ctEXtComment\0%PDF-");
+        assert_eq!(
+            DetectedFormat::sniff(&png),
+            Some(DetectedFormat::Raster(ImageFormat::Png))
+        );
+        let mut jpeg = JPEG.to_vec();
+        jpeg.extend_from_slice(b"\xFF\xFE\0\x0bfrom %PDF-");
+        assert_eq!(
+            DetectedFormat::sniff(&jpeg),
+            Some(DetectedFormat::Raster(ImageFormat::Jpeg))
+        );
+        assert_eq!(
+            DetectedFormat::sniff(b"<!-- converted from %PDF-1.4 --><svg width=\"4\"/>"),
+            Some(DetectedFormat::Svg)
+        );
+
+        assert_eq!(DetectedFormat::sniff(b"<html><body></body></html>"), None);
+        assert_eq!(DetectedFormat::sniff(b"plain text mentioning <svg"), None);
+        assert_eq!(DetectedFormat::sniff(b""), None);
+    }
+
+    #[test]
+    fn test_matches_extension() {
+        let jpeg = DetectedFormat::Raster(ImageFormat::Jpeg);
+        assert!(jpeg.matches_extension("jpg"));
+        assert!(jpeg.matches_extension("JPEG"));
+        assert!(!jpeg.matches_extension("png"));
+        assert!(DetectedFormat::Svg.matches_extension("SVG"));
+        assert!(!DetectedFormat::Svgz.matches_extension("svg"));
+        assert!(DetectedFormat::Pdf.matches_extension("pdf"));
+        assert!(DetectedFormat::Heif.matches_extension("HEIC"));
+        assert!(DetectedFormat::Jxl.matches_extension("jxl"));
+
+        let tiff = DetectedFormat::Raster(ImageFormat::Tiff);
+        assert!(tiff.matches_extension("tif"));
+        assert!(tiff.matches_extension("NEF"));
+        assert!(!jpeg.matches_extension("dng"));
+        assert!(is_camera_raw(Path::new("IMG_0001.CR2")));
+        assert!(!is_camera_raw(Path::new("IMG_0001.tif")));
+    }
+
+    #[test]
+    fn test_format_mismatch() {
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+
+        let misnamed = dir.join("photo.jpg");
+        std::fs::write(&misnamed, PNG).unwrap();
+        let mismatch = FormatMismatch::check(&misnamed).unwrap();
+        assert_eq!(mismatch.extension, "jpg");
+        assert_eq!(mismatch.detected.name(), "PNG");
+
+        let named = dir.join("photo.png");
+        std::fs::write(&named, PNG).unwrap();
+        assert_eq!(FormatMismatch::check(&named), None);
+
+        // Without an extension there is nothing to contradict.
+        let bare = dir.join("image");
+        std::fs::write(&bare, PNG).unwrap();
+        assert_eq!(FormatMismatch::check(&bare), None);
+        assert_eq!(DocumentKind::detect(&bare), Some(DocumentKind::Raster));
+    }
+}
@@ -1,9 +1,14 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // src/domain/document/core/metadata.rs
 //
-// Document metadata structures and EXIF parsing.
+// Document metadata structures, EXIF parsing and PDF document information.
 
 use std::io::Cursor;
+use std::ops::Range;
+
+use super::format::FormatMismatch;
+use super::tags::MetaTag;
+use crate::domain::document::operations::container;
 
 /// Minutes per degree for GPS coordinate conversion (DMS to decimal degrees).
 const MINUTES_PER_DEGREE: f64 = 60.0;
@@ -11,6 +16,32 @@ const MINUTES_PER_DEGREE: f64 = 60.0;
 /// Seconds per degree for GPS coordinate conversion (DMS to decimal degrees).
 const SECONDS_PER_DEGREE: f64 = 3600.0;
 
+/// PDF points per inch.
+const POINTS_PER_INCH: f64 = 72.0;
+
+/// Millimeters per inch.
+const MM_PER_INCH: f64 = 25.4;
+
+/// Largest difference in millimeters for a page to count as a paper format.
+const PAPER_TOLERANCE_MM: f64 = 2.0;
+
+/// Named paper formats: name, width and height in millimeters (portrait),
+/// and whether the format is measured in inches.
+const PAPER_FORMATS: &[(&str, f64, f64, bool)] = &[
+    ("A0", 841.0, 1189.0, false),
+    ("A1", 594.0, 841.0, false),
+    ("A2", 420.0, 594.0, false),
+    ("A3", 297.0, 420.0, false),
+    ("A4", 210.0, 297.0, false),
+    ("A5", 148.0, 210.0, false),
+    ("A6", 105.0, 148.0, false),
+    ("B4", 250.0, 353.0, false),
+    ("B5", 176.0, 250.0, false),
+    ("US Letter", 215.9, 279.4, true),
+    ("US Legal", 215.9, 355.6, true),
+    ("Tabloid", 279.4, 431.8, true),
+];
+
 /// Basic document metadata (always available).
 #[derive(Debug, Clone)]
 pub struct BasicMeta {
@@ -28,6 +59,8 @@ pub struct BasicMeta {
     pub file_size: u64,
     /// Color type description (e.g., "RGBA8", "RGB8", "Grayscale").
     pub color_type: String,
+    /// Set if the file extension does not match the content.
+    pub format_mismatch: Option<FormatMismatch>,
 }
 
 impl BasicMeta {
@@ -71,29 +104,26 @@ pub struct ExifMeta {
     pub focal_length: Option<String>,
     pub gps_latitude: Option<f64>,
     pub gps_longitude: Option<f64>,
+    /// EXIF Orientation value (1-8).
+    pub orientation: Option<u16>,
 }
 
 impl ExifMeta {
     /// Parse EXIF data from raw image bytes.
     ///
     /// Extracts camera information, exposure settings, and GPS coordinates
-    /// from JPEG/TIFF EXIF metadata using the kamadak-exif crate.
+    /// from JPEG/TIFF/HEIF/JPEG XL EXIF metadata using the kamadak-exif
+    /// crate.
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        use exif::{In, Reader, Tag};
+        use exif::{In, Tag};
 
-        let cursor = Cursor::new(bytes);
-        let exif_reader = Reader::new();
-        let exif = exif_reader.read_from_container(&mut cursor.clone()).ok()?;
+        let exif = read_exif(bytes)?;
 
         let mut meta = Self::default();
 
         // Camera make and model
-        if let Some(field) = exif.get_field(Tag::Make, In::PRIMARY) {
-            meta.camera_make = Some(field.display_value().to_string().trim().to_string());
-        }
-        if let Some(field) = exif.get_field(Tag::Model, In::PRIMARY) {
-            meta.camera_model = Some(field.display_value().to_string().trim().to_string());
-        }
+        meta.camera_make = Self::ascii_field(&exif, Tag::Make);
+        meta.camera_model = Self::ascii_field(&exif, Tag::Model);
 
         // Date and time
         if let Some(field) = exif.get_field(Tag::DateTime, In::PRIMARY) {
@@ -124,6 +154,15 @@ impl ExifMeta {
             meta.focal_length = Some(field.display_value().to_string());
         }
 
+        // Orientation (only the eight defined values)
+        if let Some(field) = exif.get_field(Tag::Orientation, In::PRIMARY) {
+            meta.orientation = field
+                .value
+                .get_uint(0)
+                .and_then(|value| u16::try_from(value).ok())
+                .filter(|value| (1..=8).contains(value));
+        }
+
         // GPS coordinates
         meta.gps_latitude = Self::parse_gps_coord(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef);
         meta.gps_longitude = Self::parse_gps_coord(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef);
@@ -131,8 +170,25 @@ impl ExifMeta {
         Some(meta)
     }
 
+    /// Text of an ASCII field, without the quotes `display_value` adds.
+    pub(crate) fn ascii_field(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
+        let field = exif.get_field(tag, exif::In::PRIMARY)?;
+        let text = match &field.value {
+            exif::Value::Ascii(strings) => strings
+                .first()
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned())?,
+            _ => field.display_value().to_string(),
+        };
+        let text = text.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
     /// Parse GPS coordinate from EXIF data (converts DMS to decimal degrees).
-    fn parse_gps_coord(exif: &exif::Exif, coord_tag: exif::Tag, ref_tag: exif::Tag) -> Option<f64> {
+    pub(crate) fn parse_gps_coord(
+        exif: &exif::Exif,
+        coord_tag: exif::Tag,
+        ref_tag: exif::Tag,
+    ) -> Option<f64> {
         use exif::{In, Value};
 
         let coord_field = exif.get_field(coord_tag, In::PRIMARY)?;
@@ -189,9 +245,208 @@ impl ExifMeta {
     }
 }
 
+/// Parse the EXIF data of an image file.
+///
+/// JPEG XL containers are unpacked here, since kamadak-exif does not know
+/// them.
+pub(crate) fn read_exif(bytes: &[u8]) -> Option<exif::Exif> {
+    let exif_reader = exif::Reader::new();
+    match container::jxl_exif(bytes) {
+        Some(tiff) => exif_reader.read_raw(tiff.to_vec()).ok(),
+        None => exif_reader
+            .read_from_container(&mut Cursor::new(bytes))
+            .ok(),
+    }
+}
+
+/// Physical size of a PDF page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSize {
+    /// Width in PDF points (1/72 inch).
+    pub width: f64,
+    /// Height in PDF points (1/72 inch).
+    pub height: f64,
+}
+
+impl PageSize {
+    /// Size in millimeters.
+    pub fn mm(self) -> (f64, f64) {
+        let factor = MM_PER_INCH / POINTS_PER_INCH;
+        (self.width * factor, self.height * factor)
+    }
+
+    /// Size in inches.
+    pub fn inches(self) -> (f64, f64) {
+        (self.width / POINTS_PER_INCH, self.height / POINTS_PER_INCH)
+    }
+
+    /// Named paper format in either orientation, and whether it is measured
+    /// in inches.
+    fn paper_format(self) -> Option<(&'static str, bool)> {
+        let (width, height) = self.mm();
+        let (short, long) = (width.min(height), width.max(height));
+        PAPER_FORMATS
+            .iter()
+            .find(|(_, format_width, format_height, _)| {
+                (short - format_width).abs() <= PAPER_TOLERANCE_MM
+                    && (long - format_height).abs() <= PAPER_TOLERANCE_MM
+            })
+            .map(|&(name, _, _, imperial)| (name, imperial))
+    }
+
+    /// Name of the paper format (e.g., "A4", "US Letter").
+    pub fn paper_name(self) -> Option<&'static str> {
+        self.paper_format().map(|(name, _)| name)
+    }
+
+    /// Format as "210 × 297 mm (A4)"; US formats are shown in inches.
+    pub fn display(self) -> String {
+        match self.paper_format() {
+            Some((name, true)) => {
+                let (width, height) = self.inches();
+                format!("{width:.1} × {height:.1} in ({name})")
+            }
+            Some((name, false)) => {
+                let (width, height) = self.mm();
+                format!("{width:.0} × {height:.0} mm ({name})")
+            }
+            None => {
+                let (width, height) = self.mm();
+                format!("{width:.0} × {height:.0} mm")
+            }
+        }
+    }
+}
+
+/// Operations a PDF allows (restricted by its owner password).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdfPermissions {
+    pub print: bool,
+    pub modify: bool,
+    pub copy: bool,
+    pub annotate: bool,
+    pub fill_forms: bool,
+}
+
+impl PdfPermissions {
+    /// Whether nothing is restricted.
+    pub fn all(self) -> bool {
+        self.print && self.modify && self.copy && self.annotate && self.fill_forms
+    }
+}
+
+impl Default for PdfPermissions {
+    fn default() -> Self {
+        Self {
+            print: true,
+            modify: true,
+            copy: true,
+            annotate: true,
+            fill_forms: true,
+        }
+    }
+}
+
+/// PDF document information (optional, for portable documents).
+#[derive(Debug, Clone, Default)]
+pub struct PdfMeta {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    /// Application that created the original document.
+    pub creator: Option<String>,
+    /// Application that converted it to PDF.
+    pub producer: Option<String>,
+    pub creation_date: Option<String>,
+    pub modification_date: Option<String>,
+    /// PDF version (e.g., "PDF-1.7").
+    pub version: Option<String>,
+    /// Whether the document is encrypted.
+    pub encrypted: bool,
+    pub permissions: PdfPermissions,
+    /// Unrotated size of each page.
+    pub page_sizes: Vec<PageSize>,
+}
+
+impl PdfMeta {
+    /// Runs of consecutive pages of the same size (0-based page ranges).
+    pub fn page_size_runs(&self) -> Vec<(Range<usize>, PageSize)> {
+        let mut runs: Vec<(Range<usize>, PageSize)> = Vec::new();
+        for (index, &size) in self.page_sizes.iter().enumerate() {
+            match runs.last_mut() {
+                Some((pages, run_size)) if run_size.display() == size.display() => {
+                    pages.end = index + 1;
+                }
+                _ => runs.push((index..index + 1, size)),
+            }
+        }
+        runs
+    }
+}
+
 /// Complete document metadata container.
 #[derive(Debug, Clone)]
 pub struct DocumentMeta {
     pub basic: BasicMeta,
     pub exif: Option<ExifMeta>,
+    pub pdf: Option<PdfMeta>,
+    /// Every EXIF, XMP and IPTC tag, for browsing.
+    pub tags: Vec<MetaTag>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_size_display() {
+        let a4 = PageSize {
+            width: 595.0,
+            height: 842.0,
+        };
+        assert_eq!(a4.paper_name(), Some("A4"));
+        assert_eq!(a4.display(), "210 × 297 mm (A4)");
+
+        let letter_landscape = PageSize {
+            width: 792.0,
+            height: 612.0,
+        };
+        assert_eq!(letter_landscape.paper_name(), Some("US Letter"));
+        assert_eq!(letter_landscape.display(), "11.0 × 8.5 in (US Letter)");
+
+        let custom = PageSize {
+            width: 500.0,
+            height: 500.0,
+        };
+        assert_eq!(custom.paper_name(), None);
+        assert_eq!(custom.display(), "176 × 176 mm");
+    }
+
+    #[test]
+    fn test_page_size_runs() {
+        let a4 = PageSize {
+            width: 595.0,
+            height: 842.0,
+        };
+        let a4_rounded = PageSize {
+            width: 595.3,
+            height: 841.9,
+        };
+        let a3 = PageSize {
+            width: 842.0,
+            height: 1191.0,
+        };
+        let meta = PdfMeta {
+            page_sizes: vec![a4, a4_rounded, a3, a4],
+            ..PdfMeta::default()
+        };
+
+        let runs = meta.page_size_runs();
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].0, 0..2);
+        assert_eq!(runs[1], (2..3, a3));
+        assert_eq!(runs[2].0, 3..4);
+        assert!(PdfMeta::default().page_size_runs().is_empty());
+    }
 }
@@ -5,8 +5,10 @@
 
 pub mod content;
 pub mod document;
+pub mod format;
 pub mod metadata;
 pub mod page;
+pub mod tags;
 
 // Re-export commonly used types
 pub use content::DocumentContent;
@@ -3,8 +3,15 @@
 //
 // Page abstraction for multi-page documents.
 
+use std::ops::Range;
+
 use cosmic::widget::image::Handle as ImageHandle;
 
+use crate::domain::document::operations::search::TextRect;
+
+/// Number of jumps kept in the link history.
+const HISTORY_LIMIT: usize = 100;
+
 /// Represents a single page in a multi-page document.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -72,3 +79,295 @@ impl Page {
         (self.width, self.height)
     }
 }
+
+/// Entry of a document outline (table of contents / bookmarks).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntry {
+    /// Title shown in the outline.
+    pub title: String,
+    /// Target page (0-based), if the entry points into the document.
+    pub page: Option<usize>,
+    /// Whether the document wants this entry expanded initially.
+    pub open: bool,
+    /// Nested entries.
+    pub children: Vec<OutlineEntry>,
+}
+
+/// Where a link on a page leads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    /// Page of the same document (0-based).
+    Page(usize),
+    /// External resource (web page, mail address).
+    Uri(String),
+}
+
+/// Clickable area on a page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageLink {
+    /// Page index (0-based).
+    pub page: usize,
+    /// Bounds on the unrotated page, in page units.
+    pub rect: TextRect,
+    /// Where the link leads.
+    pub target: LinkTarget,
+}
+
+/// Pages left by following links, for going back and forward.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageHistory {
+    back: Vec<usize>,
+    forward: Vec<usize>,
+}
+
+impl PageHistory {
+    /// Remember `page` before jumping away from it.
+    ///
+    /// A new jump makes the pages ahead unreachable.
+    pub fn push(&mut self, page: usize) {
+        if self.back.len() == HISTORY_LIMIT {
+            self.back.remove(0);
+        }
+        self.back.push(page);
+        self.forward.clear();
+    }
+
+    /// Page to go back to from `current` (None at the start).
+    pub fn back(&mut self, current: usize) -> Option<usize> {
+        let page = self.back.pop()?;
+        self.forward.push(current);
+        Some(page)
+    }
+
+    /// Page to go forward to from `current` (None at the end).
+    pub fn forward(&mut self, current: usize) -> Option<usize> {
+        let page = self.forward.pop()?;
+        self.back.push(current);
+        Some(page)
+    }
+
+    /// Whether there is a page to go back to.
+    #[must_use]
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    /// Whether there is a page to go forward to.
+    #[must_use]
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+/// Arrangement of the pages of a multi-page document on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageLayout {
+    /// Show two pages side by side.
+    pub two_page_spread: bool,
+    /// Show the first page of a spread layout alone, so that the pages of
+    /// printed double spreads end up next to each other.
+    pub cover_alone: bool,
+    /// Read from right to left (manga): the first page of a spread is shown
+    /// on the right.
+    pub right_to_left: bool,
+    /// Show all pages below each other in a scrollable strip (overrides
+    /// the spread).
+    pub continuous: bool,
+}
+
+impl Default for PageLayout {
+    fn default() -> Self {
+        Self {
+            two_page_spread: false,
+            cover_alone: true,
+            right_to_left: false,
+            continuous: false,
+        }
+    }
+}
+
+impl PageLayout {
+    /// Pages shown together with `page`, in reading order.
+    #[must_use]
+    pub fn visible_pages(self, page: usize, page_count: usize) -> Range<usize> {
+        let page = page.min(page_count.saturating_sub(1));
+        if !self.two_page_spread || self.continuous || (self.cover_alone && page == 0) {
+            return page..(page + 1).min(page_count);
+        }
+
+        // With the cover alone spreads start on odd pages: 0 | 1 2 | 3 4 | ...
+        let first = if self.cover_alone {
+            page - (page - 1) % 2
+        } else {
+            page - page % 2
+        };
+        first..(first + 2).min(page_count)
+    }
+
+    /// First page of the view after the one showing `page`.
+    #[must_use]
+    pub fn next_page(self, page: usize, page_count: usize) -> Option<usize> {
+        let next = self.visible_pages(page, page_count).end;
+        (next < page_count).then_some(next)
+    }
+
+    /// First page of the view before the one showing `page`.
+    #[must_use]
+    pub fn prev_page(self, page: usize, page_count: usize) -> Option<usize> {
+        let first = self.visible_pages(page, page_count).start;
+        let prev = first.checked_sub(1)?;
+        Some(self.visible_pages(prev, page_count).start)
+    }
+}
+
+/// Vertical positions of the pages in the continuous view.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageStrip {
+    /// Top edge of each page.
+    tops: Vec<f32>,
+    /// Height of each page.
+    heights: Vec<f32>,
+}
+
+impl PageStrip {
+    /// Stack pages of the given heights with `spacing` between them.
+    #[must_use]
+    pub fn new(heights: impl IntoIterator<Item = f32>, spacing: f32) -> Self {
+        let heights: Vec<f32> = heights.into_iter().collect();
+        let mut tops = Vec::with_capacity(heights.len());
+        let mut top = 0.0;
+        for height in &heights {
+            tops.push(top);
+            top += height + spacing;
+        }
+        Self { tops, heights }
+    }
+
+    /// Top edge of `page` (0 for pages out of range).
+    #[must_use]
+    pub fn top(&self, page: usize) -> f32 {
+        self.tops.get(page).copied().unwrap_or(0.0)
+    }
+
+    /// Pages at least partly inside the area from `top` with `height`.
+    #[must_use]
+    pub fn pages_in_view(&self, top: f32, height: f32) -> Range<usize> {
+        let bottom = top + height;
+        let first = self
+            .tops
+            .iter()
+            .zip(&self.heights)
+            .position(|(page_top, page_height)| page_top + page_height > top)
+            .unwrap_or(self.tops.len());
+        let end = self.tops.partition_point(|&page_top| page_top < bottom);
+        first..end.max(first)
+    }
+
+    /// Page the reader is looking at: the one in the middle of the area,
+    /// or the last one once scrolled to the end.
+    #[must_use]
+    pub fn current_page(&self, top: f32, height: f32) -> usize {
+        let Some(last) = self.tops.len().checked_sub(1) else {
+            return 0;
+        };
+        let end = self.tops[last] + self.heights[last];
+        if top + height >= end {
+            return last;
+        }
+
+        let middle = top + height / 2.0;
+        self.tops
+            .partition_point(|&page_top| page_top <= middle)
+            .saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_layout() {
+        let single = PageLayout::default();
+        assert_eq!(single.visible_pages(3, 6), 3..4);
+        assert_eq!(single.next_page(5, 6), None);
+        assert_eq!(single.prev_page(3, 6), Some(2));
+
+        let spread = PageLayout {
+            two_page_spread: true,
+            ..PageLayout::default()
+        };
+        assert_eq!(spread.visible_pages(0, 6), 0..1);
+        assert_eq!(spread.visible_pages(1, 6), 1..3);
+        assert_eq!(spread.visible_pages(2, 6), 1..3);
+        // A trailing page without a partner stays alone.
+        assert_eq!(spread.visible_pages(5, 6), 5..6);
+
+        assert_eq!(spread.next_page(0, 6), Some(1));
+        assert_eq!(spread.next_page(2, 6), Some(3));
+        assert_eq!(spread.next_page(5, 6), None);
+        assert_eq!(spread.prev_page(4, 6), Some(1));
+        assert_eq!(spread.prev_page(1, 6), Some(0));
+        assert_eq!(spread.prev_page(0, 6), None);
+
+        // Without the cover alone spreads start on even pages.
+        let no_cover = PageLayout {
+            cover_alone: false,
+            ..spread
+        };
+        assert_eq!(no_cover.visible_pages(0, 5), 0..2);
+        assert_eq!(no_cover.visible_pages(3, 5), 2..4);
+        assert_eq!(no_cover.visible_pages(4, 5), 4..5);
+        assert_eq!(no_cover.prev_page(2, 5), Some(0));
+
+        // The continuous view moves page by page.
+        let continuous = PageLayout {
+            continuous: true,
+            ..spread
+        };
+        assert_eq!(continuous.visible_pages(2, 6), 2..3);
+        assert_eq!(continuous.next_page(2, 6), Some(3));
+    }
+
+    #[test]
+    fn test_page_strip() {
+        // Pages of 100, 200 and 100 pixels with 10 pixels in between.
+        let strip = PageStrip::new([100.0, 200.0, 100.0], 10.0);
+        assert_eq!(strip.top(1), 110.0);
+        assert_eq!(strip.top(2), 320.0);
+
+        assert_eq!(strip.pages_in_view(0.0, 50.0), 0..1);
+        assert_eq!(strip.pages_in_view(50.0, 100.0), 0..2);
+        assert_eq!(strip.pages_in_view(105.0, 4.0), 1..1);
+        assert_eq!(strip.pages_in_view(315.0, 500.0), 2..3);
+
+        assert_eq!(strip.current_page(0.0, 100.0), 0);
+        assert_eq!(strip.current_page(100.0, 100.0), 1);
+        assert_eq!(strip.current_page(300.0, 100.0), 2);
+        // Scrolled to the end, even if the middle is on the previous page.
+        assert_eq!(strip.current_page(200.0, 220.0), 2);
+
+        assert_eq!(PageStrip::default().current_page(0.0, 100.0), 0);
+    }
+
+    #[test]
+    fn test_page_history() {
+        let mut history = PageHistory::default();
+        assert!(!history.can_go_back());
+        assert_eq!(history.back(0), None);
+
+        // Jump 0 -> 5 -> 9, then back twice and forward once.
+        history.push(0);
+        history.push(5);
+        assert_eq!(history.back(9), Some(5));
+        assert_eq!(history.back(5), Some(0));
+        assert_eq!(history.back(0), None);
+        assert!(history.can_go_forward());
+        assert_eq!(history.forward(0), Some(5));
+
+        // A new jump drops the pages ahead.
+        history.push(5);
+        assert!(!history.can_go_forward());
+        assert_eq!(history.back(2), Some(5));
+    }
+}
@@ -0,0 +1,463 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/domain/document/core/tags.rs
+//
+// Complete metadata tag listing: EXIF by IFD, XMP properties and IPTC-IIM.
+
+use crate::domain::document::operations::container;
+
+/// Longest tag value kept in characters (binary blobs like maker notes).
+const MAX_VALUE_CHARS: usize = 256;
+
+/// Marker byte that starts every IPTC-IIM dataset.
+pub const IIM_TAG_MARKER: u8 = 0x1C;
+
+/// IPTC-IIM record of the application datasets (caption, keywords, ...).
+pub const IIM_APPLICATION_RECORD: u8 = 2;
+
+/// Names of the IPTC-IIM application record datasets.
+const IIM_DATASETS: &[(u8, &str)] = &[
+    (5, "Object Name"),
+    (7, "Edit Status"),
+    (10, "Urgency"),
+    (15, "Category"),
+    (20, "Supplemental Category"),
+    (25, "Keywords"),
+    (40, "Special Instructions"),
+    (55, "Date Created"),
+    (60, "Time Created"),
+    (62, "Digital Creation Date"),
+    (63, "Digital Creation Time"),
+    (80, "By-line"),
+    (85, "By-line Title"),
+    (90, "City"),
+    (92, "Sub-location"),
+    (95, "Province/State"),
+    (100, "Country Code"),
+    (101, "Country Name"),
+    (103, "Original Transmission Reference"),
+    (105, "Headline"),
+    (110, "Credit"),
+    (115, "Source"),
+    (116, "Copyright Notice"),
+    (118, "Contact"),
+    (120, "Caption/Abstract"),
+    (122, "Writer/Editor"),
+];
+
+/// Where a metadata tag is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagGroup {
+    /// EXIF IFD0 (TIFF tags of the main image).
+    Image,
+    /// EXIF sub-IFD (exposure, lens, dates).
+    Exif,
+    /// EXIF GPS IFD.
+    Gps,
+    /// EXIF interoperability IFD.
+    Interop,
+    /// EXIF IFD1 (embedded thumbnail).
+    Thumbnail,
+    /// XMP packet.
+    Xmp,
+    /// IPTC-IIM application record.
+    Iptc,
+}
+
+impl TagGroup {
+    /// Short display name.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Image => "Image",
+            Self::Exif => "Exif",
+            Self::Gps => "GPS",
+            Self::Interop => "Interop",
+            Self::Thumbnail => "Thumbnail",
+            Self::Xmp => "XMP",
+            Self::Iptc => "IPTC",
+        }
+    }
+}
+
+/// A single metadata tag with its value formatted for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaTag {
+    pub group: TagGroup,
+    /// Tag name (e.g., "DateTimeOriginal", "dc:rights", "Keywords").
+    pub name: String,
+    pub value: String,
+}
+
+impl MetaTag {
+    fn new(group: TagGroup, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let mut value = value.into();
+        if let Some((end, _)) = value.char_indices().nth(MAX_VALUE_CHARS) {
+            value.truncate(end);
+            value.push('…');
+        }
+        Self {
+            group,
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// Whether the group, name or value contains `query` (case-insensitive).
+    #[must_use]
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.group.name().to_lowercase().contains(&query)
+            || self.name.to_lowercase().contains(&query)
+            || self.value.to_lowercase().contains(&query)
+    }
+}
+
+/// Read every EXIF, XMP and IPTC tag of an image file.
+#[must_use]
+pub fn read_tags(bytes: &[u8]) -> Vec<MetaTag> {
+    let mut tags = super::metadata::read_exif(bytes)
+        .map(|exif| exif_tags(&exif))
+        .unwrap_or_default();
+    if let Some(packet) = container::find_xmp(bytes) {
+        tags.extend(xmp_tags(packet));
+    }
+    if let Some(iim) = container::jpeg_iptc(bytes) {
+        tags.extend(iptc_tags(iim));
+    }
+    tags
+}
+
+/// List the EXIF and XMP tags of raw metadata blocks.
+#[must_use]
+pub fn block_tags(blocks: &container::MetadataBlocks) -> Vec<MetaTag> {
+    let mut tags = blocks
+        .exif
+        .as_ref()
+        .and_then(|tiff| exif::Reader::new().read_raw(tiff.clone()).ok())
+        .map(|exif| exif_tags(&exif))
+        .unwrap_or_default();
+    if let Some(packet) = &blocks.xmp {
+        tags.extend(xmp_tags(packet));
+    }
+    tags
+}
+
+/// List all EXIF fields, grouped by IFD.
+#[must_use]
+pub fn exif_tags(exif: &exif::Exif) -> Vec<MetaTag> {
+    let mut tags: Vec<MetaTag> = exif
+        .fields()
+        .map(|field| {
+            let group = if field.ifd_num == exif::In::THUMBNAIL {
+                TagGroup::Thumbnail
+            } else {
+                match field.tag.context() {
+                    exif::Context::Exif => TagGroup::Exif,
+                    exif::Context::Gps => TagGroup::Gps,
+                    exif::Context::Interop => TagGroup::Interop,
+                    _ => TagGroup::Image,
+                }
+            };
+            let value = field.display_value().with_unit(exif).to_string();
+            MetaTag::new(group, field.tag.to_string(), value)
+        })
+        .collect();
+
+    // Stable sort keeps the file order within each group.
+    tags.sort_by_key(|tag| tag.group as u8);
+    tags
+}
+
+/// List the properties of an XMP packet.
+///
+/// Simple properties are read from attributes and elements of
+/// `rdf:Description`; the items of arrays (`rdf:Bag`, `rdf:Seq`, `rdf:Alt`)
+/// are joined into one value. Nested structures are flattened to their
+/// fields.
+#[must_use]
+pub fn xmp_tags(packet: &[u8]) -> Vec<MetaTag> {
+    let xml = String::from_utf8_lossy(packet);
+    let mut properties: Vec<(String, Vec<String>)> = Vec::new();
+    // Open elements with the index of the property they hold text for.
+    let mut open: Vec<(String, Option<usize>)> = Vec::new();
+    let mut rest = xml.as_ref();
+
+    while let Some(start) = rest.find('<') {
+        add_xmp_text(&rest[..start], &mut open, &mut properties);
+        rest = &rest[start..];
+
+        // Skip processing instructions, comments and declarations.
+        let skip_until = if rest.starts_with("<?") {
+            Some("?>")
+        } else if rest.starts_with("<!--") {
+            Some("-->")
+        } else if rest.starts_with("<!") {
+            Some(">")
+        } else {
+            None
+        };
+        if let Some(end_marker) = skip_until {
+            let Some(end) = rest.find(end_marker) else {
+                break;
+            };
+            rest = &rest[end + end_marker.len()..];
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            // Close up to the matching element (tolerates broken nesting).
+            if let Some(index) = open
+                .iter()
+                .rposition(|(open_name, _)| open_name == name.trim())
+            {
+                open.truncate(index);
+            }
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attributes) = tag
+            .split_once(|c: char| c.is_whitespace())
+            .unwrap_or((tag, ""));
+
+        for (attribute, value) in xml_attributes(attributes) {
+            if attribute == "rdf:resource" && !is_rdf_syntax(name) {
+                properties.push((name.to_string(), vec![value]));
+            } else if !attribute.starts_with("xmlns")
+                && !attribute.starts_with("rdf:")
+                && !attribute.starts_with("xml:")
+                && !attribute.starts_with("x:")
+            {
+                properties.push((attribute.to_string(), vec![value]));
+            }
+        }
+
+        if !self_closing {
+            open.push((name.to_string(), None));
+        }
+    }
+
+    properties
+        .into_iter()
+        .map(|(name, values)| MetaTag::new(TagGroup::Xmp, name, values.join(", ")))
+        .collect()
+}
+
+/// Add the text between two tags to the innermost open property.
+fn add_xmp_text(
+    text: &str,
+    open: &mut [(String, Option<usize>)],
+    properties: &mut Vec<(String, Vec<String>)>,
+) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+
+    // Text of array items belongs to the property holding the array.
+    let Some((name, index)) = open.iter_mut().rev().find(|(name, _)| !is_rdf_syntax(name)) else {
+        return;
+    };
+    let index = *index.get_or_insert_with(|| {
+        properties.push((name.clone(), Vec::new()));
+        properties.len() - 1
+    });
+    properties[index].1.push(xml_unescape(text));
+}
+
+/// Whether an element is RDF/XMP structure rather than a property.
+fn is_rdf_syntax(name: &str) -> bool {
+    name.starts_with("rdf:") || name.starts_with("x:")
+}
+
+/// Split `name="value"` pairs (single or double quotes).
+fn xml_attributes(mut attributes: &str) -> Vec<(&str, String)> {
+    let mut pairs = Vec::new();
+    while let Some(eq) = attributes.find('=') {
+        let name = attributes[..eq].trim();
+        let after = attributes[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = after[1..].find(quote) else {
+            break;
+        };
+        pairs.push((name, xml_unescape(&after[1..=end])));
+        attributes = &after[end + 2..];
+    }
+    pairs
+}
+
+/// Replace the predefined XML entities and character references.
+fn xml_unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => out.push(c),
+            None => out.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// List the application record datasets of IPTC-IIM data.
+///
+/// Repeated datasets (keywords, categories) are joined into one value.
+#[must_use]
+pub fn iptc_tags(mut iim: &[u8]) -> Vec<MetaTag> {
+    let mut datasets: Vec<(u8, Vec<String>)> = Vec::new();
+
+    while iim.len() >= 5 && iim[0] == IIM_TAG_MARKER {
+        let record = iim[1];
+        let dataset = iim[2];
+        let size = usize::from(u16::from_be_bytes([iim[3], iim[4]]));
+        // Extended datasets (size above 32767) carry binary data only.
+        if size & 0x8000 != 0 {
+            break;
+        }
+        let Some(data) = iim.get(5..5 + size) else {
+            break;
+        };
+        iim = &iim[5 + size..];
+
+        // Dataset 0 is the record version.
+        if record != IIM_APPLICATION_RECORD || dataset == 0 {
+            continue;
+        }
+        let text = String::from_utf8_lossy(data).trim().to_string();
+        match datasets.iter_mut().find(|(number, _)| *number == dataset) {
+            Some((_, values)) => values.push(text),
+            None => datasets.push((dataset, vec![text])),
+        }
+    }
+
+    datasets
+        .into_iter()
+        .map(|(dataset, values)| {
+            let name = IIM_DATASETS
+                .iter()
+                .find(|(number, _)| *number == dataset)
+                .map_or_else(
+                    || format!("Dataset 2:{dataset}"),
+                    |(_, name)| (*name).to_string(),
+                );
+            MetaTag::new(TagGroup::Iptc, name, values.join(", "))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(group: TagGroup, name: &str, value: &str) -> MetaTag {
+        MetaTag::new(group, name, value)
+    }
+
+    #[test]
+    fn test_xmp_tags() {
+        let packet = br#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmp:Rating="4" xmp:CreatorTool='Noctua &amp; friends'>
+   <dc:subject>
+    <rdf:Bag><rdf:li>owl</rdf:li><rdf:li>night</rdf:li></rdf:Bag>
+   </dc:subject>
+   <dc:rights>
+    <rdf:Alt><rdf:li xml:lang="x-default">&#169; Archive</rdf:li></rdf:Alt>
+   </dc:rights>
+   <xmpRights:WebStatement rdf:resource="https://example.org/rights"/>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+        assert_eq!(
+            xmp_tags(packet),
+            vec![
+                tag(TagGroup::Xmp, "xmp:Rating", "4"),
+                tag(TagGroup::Xmp, "xmp:CreatorTool", "Noctua & friends"),
+                tag(TagGroup::Xmp, "dc:subject", "owl, night"),
+                tag(TagGroup::Xmp, "dc:rights", "© Archive"),
+                tag(
+                    TagGroup::Xmp,
+                    "xmpRights:WebStatement",
+                    "https://example.org/rights"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iptc_tags() {
+        let mut iim = Vec::new();
+        for (record, dataset, value) in [
+            (1u8, 90u8, &b"\x1b%G"[..]),
+            (2, 0, b"\0\x04"),
+            (2, 120, b"An owl at night"),
+            (2, 25, b"owl"),
+            (2, 25, b"night"),
+            (2, 116, "© Archive".as_bytes()),
+            (2, 200, b"custom"),
+        ] {
+            iim.extend_from_slice(&[IIM_TAG_MARKER, record, dataset]);
+            iim.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            iim.extend_from_slice(value);
+        }
+
+        assert_eq!(
+            iptc_tags(&iim),
+            vec![
+                tag(TagGroup::Iptc, "Caption/Abstract", "An owl at night"),
+                tag(TagGroup::Iptc, "Keywords", "owl, night"),
+                tag(TagGroup::Iptc, "Copyright Notice", "© Archive"),
+                tag(TagGroup::Iptc, "Dataset 2:200", "custom"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_meta_tag() {
+        let long = tag(TagGroup::Exif, "MakerNote", &"0".repeat(1000));
+        assert_eq!(long.value.chars().count(), MAX_VALUE_CHARS + 1);
+        assert!(long.value.ends_with('…'));
+
+        let keywords = tag(TagGroup::Iptc, "Keywords", "Owl, Night");
+        assert!(keywords.matches(""));
+        assert!(keywords.matches("night"));
+        assert!(keywords.matches("iptc"));
+        assert!(keywords.matches(" KEYWORDS "));
+        assert!(!keywords.matches("gps"));
+    }
+}
@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/domain/document/operations/color.rs
+//
+// Pixel colors in common notations (hex, HSL, CIE Lab) and the loupe image.
+
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+/// CIE XYZ of the D65 white point (sRGB reference white).
+const D65_WHITE: [f64; 3] = [0.950_47, 1.0, 1.088_83];
+
+/// Linear sRGB to CIE XYZ (D65).
+const SRGB_TO_XYZ: [[f64; 3]; 3] = [
+    [0.412_456_4, 0.357_576_1, 0.180_437_5],
+    [0.212_672_9, 0.715_152_2, 0.072_175_0],
+    [0.019_333_9, 0.119_192_0, 0.950_304_1],
+];
+
+/// Frame colors of the center cell of the loupe, picked for contrast.
+const FRAME_DARK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const FRAME_LIGHT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// An 8-bit sRGB color with alpha.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelColor(pub [u8; 4]);
+
+impl PixelColor {
+    /// Color of the pixel at `(x, y)`, if it lies within the image.
+    ///
+    /// Images with more than 8 bits per channel are reduced to 8 bits.
+    #[must_use]
+    pub fn at(img: &DynamicImage, x: u32, y: u32) -> Option<Self> {
+        img.in_bounds(x, y).then(|| Self(img.get_pixel(x, y).0))
+    }
+
+    /// Hex notation: `#rrggbb`, or `#rrggbbaa` if the pixel is not opaque.
+    #[must_use]
+    pub fn hex(self) -> String {
+        let [r, g, b, a] = self.0;
+        if a == u8::MAX {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+
+    /// Hue (0-360°), saturation and lightness (0-100 %).
+    #[must_use]
+    pub fn hsl(self) -> [f64; 3] {
+        let [r, g, b] = self.unit_rgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        let chroma = max - min;
+        if chroma == 0.0 {
+            return [0.0, 0.0, lightness * 100.0];
+        }
+
+        let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / chroma + 2.0
+        } else {
+            (r - g) / chroma + 4.0
+        } * 60.0;
+        [hue, saturation * 100.0, lightness * 100.0]
+    }
+
+    /// CIE L*a*b* (D65 white point).
+    #[must_use]
+    pub fn lab(self) -> [f64; 3] {
+        let linear = self.unit_rgb().map(srgb_to_linear);
+        let xyz: [f64; 3] = std::array::from_fn(|row| {
+            SRGB_TO_XYZ[row]
+                .iter()
+                .zip(linear)
+                .map(|(weight, value)| weight * value)
+                .sum::<f64>()
+                / D65_WHITE[row]
+        });
+        let [fx, fy, fz] = xyz.map(lab_f);
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+
+    /// RGB channels scaled to 0.0-1.0.
+    fn unit_rgb(self) -> [f64; 3] {
+        let [r, g, b, _] = self.0;
+        [r, g, b].map(|value| f64::from(value) / 255.0)
+    }
+}
+
+/// Remove the sRGB transfer curve.
+fn srgb_to_linear(value: f64) -> f64 {
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// The nonlinear compression of CIE Lab.
+fn lab_f(t: f64) -> f64 {
+    const DELTA: f64 = 6.0 / 29.0;
+    if t > DELTA.powi(3) {
+        t.cbrt()
+    } else {
+        t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+    }
+}
+
+/// Magnified view of the pixels around `(x, y)`.
+///
+/// Shows `2 * radius + 1` pixels in each direction, each drawn as a
+/// `zoom`×`zoom` square. Pixels outside the image stay transparent, and the
+/// center pixel gets a frame that contrasts with its color.
+#[must_use]
+pub fn loupe(img: &DynamicImage, x: u32, y: u32, radius: u32, zoom: u32) -> RgbaImage {
+    let cells = 2 * radius + 1;
+    let mut loupe = RgbaImage::new(cells * zoom, cells * zoom);
+
+    for cell_y in 0..cells {
+        for cell_x in 0..cells {
+            let source = (x + cell_x)
+                .checked_sub(radius)
+                .zip((y + cell_y).checked_sub(radius))
+                .and_then(|(src_x, src_y)| PixelColor::at(img, src_x, src_y));
+            let Some(PixelColor(color)) = source else {
+                continue;
+            };
+            for dy in 0..zoom {
+                for dx in 0..zoom {
+                    loupe.put_pixel(cell_x * zoom + dx, cell_y * zoom + dy, Rgba(color));
+                }
+            }
+        }
+    }
+
+    // Frame the center pixel.
+    let frame = match PixelColor::at(img, x, y) {
+        Some(color) if color.lab()[0] > 50.0 => FRAME_DARK,
+        _ => FRAME_LIGHT,
+    };
+    let start = radius * zoom;
+    let end = start + zoom.saturating_sub(1);
+    for offset in start..=end {
+        for (frame_x, frame_y) in [
+            (offset, start),
+            (offset, end),
+            (start, offset),
+            (end, offset),
+        ] {
+            loupe.put_pixel(frame_x, frame_y, frame);
+        }
+    }
+    loupe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: [f64; 3], expected: [f64; 3]) {
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual - expected).abs() < 0.01,
+                "{actual} is not {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_color_notations() {
+        let red = PixelColor([255, 0, 0, 255]);
+        assert_eq!(red.hex(), "#ff0000");
+        assert_close(red.hsl(), [0.0, 100.0, 50.0]);
+        assert_close(red.lab(), [53.24, 80.09, 67.20]);
+
+        let white = PixelColor([255, 255, 255, 128]);
+        assert_eq!(white.hex(), "#ffffff80");
+        assert_close(white.hsl(), [0.0, 0.0, 100.0]);
+        assert_close(white.lab(), [100.0, 0.0, 0.0]);
+
+        let teal = PixelColor([0, 128, 128, 255]);
+        assert_close(teal.hsl(), [180.0, 100.0, 25.1]);
+    }
+
+    #[test]
+    fn test_loupe() {
+        let mut img = RgbaImage::from_pixel(3, 3, Rgba([255, 255, 255, 255]));
+        img.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        let img = DynamicImage::ImageRgba8(img);
+        assert_eq!(PixelColor::at(&img, 3, 0), None);
+
+        // 3×3 cells of 4×4 pixels around the top-left corner.
+        let loupe = loupe(&img, 0, 0, 1, 4);
+        assert_eq!(loupe.dimensions(), (12, 12));
+        // Outside the image.
+        assert_eq!(loupe.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        // Center cell: red (L* 53) inside a dark frame.
+        assert_eq!(loupe.get_pixel(5, 5).0, [255, 0, 0, 255]);
+        assert_eq!(loupe.get_pixel(4, 4).0, [0, 0, 0, 255]);
+        // Right neighbour.
+        assert_eq!(loupe.get_pixel(9, 5).0, [255, 255, 255, 255]);
+    }
+}
@@ -0,0 +1,1676 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/domain/document/operations/container.rs
+//
+// Low-level image container editing (JPEG segments, PNG chunks, WebP RIFF chunks,
+// TIFF directories, JPEG XL boxes).
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use image::{ImageDecoder, ImageReader};
+
+use crate::domain::document::core::document::DocResult;
+
+/// Signature that prefixes EXIF data inside a JPEG APP1 segment.
+const JPEG_EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+/// Signature that prefixes an XMP packet inside a JPEG APP1 segment.
+const JPEG_XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// Signature that prefixes Photoshop image resources inside a JPEG APP13
+/// segment.
+const JPEG_PHOTOSHOP_HEADER: &[u8] = b"Photoshop 3.0\0";
+
+/// Signatures of the APPn segments needed to show a JPEG: JFIF (APP0), ICC
+/// profile (APP2) and Adobe color transform (APP14).
+const JPEG_JFIF_HEADER: &[u8] = b"JFIF\0";
+const JPEG_ICC_HEADER: &[u8] = b"ICC_PROFILE\0";
+const JPEG_ADOBE_HEADER: &[u8] = b"Adobe";
+
+/// Photoshop image resource ID of IPTC-IIM data.
+const PHOTOSHOP_IPTC_RESOURCE: u16 = 0x0404;
+
+/// Start and end of an XMP packet's root element.
+const XMP_START: &[u8] = b"<x:xmpmeta";
+const XMP_END: &[u8] = b"</x:xmpmeta>";
+
+/// Keyword of the PNG iTXt chunk that carries an XMP packet.
+const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+
+/// PNG file signature.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Signature box of the JPEG XL container format.
+const JXL_SIGNATURE: &[u8] = b"\0\0\0\x0cJXL \r\n\x87\n";
+
+/// TIFF tag number of the Orientation field.
+const TAG_ORIENTATION: u16 = 0x0112;
+
+/// TIFF field types used when writing EXIF fields.
+const TYPE_BYTE: u16 = 1;
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+const TYPE_RATIONAL: u16 = 5;
+
+/// TIFF tag numbers of the pointers to the EXIF and GPS directories.
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+
+/// TIFF tag number of the NewSubfileType field.
+const TAG_NEW_SUBFILE_TYPE: u16 = 0x00FE;
+
+/// Upper bound on the directories followed in a TIFF file.
+const TIFF_MAX_IFDS: usize = 65_536;
+
+/// Upper bound on the values read from a single TIFF directory entry.
+const TIFF_MAX_ENTRY_VALUES: usize = 256;
+
+/// TIFF tag numbers used to find embedded JPEG previews.
+const TAG_COMPRESSION: u16 = 0x0103;
+const TAG_STRIP_OFFSETS: u16 = 0x0111;
+const TAG_STRIP_BYTE_COUNTS: u16 = 0x0117;
+const TAG_SUB_IFDS: u16 = 0x014A;
+const TAG_JPEG_OFFSET: u16 = 0x0201;
+const TAG_JPEG_LENGTH: u16 = 0x0202;
+
+/// TIFF compression values for JPEG (old-style and new-style).
+const COMPRESSION_OLD_JPEG: u32 = 6;
+const COMPRESSION_JPEG: u32 = 7;
+
+// VP8X feature flags.
+const VP8X_FLAG_ICC: u8 = 0x20;
+const VP8X_FLAG_ALPHA: u8 = 0x10;
+const VP8X_FLAG_EXIF: u8 = 0x08;
+const VP8X_FLAG_XMP: u8 = 0x04;
+
+// =============================================================================
+// Metadata Blocks
+// =============================================================================
+
+/// Raw metadata blocks carried by an image file.
+///
+/// The blocks are kept as opaque bytes so they can be copied verbatim
+/// into an exported file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataBlocks {
+    /// EXIF data as a raw TIFF structure (without the `Exif\0\0` prefix).
+    pub exif: Option<Vec<u8>>,
+    /// Embedded ICC color profile.
+    pub icc_profile: Option<Vec<u8>>,
+    /// XMP packet (UTF-8 XML).
+    pub xmp: Option<Vec<u8>>,
+}
+
+impl MetadataBlocks {
+    /// Read the metadata blocks of an image file.
+    ///
+    /// Formats without metadata support simply yield empty blocks.
+    pub fn from_file(path: &Path) -> DocResult<Self> {
+        let mut decoder = ImageReader::open(path)?
+            .with_guessed_format()?
+            .into_decoder()?;
+
+        Ok(Self {
+            exif: decoder.exif_metadata()?,
+            icc_profile: decoder.icc_profile()?,
+            xmp: decoder.xmp_metadata()?,
+        })
+    }
+
+    /// Check whether no metadata block is present.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.exif.is_none() && self.icc_profile.is_none() && self.xmp.is_none()
+    }
+}
+
+/// Find the XMP packet embedded in a file of any format.
+///
+/// XMP is stored as plain XML in every container (JPEG APP1, PNG iTXt,
+/// TIFF tag, WebP chunk, ...), so a search for its root element finds it
+/// without knowing the format.
+#[must_use]
+pub fn find_xmp(data: &[u8]) -> Option<&[u8]> {
+    let start = find_bytes(data, XMP_START)?;
+    let end = start + find_bytes(&data[start..], XMP_END)? + XMP_END.len();
+    Some(&data[start..end])
+}
+
+/// Position of the first occurrence of `needle` in `data`.
+fn find_bytes(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// =============================================================================
+// EXIF
+// =============================================================================
+
+/// Byte order of a TIFF structure.
+#[derive(Clone, Copy)]
+struct ByteOrder {
+    little_endian: bool,
+}
+
+impl ByteOrder {
+    fn u16(self, data: &[u8], pos: usize) -> DocResult<u16> {
+        let bytes: [u8; 2] = data
+            .get(pos..pos + 2)
+            .ok_or_else(|| anyhow::anyhow!("Truncated EXIF data"))?
+            .try_into()?;
+        Ok(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(self, data: &[u8], pos: usize) -> DocResult<u32> {
+        let bytes: [u8; 4] = data
+            .get(pos..pos + 4)
+            .ok_or_else(|| anyhow::anyhow!("Truncated EXIF data"))?
+            .try_into()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn u16_bytes(self, value: u16) -> [u8; 2] {
+        if self.little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+
+    fn u32_bytes(self, value: u32) -> [u8; 4] {
+        if self.little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+}
+
+/// Read the byte order and IFD0 offset from a TIFF header.
+fn tiff_header(tiff: &[u8]) -> DocResult<(ByteOrder, usize)> {
+    let order = match tiff.get(..2) {
+        Some(b"II") => ByteOrder {
+            little_endian: true,
+        },
+        Some(b"MM") => ByteOrder {
+            little_endian: false,
+        },
+        _ => return Err(anyhow::anyhow!("Invalid TIFF byte order")),
+    };
+    if order.u16(tiff, 2)? != 42 {
+        return Err(anyhow::anyhow!("Invalid TIFF magic number"));
+    }
+    Ok((order, order.u32(tiff, 4)? as usize))
+}
+
+/// Build a minimal EXIF structure holding only the Orientation tag.
+#[must_use]
+pub fn exif_with_orientation(orientation: u16) -> Vec<u8> {
+    let mut tiff = b"MM\0\x2a\0\0\0\x08".to_vec();
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    tiff.extend_from_slice(&TAG_ORIENTATION.to_be_bytes());
+    tiff.extend_from_slice(&TYPE_SHORT.to_be_bytes());
+    tiff.extend_from_slice(&1u32.to_be_bytes());
+    tiff.extend_from_slice(&orientation.to_be_bytes());
+    tiff.extend_from_slice(&[0, 0]);
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff
+}
+
+/// Read the Orientation tag (1-8) from raw EXIF data.
+pub fn exif_orientation(tiff: &[u8]) -> DocResult<Option<u16>> {
+    let (order, ifd0) = tiff_header(tiff)?;
+    let count = order.u16(tiff, ifd0)? as usize;
+
+    for i in 0..count {
+        let entry = ifd0 + 2 + i * 12;
+        if order.u16(tiff, entry)? == TAG_ORIENTATION {
+            return Ok(Some(order.u16(tiff, entry + 8)?));
+        }
+    }
+    Ok(None)
+}
+
+/// Set the Orientation tag (1-8) in raw EXIF data.
+///
+/// An existing tag is patched in place. Otherwise IFD0 is copied to the end
+/// of the structure with the new entry added; all other values keep their
+/// offsets, so maker notes and sub-IFDs stay intact.
+pub fn exif_set_orientation(tiff: &[u8], orientation: u16) -> DocResult<Vec<u8>> {
+    let (order, ifd0) = tiff_header(tiff)?;
+    let count = order.u16(tiff, ifd0)? as usize;
+    let entries_end = ifd0 + 2 + count * 12;
+    let next_ifd = order.u32(tiff, entries_end)?;
+
+    let mut out = tiff.to_vec();
+    let mut insert_at = count;
+    for i in 0..count {
+        let entry = ifd0 + 2 + i * 12;
+        let tag = order.u16(tiff, entry)?;
+        if tag == TAG_ORIENTATION {
+            out[entry + 2..entry + 4].copy_from_slice(&order.u16_bytes(TYPE_SHORT));
+            out[entry + 4..entry + 8].copy_from_slice(&order.u32_bytes(1));
+            out[entry + 8..entry + 10].copy_from_slice(&order.u16_bytes(orientation));
+            out[entry + 10..entry + 12].fill(0);
+            return Ok(out);
+        }
+        if tag > TAG_ORIENTATION && insert_at == count {
+            insert_at = i;
+        }
+    }
+
+    // A missing tag means "normal"; nothing to add.
+    if orientation == 1 {
+        return Ok(out);
+    }
+
+    // IFDs start on a word boundary.
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+    let new_ifd = u32::try_from(out.len()).map_err(|_| anyhow::anyhow!("EXIF data too large"))?;
+
+    let mut entry = Vec::with_capacity(12);
+    entry.extend_from_slice(&order.u16_bytes(TAG_ORIENTATION));
+    entry.extend_from_slice(&order.u16_bytes(TYPE_SHORT));
+    entry.extend_from_slice(&order.u32_bytes(1));
+    entry.extend_from_slice(&order.u16_bytes(orientation));
+    entry.extend_from_slice(&[0, 0]);
+
+    // Entries must stay sorted by tag number.
+    let split = ifd0 + 2 + insert_at * 12;
+    out.extend_from_slice(&order.u16_bytes((count + 1) as u16));
+    out.extend_from_slice(&tiff[ifd0 + 2..split]);
+    out.extend_from_slice(&entry);
+    out.extend_from_slice(&tiff[split..entries_end]);
+    out.extend_from_slice(&order.u32_bytes(next_ifd));
+
+    out[4..8].copy_from_slice(&order.u32_bytes(new_ifd));
+    Ok(out)
+}
+
+/// Directory of an EXIF structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExifIfd {
+    /// IFD0, the main image (description, artist, copyright, ...).
+    Primary,
+    /// EXIF sub-IFD (exposure, original date, ...).
+    Exif,
+    /// GPS sub-IFD.
+    Gps,
+}
+
+/// Value of an EXIF field to write.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExifValue {
+    /// Text (NUL-terminated when written).
+    Ascii(String),
+    /// Raw bytes (BYTE).
+    Bytes(Vec<u8>),
+    /// Unsigned fractions (RATIONAL).
+    Rationals(Vec<(u32, u32)>),
+}
+
+impl ExifValue {
+    /// Field type and element count.
+    fn kind(&self) -> (u16, usize) {
+        match self {
+            Self::Ascii(text) => (TYPE_ASCII, text.len() + 1),
+            Self::Bytes(bytes) => (TYPE_BYTE, bytes.len()),
+            Self::Rationals(values) => (TYPE_RATIONAL, values.len()),
+        }
+    }
+
+    /// Encoded value bytes.
+    fn encode(&self, order: ByteOrder) -> Vec<u8> {
+        match self {
+            Self::Ascii(text) => {
+                let mut bytes = text.as_bytes().to_vec();
+                bytes.push(0);
+                bytes
+            }
+            Self::Bytes(bytes) => bytes.clone(),
+            Self::Rationals(values) => values
+                .iter()
+                .flat_map(|&(numerator, denominator)| {
+                    let mut bytes = order.u32_bytes(numerator).to_vec();
+                    bytes.extend_from_slice(&order.u32_bytes(denominator));
+                    bytes
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Change of a single EXIF field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExifFieldEdit {
+    pub ifd: ExifIfd,
+    pub tag: u16,
+    /// New value, or None to remove the field.
+    pub value: Option<ExifValue>,
+}
+
+/// A TIFF directory: raw 12-byte entries and the offset of the next one.
+#[derive(Default)]
+struct Ifd {
+    entries: Vec<[u8; 12]>,
+    next: u32,
+}
+
+impl Ifd {
+    /// Read the directory at `offset`.
+    fn read(order: ByteOrder, tiff: &[u8], offset: usize) -> DocResult<Self> {
+        let count = order.u16(tiff, offset)? as usize;
+        let entries = (0..count)
+            .map(|i| {
+                let entry = offset + 2 + i * 12;
+                tiff.get(entry..entry + 12)
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| anyhow::anyhow!("Truncated EXIF data"))
+            })
+            .collect::<DocResult<Vec<[u8; 12]>>>()?;
+        let next = order.u32(tiff, offset + 2 + count * 12)?;
+        Ok(Self { entries, next })
+    }
+
+    /// Size of the directory in bytes.
+    fn len(&self) -> usize {
+        2 + self.entries.len() * 12 + 4
+    }
+
+    /// Index of the entry for `tag`.
+    fn find(&self, order: ByteOrder, tag: u16) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| order.u16(entry, 0).ok() == Some(tag))
+    }
+
+    /// Value of a LONG entry (sub-IFD pointers).
+    fn long(&self, order: ByteOrder, tag: u16) -> Option<usize> {
+        let entry = &self.entries[self.find(order, tag)?];
+        order.u32(entry, 8).ok().map(|value| value as usize)
+    }
+
+    /// Add or replace an entry, keeping the entries sorted by tag number.
+    fn insert(&mut self, order: ByteOrder, entry: [u8; 12]) {
+        let tag = order.u16(&entry, 0).unwrap_or_default();
+        match self.find(order, tag) {
+            Some(index) => self.entries[index] = entry,
+            None => {
+                let index = self
+                    .entries
+                    .iter()
+                    .position(|other| order.u16(other, 0).unwrap_or_default() > tag)
+                    .unwrap_or(self.entries.len());
+                self.entries.insert(index, entry);
+            }
+        }
+    }
+
+    /// Remove the entry for `tag`, returning it.
+    fn remove(&mut self, order: ByteOrder, tag: u16) -> Option<[u8; 12]> {
+        self.find(order, tag)
+            .map(|index| self.entries.remove(index))
+    }
+
+    /// Append the directory to `out` on a word boundary; returns its offset.
+    fn write(&self, order: ByteOrder, out: &mut Vec<u8>) -> DocResult<u32> {
+        let offset = append_aligned(out, &[])?;
+        let count = u16::try_from(self.entries.len())
+            .map_err(|_| anyhow::anyhow!("Too many EXIF fields"))?;
+        out.extend_from_slice(&order.u16_bytes(count));
+        for entry in &self.entries {
+            out.extend_from_slice(entry);
+        }
+        out.extend_from_slice(&order.u32_bytes(self.next));
+        Ok(offset)
+    }
+}
+
+/// Append `data` to `out` on a word boundary; returns its offset.
+fn append_aligned(out: &mut Vec<u8>, data: &[u8]) -> DocResult<u32> {
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+    let offset = u32::try_from(out.len()).map_err(|_| anyhow::anyhow!("EXIF data too large"))?;
+    out.extend_from_slice(data);
+    Ok(offset)
+}
+
+/// Build a directory entry with its value inline or at `data_offset`.
+fn ifd_entry(order: ByteOrder, tag: u16, kind: u16, count: u32, value: [u8; 4]) -> [u8; 12] {
+    let mut entry = [0; 12];
+    entry[0..2].copy_from_slice(&order.u16_bytes(tag));
+    entry[2..4].copy_from_slice(&order.u16_bytes(kind));
+    entry[4..8].copy_from_slice(&order.u32_bytes(count));
+    entry[8..12].copy_from_slice(&value);
+    entry
+}
+
+/// Overwrite the out-of-line value of an entry with zeros.
+///
+/// Values of four bytes or less are stored in the entry itself.
+fn zero_entry_value(order: ByteOrder, out: &mut [u8], entry: &[u8; 12]) {
+    let size = match order.u16(entry, 2).unwrap_or_default() {
+        1 | 2 | 6 | 7 => 1,
+        3 | 8 => 2,
+        4 | 9 | 11 => 4,
+        5 | 10 | 12 => 8,
+        _ => return,
+    };
+    let Ok(count) = order.u32(entry, 4) else {
+        return;
+    };
+    let Some(len) = (count as usize).checked_mul(size).filter(|&len| len > 4) else {
+        return;
+    };
+    let Ok(offset) = order.u32(entry, 8) else {
+        return;
+    };
+    if let Some(value) = (offset as usize)
+        .checked_add(len)
+        .and_then(|end| out.get_mut(offset as usize..end))
+    {
+        value.fill(0);
+    }
+}
+
+/// Apply field edits to one directory.
+fn edit_ifd(
+    order: ByteOrder,
+    out: &mut Vec<u8>,
+    ifd: &mut Ifd,
+    edits: &[&ExifFieldEdit],
+) -> DocResult<()> {
+    for edit in edits {
+        // Old values must not linger in the file (e.g., a removed location).
+        if let Some(old) = ifd.remove(order, edit.tag) {
+            zero_entry_value(order, out, &old);
+        }
+        let Some(value) = &edit.value else {
+            continue;
+        };
+
+        let (kind, count) = value.kind();
+        let count = u32::try_from(count).map_err(|_| anyhow::anyhow!("EXIF value too large"))?;
+        let bytes = value.encode(order);
+        let mut inline = [0; 4];
+        if bytes.len() <= 4 {
+            inline[..bytes.len()].copy_from_slice(&bytes);
+        } else {
+            inline = order.u32_bytes(append_aligned(out, &bytes)?);
+        }
+        ifd.insert(order, ifd_entry(order, edit.tag, kind, count, inline));
+    }
+    Ok(())
+}
+
+/// Apply field edits to raw EXIF data (TIFF structure).
+///
+/// Edited directories are rewritten at the end of the structure. All other
+/// bytes keep their offsets, so untouched fields, maker notes and the
+/// thumbnail stay byte-exact. The bytes of replaced or removed values and
+/// directories are zeroed. A sub-IFD without fields is dropped; a missing
+/// one is created.
+pub fn exif_edit(tiff: &[u8], edits: &[ExifFieldEdit]) -> DocResult<Vec<u8>> {
+    let (order, ifd0_offset) = tiff_header(tiff)?;
+    let mut ifd0 = Ifd::read(order, tiff, ifd0_offset)?;
+    let mut out = tiff.to_vec();
+    let mut ifd0_changed = false;
+
+    for (sub, pointer) in [(ExifIfd::Exif, TAG_EXIF_IFD), (ExifIfd::Gps, TAG_GPS_IFD)] {
+        let sub_edits: Vec<&ExifFieldEdit> = edits.iter().filter(|edit| edit.ifd == sub).collect();
+        if sub_edits.is_empty() {
+            continue;
+        }
+
+        let old_offset = ifd0.long(order, pointer);
+        let mut ifd = match old_offset {
+            Some(offset) => Ifd::read(order, tiff, offset)?,
+            None => Ifd::default(),
+        };
+        edit_ifd(order, &mut out, &mut ifd, &sub_edits)?;
+
+        if let Some(offset) = old_offset {
+            let old_len = Ifd::read(order, tiff, offset)?.len();
+            out[offset..offset + old_len].fill(0);
+        }
+        if ifd.entries.is_empty() {
+            ifd0.remove(order, pointer);
+        } else {
+            let offset = ifd.write(order, &mut out)?;
+            ifd0.insert(
+                order,
+                ifd_entry(order, pointer, TYPE_LONG, 1, order.u32_bytes(offset)),
+            );
+        }
+        ifd0_changed = true;
+    }
+
+    let primary: Vec<&ExifFieldEdit> = edits
+        .iter()
+        .filter(|edit| edit.ifd == ExifIfd::Primary)
+        .collect();
+    if !primary.is_empty() {
+        edit_ifd(order, &mut out, &mut ifd0, &primary)?;
+        ifd0_changed = true;
+    }
+
+    if ifd0_changed {
+        let old_len = Ifd::read(order, tiff, ifd0_offset)?.len();
+        out[ifd0_offset..ifd0_offset + old_len].fill(0);
+        let offset = ifd0.write(order, &mut out)?;
+        out[4..8].copy_from_slice(&order.u32_bytes(offset));
+    }
+    Ok(out)
+}
+
+/// Tags of the fields in the GPS directory of raw EXIF data.
+pub fn exif_gps_tags(tiff: &[u8]) -> DocResult<Vec<u16>> {
+    let (order, ifd0_offset) = tiff_header(tiff)?;
+    let ifd0 = Ifd::read(order, tiff, ifd0_offset)?;
+    let Some(offset) = ifd0.long(order, TAG_GPS_IFD) else {
+        return Ok(Vec::new());
+    };
+    Ifd::read(order, tiff, offset)?
+        .entries
+        .iter()
+        .map(|entry| order.u16(entry, 0))
+        .collect()
+}
+
+// =============================================================================
+// TIFF
+// =============================================================================
+
+/// List the offsets of the page directories (IFDs) in a TIFF file.
+///
+/// Follows the IFD chain and skips reduced-resolution copies (thumbnails)
+/// of other pages. Only classic TIFF is supported, not BigTIFF.
+pub fn tiff_page_offsets(tiff: &[u8]) -> DocResult<Vec<u32>> {
+    let (order, mut offset) = tiff_header(tiff)?;
+    let mut visited = HashSet::new();
+    let mut pages = Vec::new();
+
+    // Broken files may link directories in a loop.
+    while offset != 0 && visited.len() < TIFF_MAX_IFDS && visited.insert(offset) {
+        let count = order.u16(tiff, offset)? as usize;
+        let mut reduced = false;
+        for i in 0..count {
+            let entry = offset + 2 + i * 12;
+            if order.u16(tiff, entry)? == TAG_NEW_SUBFILE_TYPE {
+                let value = if order.u16(tiff, entry + 2)? == TYPE_SHORT {
+                    u32::from(order.u16(tiff, entry + 8)?)
+                } else {
+                    order.u32(tiff, entry + 8)?
+                };
+                reduced = value & 1 != 0;
+            }
+        }
+
+        if !reduced {
+            pages.push(u32::try_from(offset)?);
+        }
+        offset = order.u32(tiff, offset + 2 + count * 12)? as usize;
+    }
+    Ok(pages)
+}
+
+/// Find the JPEG images embedded in a TIFF-based camera RAW file (DNG, CR2,
+/// NEF, ARW, ...), largest first.
+///
+/// Walks the IFD chain and all SubIFDs and collects JPEG streams referenced
+/// through JPEGInterchangeFormat or stored as a single JPEG strip. Sensor
+/// data stored as lossless JPEG shows up as well; it does not decode as a
+/// regular JPEG and should be skipped by the caller.
+pub fn tiff_jpeg_previews(tiff: &[u8]) -> DocResult<Vec<&[u8]>> {
+    let (order, ifd0) = tiff_header(tiff)?;
+    let mut pending = vec![ifd0];
+    let mut visited = HashSet::new();
+    let mut ranges = Vec::new();
+
+    while let Some(offset) = pending.pop() {
+        // Broken files may link directories in a loop.
+        if offset == 0 || visited.len() >= TIFF_MAX_IFDS || !visited.insert(offset) {
+            continue;
+        }
+
+        let count = order.u16(tiff, offset)? as usize;
+        let (mut compression, mut strips, mut strip_lengths) = (None, Vec::new(), Vec::new());
+        let (mut jpeg_offset, mut jpeg_length) = (None, None);
+        for i in 0..count {
+            let entry = offset + 2 + i * 12;
+            match order.u16(tiff, entry)? {
+                TAG_COMPRESSION => {
+                    compression = tiff_entry_values(order, tiff, entry)?.first().copied()
+                }
+                TAG_STRIP_OFFSETS => strips = tiff_entry_values(order, tiff, entry)?,
+                TAG_STRIP_BYTE_COUNTS => strip_lengths = tiff_entry_values(order, tiff, entry)?,
+                TAG_JPEG_OFFSET => {
+                    jpeg_offset = tiff_entry_values(order, tiff, entry)?.first().copied()
+                }
+                TAG_JPEG_LENGTH => {
+                    jpeg_length = tiff_entry_values(order, tiff, entry)?.first().copied()
+                }
+                TAG_SUB_IFDS => pending.extend(
+                    tiff_entry_values(order, tiff, entry)?
+                        .into_iter()
+                        .map(|sub_ifd| sub_ifd as usize),
+                ),
+                _ => {}
+            }
+        }
+
+        if let (Some(start), Some(length)) = (jpeg_offset, jpeg_length) {
+            ranges.push((start as usize, length as usize));
+        }
+        if matches!(compression, Some(COMPRESSION_OLD_JPEG | COMPRESSION_JPEG))
+            && let ([start], [length]) = (strips.as_slice(), strip_lengths.as_slice())
+        {
+            ranges.push((*start as usize, *length as usize));
+        }
+
+        pending.push(order.u32(tiff, offset + 2 + count * 12)? as usize);
+    }
+
+    ranges.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranges.dedup();
+    Ok(ranges
+        .into_iter()
+        .filter_map(|(start, length)| tiff.get(start..start.checked_add(length)?))
+        .filter(|jpeg| jpeg.starts_with(&[0xFF, 0xD8]))
+        .collect())
+}
+
+/// Read the values of a SHORT, LONG or IFD directory entry.
+fn tiff_entry_values(order: ByteOrder, tiff: &[u8], entry: usize) -> DocResult<Vec<u32>> {
+    let kind = order.u16(tiff, entry + 2)?;
+    let count = (order.u32(tiff, entry + 4)? as usize).min(TIFF_MAX_ENTRY_VALUES);
+    let size = if kind == TYPE_SHORT { 2 } else { 4 };
+
+    // Values that do not fit into the entry are stored elsewhere.
+    let start = if count * size <= 4 {
+        entry + 8
+    } else {
+        order.u32(tiff, entry + 8)? as usize
+    };
+    (0..count)
+        .map(|i| {
+            let pos = start + i * size;
+            if kind == TYPE_SHORT {
+                order.u16(tiff, pos).map(u32::from)
+            } else {
+                order.u32(tiff, pos)
+            }
+        })
+        .collect()
+}
+
+/// Point the TIFF header at the directory at `offset`.
+///
+/// Decoders that only read the first page then decode this one instead.
+pub fn tiff_select_page(tiff: &mut [u8], offset: u32) -> DocResult<()> {
+    let (order, _) = tiff_header(tiff)?;
+    tiff[4..8].copy_from_slice(&order.u32_bytes(offset));
+    Ok(())
+}
+
+// =============================================================================
+// JPEG
+// =============================================================================
+
+/// Set the EXIF Orientation tag of an encoded JPEG without touching the
+/// compressed image data.
+///
+/// Adds an EXIF segment if the file has none.
+pub fn jpeg_set_orientation(data: &mut Vec<u8>, orientation: u16) -> DocResult<()> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(anyhow::anyhow!("Not a JPEG stream"));
+    }
+
+    // Look for an existing EXIF segment among the leading APPn segments.
+    let mut pos = 2;
+    let mut insert_at = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF && (0xE0..=0xEF).contains(&data[pos + 1]) {
+        let seg_len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let body = data
+            .get(pos + 4..pos + 2 + seg_len)
+            .ok_or_else(|| anyhow::anyhow!("Truncated JPEG segment"))?;
+
+        if data[pos + 1] == 0xE1 && body.starts_with(JPEG_EXIF_HEADER) {
+            let tiff = exif_set_orientation(&body[JPEG_EXIF_HEADER.len()..], orientation)?;
+            let segment = jpeg_exif_segment(&tiff)?;
+            data.splice(pos..pos + 2 + seg_len, segment);
+            return Ok(());
+        }
+
+        // EXIF must follow a JFIF header, if present.
+        if data[pos + 1] == 0xE0 && pos == 2 {
+            insert_at = pos + 2 + seg_len;
+        }
+        pos += 2 + seg_len;
+    }
+
+    if orientation != 1 {
+        let segment = jpeg_exif_segment(&exif_with_orientation(orientation))?;
+        data.splice(insert_at..insert_at, segment);
+    }
+    Ok(())
+}
+
+/// Serialize raw EXIF data as a JPEG APP1 segment.
+fn jpeg_exif_segment(tiff: &[u8]) -> DocResult<Vec<u8>> {
+    let length = u16::try_from(2 + JPEG_EXIF_HEADER.len() + tiff.len())
+        .map_err(|_| anyhow::anyhow!("EXIF data too large for a JPEG segment"))?;
+
+    let mut segment = Vec::with_capacity(2 + length as usize);
+    segment.extend_from_slice(&[0xFF, 0xE1]);
+    segment.extend_from_slice(&length.to_be_bytes());
+    segment.extend_from_slice(JPEG_EXIF_HEADER);
+    segment.extend_from_slice(tiff);
+    Ok(segment)
+}
+
+/// Insert an XMP packet as an APP1 segment into an encoded JPEG.
+///
+/// The segment is placed after the existing APPn segments (JFIF, EXIF, ICC).
+pub fn jpeg_insert_xmp(data: &mut Vec<u8>, xmp: &[u8]) -> DocResult<()> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(anyhow::anyhow!("Not a JPEG stream"));
+    }
+
+    let length = 2 + JPEG_XMP_HEADER.len() + xmp.len();
+    let length = u16::try_from(length)
+        .map_err(|_| anyhow::anyhow!("XMP packet too large for a JPEG segment"))?;
+
+    // Skip SOI and all leading APPn segments.
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF && (0xE0..=0xEF).contains(&data[pos + 1]) {
+        let seg_len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        pos += 2 + seg_len;
+    }
+    if pos > data.len() {
+        return Err(anyhow::anyhow!("Truncated JPEG segment"));
+    }
+
+    let mut segment = Vec::with_capacity(2 + length as usize);
+    segment.extend_from_slice(&[0xFF, 0xE1]);
+    segment.extend_from_slice(&length.to_be_bytes());
+    segment.extend_from_slice(JPEG_XMP_HEADER);
+    segment.extend_from_slice(xmp);
+
+    data.splice(pos..pos, segment);
+    Ok(())
+}
+
+/// Read the EXIF and XMP blocks from the APP1 segments of a JPEG.
+///
+/// The ICC profile is left out; it stays in the file when the metadata is
+/// replaced with [`jpeg_replace_metadata`].
+pub fn jpeg_metadata(data: &[u8]) -> DocResult<MetadataBlocks> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(anyhow::anyhow!("Not a JPEG stream"));
+    }
+
+    let mut blocks = MetadataBlocks::default();
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF && (0xE0..=0xEF).contains(&data[pos + 1]) {
+        let seg_len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let body = data
+            .get(pos + 4..pos + 2 + seg_len)
+            .ok_or_else(|| anyhow::anyhow!("Truncated JPEG segment"))?;
+
+        if data[pos + 1] == 0xE1 {
+            if let Some(tiff) = body.strip_prefix(JPEG_EXIF_HEADER) {
+                blocks.exif.get_or_insert_with(|| tiff.to_vec());
+            } else if let Some(xmp) = body.strip_prefix(JPEG_XMP_HEADER) {
+                blocks.xmp.get_or_insert_with(|| xmp.to_vec());
+            }
+        }
+        pos += 2 + seg_len;
+    }
+    Ok(blocks)
+}
+
+/// Replace the EXIF and XMP segments of an encoded JPEG.
+///
+/// All other segments, including the compressed image data, are copied
+/// unchanged. Without `keep_iptc` the Photoshop resources (APP13), which
+/// carry the IPTC data, are removed as well.
+pub fn jpeg_replace_metadata(
+    data: &mut Vec<u8>,
+    blocks: &MetadataBlocks,
+    keep_iptc: bool,
+) -> DocResult<()> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(anyhow::anyhow!("Not a JPEG stream"));
+    }
+
+    let mut pos = 2;
+    let mut insert_at = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF && (0xE0..=0xEF).contains(&data[pos + 1]) {
+        let seg_len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let body = data
+            .get(pos + 4..pos + 2 + seg_len)
+            .ok_or_else(|| anyhow::anyhow!("Truncated JPEG segment"))?;
+
+        let remove = match data[pos + 1] {
+            0xE1 => body.starts_with(JPEG_EXIF_HEADER) || body.starts_with(JPEG_XMP_HEADER),
+            0xED => !keep_iptc && body.starts_with(JPEG_PHOTOSHOP_HEADER),
+            _ => false,
+        };
+        if remove {
+            data.drain(pos..pos + 2 + seg_len);
+            continue;
+        }
+
+        // EXIF must follow a JFIF header, if present.
+        if data[pos + 1] == 0xE0 && pos == 2 {
+            insert_at = pos + 2 + seg_len;
+        }
+        pos += 2 + seg_len;
+    }
+
+    if let Some(exif) = &blocks.exif {
+        let segment = jpeg_exif_segment(exif)?;
+        data.splice(insert_at..insert_at, segment);
+    }
+    if let Some(xmp) = &blocks.xmp {
+        jpeg_insert_xmp(data, xmp)?;
+    }
+    Ok(())
+}
+
+/// Remove all metadata from an encoded JPEG without touching the compressed
+/// image data.
+///
+/// Only the JFIF, ICC profile and Adobe segments stay. All other APPn
+/// segments (EXIF, XMP with its extensions, MPF, Photoshop/IPTC, ...) and
+/// comments are removed, and so is everything after the end of the image,
+/// such as the further images of an MPO file.
+pub fn jpeg_strip_metadata(data: &mut Vec<u8>) -> DocResult<()> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(anyhow::anyhow!("Not a JPEG stream"));
+    }
+    // A marker, as opposed to a stuffed zero byte or a restart marker inside
+    // entropy-coded data.
+    let is_marker = |pos: usize| {
+        data[pos] == 0xFF
+            && data
+                .get(pos + 1)
+                .is_some_and(|&next| next != 0 && !(0xD0..=0xD7).contains(&next))
+    };
+
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&[0xFF, 0xD8]);
+    let mut pos = 2;
+    while pos < data.len() {
+        if !is_marker(pos) {
+            return Err(anyhow::anyhow!("Invalid JPEG marker at {pos}"));
+        }
+        let marker = data[pos + 1];
+        if marker == 0xFF {
+            // Fill byte.
+            pos += 1;
+            continue;
+        }
+        if marker == 0xD9 {
+            out.extend_from_slice(&[0xFF, 0xD9]);
+            break;
+        }
+
+        let seg_len = data
+            .get(pos + 2..pos + 4)
+            .map(|len| usize::from(u16::from_be_bytes([len[0], len[1]])))
+            .ok_or_else(|| anyhow::anyhow!("Truncated JPEG segment"))?;
+        let end = pos + 2 + seg_len;
+        let body = data
+            .get(pos + 4..end)
+            .ok_or_else(|| anyhow::anyhow!("Truncated JPEG segment"))?;
+        let keep = match marker {
+            0xE0 => body.starts_with(JPEG_JFIF_HEADER),
+            0xE2 => body.starts_with(JPEG_ICC_HEADER),
+            0xEE => body.starts_with(JPEG_ADOBE_HEADER),
+            0xE1..=0xEF | 0xFE => false,
+            _ => true,
+        };
+        if keep {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+
+        // Entropy-coded data follows each scan header up to the next marker.
+        if marker == 0xDA {
+            let start = pos;
+            while pos < data.len() && !is_marker(pos) {
+                pos += 1;
+            }
+            out.extend_from_slice(&data[start..pos]);
+        }
+    }
+
+    *data = out;
+    Ok(())
+}
+
+/// Find the IPTC-IIM data in the Photoshop resources (APP13) of a JPEG.
+#[must_use]
+pub fn jpeg_iptc(data: &[u8]) -> Option<&[u8]> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF && (0xE0..=0xEF).contains(&data[pos + 1]) {
+        let seg_len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let body = data.get(pos + 4..pos + 2 + seg_len)?;
+
+        if data[pos + 1] == 0xED
+            && let Some(resources) = body.strip_prefix(JPEG_PHOTOSHOP_HEADER)
+            && let Some(iptc) = photoshop_resource(resources, PHOTOSHOP_IPTC_RESOURCE)
+        {
+            return Some(iptc);
+        }
+        pos += 2 + seg_len;
+    }
+    None
+}
+
+/// Replace the IPTC-IIM data in the Photoshop resources (APP13) of a JPEG.
+///
+/// Files without IPTC data are left unchanged.
+pub fn jpeg_set_iptc(data: &mut Vec<u8>, iim: &[u8]) -> DocResult<()> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(anyhow::anyhow!("Not a JPEG stream"));
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF && (0xE0..=0xEF).contains(&data[pos + 1]) {
+        let seg_len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let body = data
+            .get(pos + 4..pos + 2 + seg_len)
+            .ok_or_else(|| anyhow::anyhow!("Truncated JPEG segment"))?;
+
+        if data[pos + 1] == 0xED
+            && let Some(resources) = body.strip_prefix(JPEG_PHOTOSHOP_HEADER)
+            && let Some(resources) =
+                photoshop_replace_resource(resources, PHOTOSHOP_IPTC_RESOURCE, iim)
+        {
+            let length = u16::try_from(2 + JPEG_PHOTOSHOP_HEADER.len() + resources.len())
+                .map_err(|_| anyhow::anyhow!("IPTC data too large for a JPEG segment"))?;
+            let mut segment = Vec::with_capacity(2 + length as usize);
+            segment.extend_from_slice(&[0xFF, 0xED]);
+            segment.extend_from_slice(&length.to_be_bytes());
+            segment.extend_from_slice(JPEG_PHOTOSHOP_HEADER);
+            segment.extend_from_slice(&resources);
+            data.splice(pos..pos + 2 + seg_len, segment);
+            return Ok(());
+        }
+        pos += 2 + seg_len;
+    }
+    Ok(())
+}
+
+/// Copy Photoshop image resources with the data of resource `id` replaced.
+///
+/// Returns None if there is no such resource.
+fn photoshop_replace_resource(resources: &[u8], id: u16, data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(resources.len() + data.len());
+    let mut rest = resources;
+    let mut found = false;
+    while rest.len() >= 8 && rest.starts_with(b"8BIM") {
+        let resource_id = u16::from_be_bytes([rest[4], rest[5]]);
+
+        // Pascal string name, padded to an even length.
+        let name_len = usize::from(rest[6]);
+        let size_at = 6 + (name_len + 2) / 2 * 2;
+        let size = u32::from_be_bytes(rest.get(size_at..size_at + 4)?.try_into().ok()?) as usize;
+        let end = (size_at + 4).checked_add(size)?;
+        rest.get(..end)?;
+        // The data is padded to an even length as well.
+        let next = (end + size % 2).min(rest.len());
+
+        if resource_id == id && !found {
+            found = true;
+            out.extend_from_slice(&rest[..size_at]);
+            out.extend_from_slice(&u32::try_from(data.len()).ok()?.to_be_bytes());
+            out.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                out.push(0);
+            }
+        } else {
+            out.extend_from_slice(&rest[..next]);
+        }
+        rest = &rest[next..];
+    }
+    out.extend_from_slice(rest);
+    found.then_some(out)
+}
+
+/// Find a Photoshop image resource (`8BIM` block) by its ID.
+fn photoshop_resource(mut resources: &[u8], id: u16) -> Option<&[u8]> {
+    while resources.len() >= 8 && resources.starts_with(b"8BIM") {
+        let resource_id = u16::from_be_bytes([resources[4], resources[5]]);
+
+        // Pascal string name, padded to an even length.
+        let name_len = usize::from(resources[6]);
+        let mut pos = 6 + (name_len + 2) / 2 * 2;
+        let size = u32::from_be_bytes(resources.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        pos += 4;
+
+        let body = resources.get(pos..pos.checked_add(size)?)?;
+        if resource_id == id {
+            return Some(body);
+        }
+        // The data is padded to an even length as well.
+        resources = resources.get(pos + size + size % 2..)?;
+    }
+    None
+}
+
+// =============================================================================
+// PNG
+// =============================================================================
+
+/// Insert an XMP packet as an iTXt chunk into an encoded PNG.
+///
+/// The chunk is placed before the image data, where readers that stop at
+/// the first IDAT still find it.
+pub fn png_insert_xmp(data: &mut Vec<u8>, xmp: &[u8]) -> DocResult<()> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return Err(anyhow::anyhow!("Not a PNG stream"));
+    }
+
+    let idat = find_png_chunk(data, b"IDAT").ok_or_else(|| anyhow::anyhow!("PNG without IDAT"))?;
+
+    // keyword, NUL, compression flag, compression method, language tag NUL,
+    // translated keyword NUL, text.
+    let mut body = Vec::with_capacity(PNG_XMP_KEYWORD.len() + 5 + xmp.len());
+    body.extend_from_slice(PNG_XMP_KEYWORD);
+    body.extend_from_slice(&[0, 0, 0, 0, 0]);
+    body.extend_from_slice(xmp);
+
+    data.splice(idat..idat, png_chunk(b"iTXt", &body)?);
+    Ok(())
+}
+
+/// Find the byte offset of the first chunk of the given type.
+fn find_png_chunk(data: &[u8], kind: &[u8; 4]) -> Option<usize> {
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().ok()?) as usize;
+        if &data[pos + 4..pos + 8] == kind {
+            return Some(pos);
+        }
+        pos += 12 + len;
+    }
+    None
+}
+
+/// Serialize a PNG chunk (length, type, data, CRC).
+fn png_chunk(kind: &[u8; 4], body: &[u8]) -> DocResult<Vec<u8>> {
+    let len = u32::try_from(body.len()).map_err(|_| anyhow::anyhow!("PNG chunk too large"))?;
+
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(kind);
+    hasher.update(body);
+
+    let mut chunk = Vec::with_capacity(12 + body.len());
+    chunk.extend_from_slice(&len.to_be_bytes());
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(body);
+    chunk.extend_from_slice(&hasher.finalize().to_be_bytes());
+    Ok(chunk)
+}
+
+// =============================================================================
+// WebP
+// =============================================================================
+
+/// Rebuild an encoded WebP with the given metadata blocks.
+///
+/// The bitstream chunks (ALPH, VP8, VP8L) are kept as-is and wrapped in an
+/// extended (VP8X) container with ICCP, EXIF and XMP chunks. Any metadata
+/// already present in `data` is replaced.
+pub fn webp_with_metadata(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    blocks: &MetadataBlocks,
+) -> DocResult<Vec<u8>> {
+    if blocks.is_empty() {
+        return Ok(data.to_vec());
+    }
+
+    let chunks = parse_riff_chunks(data)?;
+    let bitstream: Vec<_> = chunks
+        .iter()
+        .filter(|(id, _)| matches!(id, b"ALPH" | b"VP8 " | b"VP8L"))
+        .collect();
+    if bitstream.is_empty() {
+        return Err(anyhow::anyhow!("WebP without image data"));
+    }
+    if width == 0 || height == 0 || width > 1 << 24 || height > 1 << 24 {
+        return Err(anyhow::anyhow!("Invalid WebP canvas size"));
+    }
+
+    let mut flags = 0;
+    if bitstream
+        .iter()
+        .any(|(id, body)| id == b"ALPH" || (id == b"VP8L" && vp8l_has_alpha(body)))
+    {
+        flags |= VP8X_FLAG_ALPHA;
+    }
+    if blocks.icc_profile.is_some() {
+        flags |= VP8X_FLAG_ICC;
+    }
+    if blocks.exif.is_some() {
+        flags |= VP8X_FLAG_EXIF;
+    }
+    if blocks.xmp.is_some() {
+        flags |= VP8X_FLAG_XMP;
+    }
+
+    let mut vp8x = vec![flags, 0, 0, 0];
+    vp8x.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+    vp8x.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+
+    let mut body = b"WEBP".to_vec();
+    write_riff_chunk(&mut body, b"VP8X", &vp8x)?;
+    if let Some(icc) = &blocks.icc_profile {
+        write_riff_chunk(&mut body, b"ICCP", icc)?;
+    }
+    for (id, chunk) in bitstream {
+        write_riff_chunk(&mut body, id, chunk)?;
+    }
+    if let Some(exif) = &blocks.exif {
+        write_riff_chunk(&mut body, b"EXIF", exif)?;
+    }
+    if let Some(xmp) = &blocks.xmp {
+        write_riff_chunk(&mut body, b"XMP ", xmp)?;
+    }
+
+    let size = u32::try_from(body.len()).map_err(|_| anyhow::anyhow!("WebP too large"))?;
+    let mut out = Vec::with_capacity(8 + body.len());
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+/// Split a RIFF/WEBP file into its top-level chunks.
+fn parse_riff_chunks(data: &[u8]) -> DocResult<Vec<([u8; 4], &[u8])>> {
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WEBP" {
+        return Err(anyhow::anyhow!("Not a WebP stream"));
+    }
+
+    let mut chunks = Vec::new();
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id: [u8; 4] = data[pos..pos + 4].try_into()?;
+        let len = u32::from_le_bytes(data[pos + 4..pos + 8].try_into()?) as usize;
+        let start = pos + 8;
+        let end = start
+            .checked_add(len)
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| anyhow::anyhow!("Truncated WebP chunk"))?;
+        chunks.push((id, &data[start..end]));
+        // Chunks are padded to an even size.
+        pos = end + (len & 1);
+    }
+    Ok(chunks)
+}
+
+/// Append a RIFF chunk (with padding byte if needed).
+fn write_riff_chunk(out: &mut Vec<u8>, id: &[u8; 4], body: &[u8]) -> DocResult<()> {
+    let len = u32::try_from(body.len()).map_err(|_| anyhow::anyhow!("WebP chunk too large"))?;
+    out.extend_from_slice(id);
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(body);
+    if body.len() % 2 == 1 {
+        out.push(0);
+    }
+    Ok(())
+}
+
+/// Read the `alpha_is_used` bit of a VP8L bitstream header.
+fn vp8l_has_alpha(body: &[u8]) -> bool {
+    body.len() >= 5 && body[0] == 0x2F && (body[4] & 0x10) != 0
+}
+
+// =============================================================================
+// JPEG XL
+// =============================================================================
+
+/// Find the EXIF data (TIFF structure) in a JPEG XL container.
+///
+/// Returns None for bare codestreams, which cannot carry EXIF, and for
+/// Brotli-compressed (`brob`) EXIF boxes.
+#[must_use]
+pub fn jxl_exif(data: &[u8]) -> Option<&[u8]> {
+    let mut pos = data.starts_with(JXL_SIGNATURE).then_some(0)?;
+
+    while pos + 8 <= data.len() {
+        let size = u32::from_be_bytes(data[pos..pos + 4].try_into().ok()?);
+        let (header_len, box_len) = match size {
+            // The box extends to the end of the file.
+            0 => (8, data.len() - pos),
+            // 64-bit size after the box type.
+            1 => {
+                let size = u64::from_be_bytes(data.get(pos + 8..pos + 16)?.try_into().ok()?);
+                (16, usize::try_from(size).ok()?)
+            }
+            size => (8, size as usize),
+        };
+        if box_len < header_len {
+            return None;
+        }
+
+        if &data[pos + 4..pos + 8] == b"Exif" {
+            let body = data.get(pos + header_len..pos.checked_add(box_len)?)?;
+            // The payload starts with the offset of the TIFF header.
+            let offset = u32::from_be_bytes(body.get(..4)?.try_into().ok()?) as usize;
+            return body.get(4usize.checked_add(offset)?..);
+        }
+        pos = pos.checked_add(box_len)?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{camera_raw, gray_tiff, jpeg, jpeg_segment, mpo};
+    use image::{DynamicImage, GenericImageView, ImageFormat, RgbImage};
+    use std::io::Cursor;
+
+    fn encode(format: ImageFormat) -> Vec<u8> {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(4, 3));
+        let mut buf = Cursor::new(Vec::new());
+        img.write_to(&mut buf, format).unwrap();
+        buf.into_inner()
+    }
+
+    fn decode_xmp(data: &[u8]) -> Option<Vec<u8>> {
+        ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .unwrap()
+            .into_decoder()
+            .unwrap()
+            .xmp_metadata()
+            .unwrap()
+    }
+
+    const XMP: &[u8] = b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>";
+
+    fn decode_orientation(data: &[u8]) -> image::metadata::Orientation {
+        ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .unwrap()
+            .into_decoder()
+            .unwrap()
+            .orientation()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_exif_set_orientation() {
+        // Patch an existing tag in place.
+        let tiff = exif_with_orientation(1);
+        let patched = exif_set_orientation(&tiff, 6).unwrap();
+        assert_eq!(patched.len(), tiff.len());
+        assert_eq!(exif_orientation(&patched).unwrap(), Some(6));
+
+        // Add the tag to an IFD0 without one (little endian, one other tag).
+        let mut tiff = b"II\x2a\0\x08\0\0\0".to_vec();
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend_from_slice(&0x0131u16.to_le_bytes()); // Software
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        tiff.extend_from_slice(&4u32.to_le_bytes());
+        tiff.extend_from_slice(b"abc\0");
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+
+        let added = exif_set_orientation(&tiff, 8).unwrap();
+        assert_eq!(exif_orientation(&added).unwrap(), Some(8));
+        let (order, ifd0) = tiff_header(&added).unwrap();
+        assert_eq!(order.u16(&added, ifd0).unwrap(), 2);
+        assert_eq!(order.u16(&added, ifd0 + 2).unwrap(), TAG_ORIENTATION);
+        assert_eq!(order.u16(&added, ifd0 + 14).unwrap(), 0x0131);
+
+        // Normal orientation does not need a tag.
+        assert_eq!(exif_set_orientation(&tiff, 1).unwrap(), tiff);
+    }
+
+    /// EXIF data with fields in IFD0, the EXIF and the GPS directory.
+    fn sample_exif() -> Vec<u8> {
+        let edits = [
+            ExifFieldEdit {
+                ifd: ExifIfd::Primary,
+                tag: 0x0131, // Software
+                value: Some(ExifValue::Ascii("Noctua Test".into())),
+            },
+            ExifFieldEdit {
+                ifd: ExifIfd::Exif,
+                tag: 0x9003, // DateTimeOriginal
+                value: Some(ExifValue::Ascii("2024:05:01 12:30:00".into())),
+            },
+            ExifFieldEdit {
+                ifd: ExifIfd::Gps,
+                tag: 0x0001, // GPSLatitudeRef
+                value: Some(ExifValue::Ascii("N".into())),
+            },
+            ExifFieldEdit {
+                ifd: ExifIfd::Gps,
+                tag: 0x0002, // GPSLatitude
+                value: Some(ExifValue::Rationals(vec![(52, 1), (31, 1), (1234, 100)])),
+            },
+        ];
+        exif_edit(&exif_with_orientation(6), &edits).unwrap()
+    }
+
+    fn exif_field(tiff: &[u8], tag: exif::Tag) -> Option<String> {
+        let exif = exif::Reader::new().read_raw(tiff.to_vec()).unwrap();
+        exif.get_field(tag, exif::In::PRIMARY)
+            .map(|field| field.display_value().to_string())
+    }
+
+    #[test]
+    fn test_exif_edit() {
+        let tiff = sample_exif();
+        assert_eq!(exif_orientation(&tiff).unwrap(), Some(6));
+        assert_eq!(exif_gps_tags(&tiff).unwrap(), vec![0x0001, 0x0002]);
+        assert_eq!(
+            exif_field(&tiff, exif::Tag::DateTimeOriginal).as_deref(),
+            Some("2024-05-01 12:30:00")
+        );
+
+        // Replace one field; only IFD0 and the old value change.
+        let edited = exif_edit(
+            &tiff,
+            &[ExifFieldEdit {
+                ifd: ExifIfd::Primary,
+                tag: 0x0131,
+                value: Some(ExifValue::Ascii("Other".into())),
+            }],
+        )
+        .unwrap();
+        assert_eq!(
+            exif_field(&edited, exif::Tag::Software).as_deref(),
+            Some("\"Other\"")
+        );
+        assert_eq!(
+            exif_field(&edited, exif::Tag::DateTimeOriginal),
+            exif_field(&tiff, exif::Tag::DateTimeOriginal)
+        );
+        let (order, ifd0) = tiff_header(&tiff).unwrap();
+        let ifd0_end = ifd0 + Ifd::read(order, &tiff, ifd0).unwrap().len();
+        let old_value = find_bytes(&tiff, b"Noctua Test").unwrap();
+        let changed: Vec<usize> = (0..tiff.len()).filter(|&i| tiff[i] != edited[i]).collect();
+        assert!(changed.iter().all(|&i| {
+            i < 8 || (ifd0..ifd0_end).contains(&i) || (old_value..old_value + 12).contains(&i)
+        }));
+        assert!(find_bytes(&edited, b"Noctua Test").is_none());
+
+        // Remove a field again.
+        let removed = exif_edit(
+            &edited,
+            &[ExifFieldEdit {
+                ifd: ExifIfd::Primary,
+                tag: 0x0131,
+                value: None,
+            }],
+        )
+        .unwrap();
+        assert_eq!(exif_field(&removed, exif::Tag::Software), None);
+        assert_eq!(exif_orientation(&removed).unwrap(), Some(6));
+    }
+
+    #[test]
+    fn test_exif_strip_gps() {
+        let tiff = sample_exif();
+        let latitude =
+            ExifValue::Rationals(vec![(52, 1), (31, 1), (1234, 100)]).encode(ByteOrder {
+                little_endian: false,
+            });
+        assert!(find_bytes(&tiff, &latitude).is_some());
+
+        let edits: Vec<ExifFieldEdit> = exif_gps_tags(&tiff)
+            .unwrap()
+            .into_iter()
+            .map(|tag| ExifFieldEdit {
+                ifd: ExifIfd::Gps,
+                tag,
+                value: None,
+            })
+            .collect();
+        let stripped = exif_edit(&tiff, &edits).unwrap();
+
+        assert!(exif_gps_tags(&stripped).unwrap().is_empty());
+        assert!(find_bytes(&stripped, &latitude).is_none());
+        let (order, ifd0) = tiff_header(&stripped).unwrap();
+        let ifd0 = Ifd::read(order, &stripped, ifd0).unwrap();
+        assert_eq!(ifd0.find(order, TAG_GPS_IFD), None);
+        assert_eq!(
+            exif_field(&stripped, exif::Tag::DateTimeOriginal),
+            exif_field(&tiff, exif::Tag::DateTimeOriginal)
+        );
+    }
+
+    #[test]
+    fn test_tiff_pages() {
+        let mut tiff = gray_tiff(&[(4, 3, 10, false), (2, 2, 0, true), (5, 6, 200, false)]);
+        let pages = tiff_page_offsets(&tiff).unwrap();
+        assert_eq!(pages.len(), 2);
+
+        tiff_select_page(&mut tiff, pages[1]).unwrap();
+        let page = ImageReader::with_format(Cursor::new(&tiff), ImageFormat::Tiff)
+            .decode()
+            .unwrap();
+        assert_eq!(page.dimensions(), (5, 6));
+        assert_eq!(page.to_luma8().get_pixel(0, 0).0, [200]);
+
+        // A directory linking back to itself ends the chain.
+        let mut looped = gray_tiff(&[(1, 1, 0, false)]);
+        let end = 8 + 2 + 10 * 12;
+        looped[end..end + 4].copy_from_slice(&8u32.to_le_bytes());
+        assert_eq!(tiff_page_offsets(&looped).unwrap(), vec![8]);
+
+        assert!(tiff_page_offsets(&encode(ImageFormat::Png)).is_err());
+    }
+
+    #[test]
+    fn test_jxl_exif() {
+        let exif = exif_with_orientation(6);
+
+        let mut data = JXL_SIGNATURE.to_vec();
+        data.extend_from_slice(b"\0\0\0\x14ftypjxl \0\0\0\0jxl ");
+        data.extend_from_slice(&(8 + 4 + exif.len() as u32).to_be_bytes());
+        data.extend_from_slice(b"Exif\0\0\0\0");
+        data.extend_from_slice(&exif);
+        data.extend_from_slice(b"\0\0\0\x0ajxlc\xff\x0a");
+
+        assert_eq!(jxl_exif(&data), Some(exif.as_slice()));
+        assert_eq!(
+            crate::domain::document::core::metadata::ExifMeta::from_bytes(&data)
+                .and_then(|meta| meta.orientation),
+            Some(6)
+        );
+
+        // Bare codestreams have no boxes.
+        assert_eq!(jxl_exif(b"\xff\x0a\xfa\x1f"), None);
+        // Truncated box sizes must not loop or panic.
+        assert_eq!(jxl_exif(&data[..JXL_SIGNATURE.len() + 6]), None);
+    }
+
+    #[test]
+    fn test_tiff_jpeg_previews() {
+        let thumbnail = jpeg(4, 2);
+        let preview = jpeg(64, 32);
+        let raw = camera_raw(&thumbnail, &preview, 6);
+
+        let previews = tiff_jpeg_previews(&raw).unwrap();
+        assert_eq!(previews, vec![preview.as_slice(), thumbnail.as_slice()]);
+
+        // Ranges outside the file are dropped instead of failing.
+        let truncated = &raw[..raw.len() - preview.len() / 2];
+        assert_eq!(
+            tiff_jpeg_previews(truncated).unwrap(),
+            vec![thumbnail.as_slice()]
+        );
+    }
+
+    #[test]
+    fn test_jpeg_set_orientation() {
+        use image::metadata::Orientation;
+
+        let original = encode(ImageFormat::Jpeg);
+        let mut data = original.clone();
+
+        jpeg_set_orientation(&mut data, 6).unwrap();
+        assert_eq!(decode_orientation(&data), Orientation::Rotate90);
+        // The segment goes after the JFIF header; everything else is untouched.
+        let jfif_end = 4 + u16::from_be_bytes([original[4], original[5]]) as usize;
+        assert_eq!(data[..jfif_end], original[..jfif_end]);
+        assert!(data.ends_with(&original[jfif_end..]));
+
+        jpeg_set_orientation(&mut data, 7).unwrap();
+        assert_eq!(decode_orientation(&data), Orientation::Rotate270FlipH);
+
+        jpeg_set_orientation(&mut data, 1).unwrap();
+        assert_eq!(decode_orientation(&data), Orientation::NoTransforms);
+    }
+
+    #[test]
+    fn test_jpeg_replace_metadata() {
+        let original = encode(ImageFormat::Jpeg);
+        let mut data = original.clone();
+        let blocks = MetadataBlocks {
+            exif: Some(sample_exif()),
+            icc_profile: None,
+            xmp: Some(XMP.to_vec()),
+        };
+        jpeg_replace_metadata(&mut data, &blocks, true).unwrap();
+        assert_eq!(jpeg_metadata(&data).unwrap(), blocks);
+        assert_eq!(decode_xmp(&data).as_deref(), Some(XMP));
+
+        // Removing all blocks restores the original file.
+        jpeg_replace_metadata(&mut data, &MetadataBlocks::default(), false).unwrap();
+        assert_eq!(data, original);
+    }
+
+    #[test]
+    fn test_jpeg_strip_metadata() {
+        // Comments, EXIF, extended XMP, the MPF index and the second image
+        // of an MPO file all go.
+        let mut data = mpo(16, 16);
+        jpeg_strip_metadata(&mut data).unwrap();
+        assert_eq!(data, jpeg(16, 16));
+
+        // The segments needed to show the image stay.
+        let mut original = jpeg(16, 16);
+        let jfif_end = 4 + u16::from_be_bytes([original[4], original[5]]) as usize;
+        let kept = [
+            jpeg_segment(0xE2, b"ICC_PROFILE\0\x01\x01"),
+            jpeg_segment(0xEE, b"Adobe\0\x64\0\0\0\0\x01"),
+        ]
+        .concat();
+        original.splice(jfif_end..jfif_end, kept);
+        let mut data = original.clone();
+        data.splice(jfif_end..jfif_end, jpeg_segment(0xFE, b"comment"));
+        jpeg_strip_metadata(&mut data).unwrap();
+        assert_eq!(data, original);
+
+        assert!(jpeg_strip_metadata(&mut b"GIF89a".to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_jpeg_xmp_round_trip() {
+        let mut data = encode(ImageFormat::Jpeg);
+        jpeg_insert_xmp(&mut data, XMP).unwrap();
+        assert_eq!(decode_xmp(&data).as_deref(), Some(XMP));
+    }
+
+    #[test]
+    fn test_png_xmp_round_trip() {
+        let mut data = encode(ImageFormat::Png);
+        png_insert_xmp(&mut data, XMP).unwrap();
+        assert_eq!(decode_xmp(&data).as_deref(), Some(XMP));
+    }
+
+    #[test]
+    fn test_webp_metadata_round_trip() {
+        let data = encode(ImageFormat::WebP);
+        let blocks = MetadataBlocks {
+            exif: Some(b"MM\0*\0\0\0\x08\0\0".to_vec()),
+            icc_profile: None,
+            xmp: Some(XMP.to_vec()),
+        };
+        let out = webp_with_metadata(&data, 4, 3, &blocks).unwrap();
+
+        let mut decoder = ImageReader::new(Cursor::new(&out))
+            .with_guessed_format()
+            .unwrap()
+            .into_decoder()
+            .unwrap();
+        assert_eq!(decoder.dimensions(), (4, 3));
+        assert_eq!(decoder.xmp_metadata().unwrap(), blocks.xmp);
+        assert_eq!(decoder.exif_metadata().unwrap(), blocks.exif);
+    }
+
+    #[test]
+    fn test_find_xmp() {
+        let packet: &[u8] = b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF/></x:xmpmeta>";
+        let mut data = encode(ImageFormat::Png);
+        png_insert_xmp(&mut data, packet).unwrap();
+        assert_eq!(find_xmp(&data), Some(packet));
+        assert_eq!(find_xmp(&encode(ImageFormat::Png)), None);
+    }
+
+    #[test]
+    fn test_jpeg_iptc() {
+        let iptc: &[u8] = b"\x1c\x02\x19\x00\x03owl";
+
+        // A resource before the IPTC one, with a name and odd-sized data.
+        let mut resources = Vec::new();
+        resources.extend_from_slice(b"8BIM\x03\xed\x02ab\0\0\0\0\x01x\0");
+        resources.extend_from_slice(b"8BIM\x04\x04\0\0");
+        resources.extend_from_slice(&(iptc.len() as u32).to_be_bytes());
+        resources.extend_from_slice(iptc);
+        resources.push(0);
+
+        let mut segment = vec![0xFF, 0xED];
+        let length = (2 + JPEG_PHOTOSHOP_HEADER.len() + resources.len()) as u16;
+        segment.extend_from_slice(&length.to_be_bytes());
+        segment.extend_from_slice(JPEG_PHOTOSHOP_HEADER);
+        segment.extend_from_slice(&resources);
+
+        let mut data = encode(ImageFormat::Jpeg);
+        assert_eq!(jpeg_iptc(&data), None);
+        data.splice(2..2, segment);
+        assert_eq!(jpeg_iptc(&data), Some(iptc));
+
+        // Replacing the data keeps the other resources.
+        let edited: &[u8] = b"\x1c\x02\x19\x00\x04lynx";
+        jpeg_set_iptc(&mut data, edited).unwrap();
+        assert_eq!(jpeg_iptc(&data), Some(edited));
+        let resources = &data[6 + JPEG_PHOTOSHOP_HEADER.len()..];
+        assert!(resources.starts_with(b"8BIM\x03\xed\x02ab\0\0\0\0\x01x\0"));
+        assert!(
+            ImageReader::new(Cursor::new(&data))
+                .with_guessed_format()
+                .unwrap()
+                .decode()
+                .is_ok()
+        );
+    }
+}
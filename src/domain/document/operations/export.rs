@@ -5,8 +5,12 @@
 
 use std::path::Path;
 
-use image::DynamicImage;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, GenericImageView, ImageEncoder};
 
+use super::container::{self, MetadataBlocks};
 use crate::domain::document::core::document::DocResult;
 
 /// Supported export formats.
@@ -66,11 +70,12 @@ impl ExportFormat {
 
 /// Export options for image formats.
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct ImageExportOptions {
-    /// Quality setting (0-100) for lossy formats.
+    /// Quality setting (1-100) for lossy formats (JPEG, lossy WebP).
     pub quality: u8,
-    /// Whether to preserve metadata (EXIF, etc.).
+    /// Use lossless compression for WebP.
+    pub lossless: bool,
+    /// Whether to preserve metadata (EXIF, ICC profile, XMP).
     pub preserve_metadata: bool,
 }
 
@@ -78,6 +83,7 @@ impl Default for ImageExportOptions {
     fn default() -> Self {
         Self {
             quality: 90,
+            lossless: false,
             preserve_metadata: true,
         }
     }
@@ -85,23 +91,73 @@ impl Default for ImageExportOptions {
 
 /// Export a raster image to a file.
 ///
-/// This function handles format-specific encoding and options.
+/// The metadata blocks are written only if `options.preserve_metadata` is set.
 pub fn export_image(
     img: &DynamicImage,
     path: &Path,
     format: ExportFormat,
-    _options: &ImageExportOptions,
+    options: &ImageExportOptions,
+    metadata: &MetadataBlocks,
 ) -> DocResult<()> {
+    let data = encode_image(img, format, options, metadata)?;
+    std::fs::write(path, data)?;
+    Ok(())
+}
+
+/// Encode a raster image into an in-memory file.
+pub fn encode_image(
+    img: &DynamicImage,
+    format: ExportFormat,
+    options: &ImageExportOptions,
+    metadata: &MetadataBlocks,
+) -> DocResult<Vec<u8>> {
+    let empty = MetadataBlocks::default();
+    let metadata = if options.preserve_metadata {
+        metadata
+    } else {
+        &empty
+    };
+
+    let mut buf = Vec::new();
     match format {
         ExportFormat::Png => {
-            img.save_with_format(path, image::ImageFormat::Png)?;
+            let mut encoder = PngEncoder::new(&mut buf);
+            set_encoder_metadata(&mut encoder, metadata);
+            img.write_with_encoder(encoder)?;
+
+            if let Some(xmp) = &metadata.xmp
+                && let Err(e) = container::png_insert_xmp(&mut buf, xmp)
+            {
+                log::warn!("XMP packet not preserved: {e}");
+            }
         }
         ExportFormat::Jpeg => {
-            // TODO: Apply quality settings
-            img.save_with_format(path, image::ImageFormat::Jpeg)?;
+            let quality = options.quality.clamp(1, 100);
+            let mut encoder = JpegEncoder::new_with_quality(&mut buf, quality);
+            set_encoder_metadata(&mut encoder, metadata);
+            img.write_with_encoder(encoder)?;
+
+            // A JPEG segment holds at most 64 KiB; larger packets are left
+            // out like EXIF or ICC data the encoder rejects.
+            if let Some(xmp) = &metadata.xmp
+                && let Err(e) = container::jpeg_insert_xmp(&mut buf, xmp)
+            {
+                log::warn!("XMP packet not preserved: {e}");
+            }
         }
         ExportFormat::WebP => {
-            img.save_with_format(path, image::ImageFormat::WebP)?;
+            // WebP metadata lives in the RIFF container, so the bitstream is
+            // encoded first and wrapped afterwards.
+            let bitstream = if options.lossless {
+                let mut data = Vec::new();
+                img.write_with_encoder(WebPEncoder::new_lossless(&mut data))?;
+                data
+            } else {
+                encode_webp_lossy(img, options.quality)?
+            };
+
+            let (width, height) = img.dimensions();
+            buf = container::webp_with_metadata(&bitstream, width, height, metadata)?;
         }
         ExportFormat::Pdf | ExportFormat::Svg => {
             return Err(anyhow::anyhow!(
@@ -111,7 +167,35 @@ pub fn export_image(
         }
     }
 
-    Ok(())
+    Ok(buf)
+}
+
+/// Hand EXIF and ICC blocks to an encoder that embeds them natively.
+fn set_encoder_metadata(encoder: &mut impl ImageEncoder, metadata: &MetadataBlocks) {
+    if let Some(icc) = &metadata.icc_profile
+        && let Err(e) = encoder.set_icc_profile(icc.clone())
+    {
+        log::warn!("ICC profile not preserved: {e}");
+    }
+    if let Some(exif) = &metadata.exif
+        && let Err(e) = encoder.set_exif_metadata(exif.clone())
+    {
+        log::warn!("EXIF data not preserved: {e}");
+    }
+}
+
+/// Encode a lossy WebP bitstream via libwebp.
+fn encode_webp_lossy(img: &DynamicImage, quality: u8) -> DocResult<Vec<u8>> {
+    // libwebp only accepts 8-bit RGB(A) input.
+    let img = if img.color().has_alpha() {
+        DynamicImage::ImageRgba8(img.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(img.to_rgb8())
+    };
+
+    let encoder = webp::Encoder::from_image(&img)
+        .map_err(|e| anyhow::anyhow!("WebP encoding failed: {e}"))?;
+    Ok(encoder.encode(f32::from(quality.clamp(1, 100))).to_vec())
 }
 
 /// Export a document to a standard paper format (A4, Letter, etc.).
@@ -132,7 +216,7 @@ pub fn export_to_paper_format(
 
     // Export with default options
     let options = ImageExportOptions::default();
-    export_image(&resized, path, format, &options)
+    export_image(&resized, path, format, &options, &MetadataBlocks::default())
 }
 
 #[cfg(test)]
@@ -158,4 +242,88 @@ mod tests {
         );
         assert_eq!(ExportFormat::from_path(Path::new("test.txt")), None);
     }
+
+    fn gradient() -> DynamicImage {
+        DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 64, |x, y| {
+            image::Rgb([(x * 4) as u8, (y * 4) as u8, ((x + y) * 2) as u8])
+        }))
+    }
+
+    fn with_quality(quality: u8) -> ImageExportOptions {
+        ImageExportOptions {
+            quality,
+            ..ImageExportOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_jpeg_quality() {
+        let img = gradient();
+        let blocks = MetadataBlocks::default();
+        let low = encode_image(&img, ExportFormat::Jpeg, &with_quality(10), &blocks).unwrap();
+        let high = encode_image(&img, ExportFormat::Jpeg, &with_quality(95), &blocks).unwrap();
+        assert!(low.len() < high.len());
+    }
+
+    #[test]
+    fn test_metadata_preserved() {
+        use image::{ImageDecoder, ImageReader};
+        use std::io::Cursor;
+
+        let img = gradient();
+        let blocks = MetadataBlocks {
+            exif: Some(b"MM\0*\0\0\0\x08\0\0".to_vec()),
+            icc_profile: None,
+            xmp: Some(b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>".to_vec()),
+        };
+
+        for format in [ExportFormat::Png, ExportFormat::Jpeg, ExportFormat::WebP] {
+            let data = encode_image(&img, format, &ImageExportOptions::default(), &blocks).unwrap();
+            let mut decoder = ImageReader::new(Cursor::new(data))
+                .with_guessed_format()
+                .unwrap()
+                .into_decoder()
+                .unwrap();
+            assert_eq!(decoder.exif_metadata().unwrap(), blocks.exif, "{format:?}");
+            assert_eq!(decoder.xmp_metadata().unwrap(), blocks.xmp, "{format:?}");
+        }
+
+        let options = ImageExportOptions {
+            preserve_metadata: false,
+            ..ImageExportOptions::default()
+        };
+        let data = encode_image(&img, ExportFormat::Jpeg, &options, &blocks).unwrap();
+        let mut decoder = ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .unwrap()
+            .into_decoder()
+            .unwrap();
+        assert_eq!(decoder.exif_metadata().unwrap(), None);
+    }
+
+    #[test]
+    fn test_oversized_xmp_is_skipped() {
+        use image::{ImageDecoder, ImageReader};
+        use std::io::Cursor;
+
+        // Too large for a JPEG segment, as PNG or WebP sources may carry.
+        let mut xmp = b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">".to_vec();
+        xmp.resize(70_000, b' ');
+        xmp.extend_from_slice(b"</x:xmpmeta>");
+        let blocks = MetadataBlocks {
+            exif: Some(b"MM\0*\0\0\0\x08\0\0".to_vec()),
+            icc_profile: None,
+            xmp: Some(xmp),
+        };
+
+        let options = ImageExportOptions::default();
+        let data = encode_image(&gradient(), ExportFormat::Jpeg, &options, &blocks).unwrap();
+        let mut decoder = ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .unwrap()
+            .into_decoder()
+            .unwrap();
+        assert_eq!(decoder.exif_metadata().unwrap(), blocks.exif);
+        assert_eq!(decoder.xmp_metadata().unwrap(), None);
+    }
 }
@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/domain/document/operations/histogram.rs
+//
+// Luminance and RGB histograms, channel statistics and clipping overlay.
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// Number of bins per channel (one per 8-bit value).
+pub const BINS: usize = 256;
+
+/// Number of distinct 24-bit RGB colors.
+const RGB_COLORS: usize = 1 << 24;
+
+/// Rec. 709 luma weights.
+const LUMA_WEIGHTS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// Colors that mark clipped highlights and shadows in the overlay.
+const HIGHLIGHT_TINT: [u8; 3] = [255, 0, 0];
+const SHADOW_TINT: [u8; 3] = [0, 96, 255];
+
+/// Share of the tint color in marked pixels (0.0-1.0).
+const TINT_STRENGTH: f32 = 0.7;
+
+/// Plot background and luminance color; the RGB channels are drawn on top.
+const PLOT_BACKGROUND: [u8; 4] = [0, 0, 0, 0];
+const PLOT_LUMA: [u8; 4] = [160, 160, 160, 255];
+
+/// A histogram channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Luma,
+    Red,
+    Green,
+    Blue,
+}
+
+impl Channel {
+    /// All channels in display order.
+    pub const ALL: [Self; 4] = [Self::Luma, Self::Red, Self::Green, Self::Blue];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Statistics of a single channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelStats {
+    pub mean: f64,
+    pub median: u8,
+    /// Share of pixels at 0 (0.0-1.0).
+    pub shadows_clipped: f64,
+    /// Share of pixels at 255 (0.0-1.0).
+    pub highlights_clipped: f64,
+}
+
+/// Histograms of an image.
+///
+/// Images with more than 8 bits per channel are reduced to 8 bits first.
+/// Fully transparent pixels are not counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// Counts per channel, indexed by `Channel`.
+    counts: [[u64; BINS]; 4],
+    pixels: u64,
+    unique_colors: usize,
+}
+
+impl Histogram {
+    /// Count the values of every pixel of an image.
+    #[must_use]
+    pub fn from_image(img: &DynamicImage) -> Self {
+        let rgba = img.to_rgba8();
+        let mut counts = [[0u64; BINS]; 4];
+        let mut pixels = 0;
+        // One bit per RGB color.
+        let mut seen = vec![0u64; RGB_COLORS / 64];
+
+        for Rgba([r, g, b, a]) in rgba.pixels().copied() {
+            if a == 0 {
+                continue;
+            }
+            pixels += 1;
+            counts[Channel::Luma.index()][usize::from(luma(r, g, b))] += 1;
+            counts[Channel::Red.index()][usize::from(r)] += 1;
+            counts[Channel::Green.index()][usize::from(g)] += 1;
+            counts[Channel::Blue.index()][usize::from(b)] += 1;
+
+            let color = (usize::from(r) << 16) | (usize::from(g) << 8) | usize::from(b);
+            seen[color / 64] |= 1 << (color % 64);
+        }
+
+        Self {
+            counts,
+            pixels,
+            unique_colors: seen.iter().map(|bits| bits.count_ones() as usize).sum(),
+        }
+    }
+
+    /// Pixel count per value of a channel.
+    #[must_use]
+    pub fn counts(&self, channel: Channel) -> &[u64; BINS] {
+        &self.counts[channel.index()]
+    }
+
+    /// Number of counted pixels.
+    #[must_use]
+    pub fn pixels(&self) -> u64 {
+        self.pixels
+    }
+
+    /// Number of distinct RGB colors.
+    #[must_use]
+    pub fn unique_colors(&self) -> usize {
+        self.unique_colors
+    }
+
+    /// Mean, median and clipping of a channel.
+    #[must_use]
+    pub fn stats(&self, channel: Channel) -> ChannelStats {
+        let counts = self.counts(channel);
+        if self.pixels == 0 {
+            return ChannelStats {
+                mean: 0.0,
+                median: 0,
+                shadows_clipped: 0.0,
+                highlights_clipped: 0.0,
+            };
+        }
+
+        let total = self.pixels as f64;
+        let sum: f64 = counts
+            .iter()
+            .enumerate()
+            .map(|(value, &count)| value as f64 * count as f64)
+            .sum();
+
+        // The median is the first value that covers half of the pixels.
+        let half = self.pixels.div_ceil(2);
+        let mut seen = 0;
+        let median = counts
+            .iter()
+            .position(|&count| {
+                seen += count;
+                seen >= half
+            })
+            .unwrap_or(0);
+
+        ChannelStats {
+            mean: sum / total,
+            median: median as u8,
+            shadows_clipped: counts[0] as f64 / total,
+            highlights_clipped: counts[BINS - 1] as f64 / total,
+        }
+    }
+
+    /// Draw the histogram: luminance in gray behind the color channels,
+    /// which are blended additively (overlaps turn white).
+    ///
+    /// Bars are scaled to the highest bin, ignoring the clipped end bins so
+    /// that a clipped image still shows its tonal range.
+    #[must_use]
+    pub fn plot(&self, width: u32, height: u32) -> RgbaImage {
+        let mut plot = RgbaImage::from_pixel(width, height, Rgba(PLOT_BACKGROUND));
+        if width == 0 || height == 0 {
+            return plot;
+        }
+
+        let peak = self
+            .counts
+            .iter()
+            .flat_map(|counts| counts[1..BINS - 1].iter().copied())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let bar = |channel: Channel, x: u32| {
+            let bin = (x as usize * BINS / width as usize).min(BINS - 1);
+            let count = self.counts(channel)[bin].min(peak);
+            (count as f64 / peak as f64 * f64::from(height)).round() as u32
+        };
+
+        for x in 0..width {
+            let luma = bar(Channel::Luma, x);
+            let rgb = [
+                bar(Channel::Red, x),
+                bar(Channel::Green, x),
+                bar(Channel::Blue, x),
+            ];
+            for y in 0..height {
+                let level = height - y;
+                let mut pixel = if level <= luma {
+                    PLOT_LUMA
+                } else {
+                    PLOT_BACKGROUND
+                };
+                if rgb.iter().any(|&bar| level <= bar) {
+                    for (channel, &bar) in rgb.iter().enumerate() {
+                        pixel[channel] = if level <= bar { 255 } else { 0 };
+                    }
+                    pixel[3] = 255;
+                }
+                plot.put_pixel(x, y, Rgba(pixel));
+            }
+        }
+        plot
+    }
+}
+
+/// Luma of an RGB color (Rec. 709).
+fn luma(r: u8, g: u8, b: u8) -> u8 {
+    let [wr, wg, wb] = LUMA_WEIGHTS;
+    (wr * f32::from(r) + wg * f32::from(g) + wb * f32::from(b))
+        .round()
+        .clamp(0.0, 255.0) as u8
+}
+
+/// Copy of an image with clipped pixels tinted.
+///
+/// Pixels with a channel at 255 are tinted red (blown-out highlights),
+/// pixels that are black in all channels blue (crushed shadows).
+#[must_use]
+pub fn clipping_overlay(img: &DynamicImage) -> RgbaImage {
+    let mut rgba = img.to_rgba8();
+    for Rgba(pixel) in rgba.pixels_mut() {
+        let [r, g, b, _] = *pixel;
+        let tint = if r == 255 || g == 255 || b == 255 {
+            HIGHLIGHT_TINT
+        } else if r == 0 && g == 0 && b == 0 {
+            SHADOW_TINT
+        } else {
+            continue;
+        };
+        for (value, tint) in pixel.iter_mut().zip(tint) {
+            *value = (f32::from(*value) * (1.0 - TINT_STRENGTH) + f32::from(tint) * TINT_STRENGTH)
+                .round() as u8;
+        }
+    }
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2×2 image: black, white, pure red and a transparent pixel.
+    fn sample() -> DynamicImage {
+        let mut img = RgbaImage::new(2, 2);
+        img.put_pixel(0, 0, Rgba([0, 0, 0, 255]));
+        img.put_pixel(1, 0, Rgba([255, 255, 255, 255]));
+        img.put_pixel(0, 1, Rgba([255, 0, 0, 255]));
+        img.put_pixel(1, 1, Rgba([10, 20, 30, 0]));
+        DynamicImage::ImageRgba8(img)
+    }
+
+    #[test]
+    fn test_histogram_stats() {
+        let histogram = Histogram::from_image(&sample());
+        assert_eq!(histogram.pixels(), 3);
+        assert_eq!(histogram.unique_colors(), 3);
+        assert_eq!(histogram.counts(Channel::Red)[255], 2);
+        assert_eq!(histogram.counts(Channel::Luma)[54], 1);
+
+        let red = histogram.stats(Channel::Red);
+        assert_eq!(red.median, 255);
+        assert!((red.mean - 170.0).abs() < 1e-9);
+        assert!((red.highlights_clipped - 2.0 / 3.0).abs() < 1e-9);
+        assert!((red.shadows_clipped - 1.0 / 3.0).abs() < 1e-9);
+
+        let green = histogram.stats(Channel::Green);
+        assert_eq!(green.median, 0);
+
+        let empty = Histogram::from_image(&DynamicImage::new_rgba8(0, 0));
+        assert_eq!(empty.stats(Channel::Luma).median, 0);
+        assert_eq!(empty.plot(16, 8).dimensions(), (16, 8));
+    }
+
+    #[test]
+    fn test_clipping_overlay() {
+        let overlay = clipping_overlay(&sample());
+        // Highlights turn red, shadows blue; other pixels are untouched.
+        assert_eq!(overlay.get_pixel(1, 0).0, [255, 77, 77, 255]);
+        assert_eq!(overlay.get_pixel(0, 0).0, [0, 67, 179, 255]);
+        assert_eq!(overlay.get_pixel(1, 1).0, [10, 20, 30, 0]);
+    }
+}
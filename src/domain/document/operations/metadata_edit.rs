@@ -0,0 +1,880 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/domain/document/operations/metadata_edit.rs
+//
+// Metadata editing: user-editable EXIF fields with their XMP and IPTC copies,
+// GPS stripping and change preview.
+
+use std::collections::BTreeMap;
+
+use super::container::{self, ExifFieldEdit, ExifIfd, ExifValue, MetadataBlocks};
+use crate::domain::document::core::document::DocResult;
+use crate::domain::document::core::metadata::ExifMeta;
+use crate::domain::document::core::tags::{IIM_APPLICATION_RECORD, IIM_TAG_MARKER, MetaTag, TagGroup};
+
+/// EXIF structure without any field, for files that carry no EXIF yet.
+const EMPTY_EXIF: &[u8] = b"MM\0\x2a\0\0\0\x08\0\0\0\0\0\0";
+
+/// EXIF tag numbers of the editable fields.
+const TAG_IMAGE_DESCRIPTION: u16 = 0x010E;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_ARTIST: u16 = 0x013B;
+const TAG_COPYRIGHT: u16 = 0x8298;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_XP_KEYWORDS: u16 = 0x9C9E;
+const TAG_GPS_VERSION_ID: u16 = 0x0000;
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+
+/// GPS IFD version written with new coordinates (2.2.0.0).
+const GPS_VERSION: [u8; 4] = [2, 2, 0, 0];
+
+/// Denominator of the seconds written to GPS coordinates (milliseconds).
+const GPS_SECONDS_DENOMINATOR: u32 = 1000;
+
+/// XMP property prefix of the EXIF GPS properties.
+const XMP_GPS_PREFIX: &str = "exif:GPS";
+
+/// IPTC-IIM application record datasets that mirror editable fields.
+const IIM_KEYWORDS: u8 = 25;
+const IIM_DATE_CREATED: u8 = 55;
+const IIM_TIME_CREATED: u8 = 60;
+const IIM_BY_LINE: u8 = 80;
+const IIM_COPYRIGHT_NOTICE: u8 = 116;
+const IIM_CAPTION: u8 = 120;
+
+/// EXIF tags holding offsets, which change whenever a directory moves.
+const POINTER_TAGS: &[&str] = &["ExifIFDPointer", "GPSInfoIFDPointer", "InteropIFDPointer"];
+
+/// A metadata field that can be edited in the properties panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MetadataField {
+    /// Capture date and time (`YYYY:MM:DD HH:MM:SS`).
+    DateTime,
+    /// Image description or caption.
+    Description,
+    Artist,
+    Copyright,
+    /// Keywords, separated by `;` or `,`.
+    Keywords,
+    /// Location as decimal `latitude, longitude`.
+    Gps,
+}
+
+impl MetadataField {
+    /// All editable fields in display order.
+    pub const ALL: [Self; 6] = [
+        Self::DateTime,
+        Self::Description,
+        Self::Artist,
+        Self::Copyright,
+        Self::Keywords,
+        Self::Gps,
+    ];
+
+    /// Read the current value in its editable form.
+    fn read(self, exif: &exif::Exif) -> Option<String> {
+        use exif::Tag;
+
+        match self {
+            Self::DateTime => ExifMeta::ascii_field(exif, Tag::DateTimeOriginal)
+                .or_else(|| ExifMeta::ascii_field(exif, Tag::DateTime)),
+            Self::Description => ExifMeta::ascii_field(exif, Tag::ImageDescription),
+            Self::Artist => ExifMeta::ascii_field(exif, Tag::Artist),
+            Self::Copyright => ExifMeta::ascii_field(exif, Tag::Copyright),
+            Self::Keywords => {
+                let field = exif.fields().find(|field| {
+                    field.ifd_num == exif::In::PRIMARY && field.tag.number() == TAG_XP_KEYWORDS
+                })?;
+                let exif::Value::Byte(bytes) = &field.value else {
+                    return None;
+                };
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                    .take_while(|&unit| unit != 0)
+                    .collect();
+                let keywords = split_keywords(&String::from_utf16_lossy(&units)).join("; ");
+                (!keywords.is_empty()).then_some(keywords)
+            }
+            Self::Gps => {
+                let lat = ExifMeta::parse_gps_coord(exif, Tag::GPSLatitude, Tag::GPSLatitudeRef)?;
+                let lon = ExifMeta::parse_gps_coord(exif, Tag::GPSLongitude, Tag::GPSLongitudeRef)?;
+                Some(format!("{lat:.6}, {lon:.6}"))
+            }
+        }
+    }
+
+    /// XMP properties holding a copy of the field.
+    fn xmp_properties(self) -> &'static [&'static str] {
+        match self {
+            Self::DateTime => &[
+                "exif:DateTimeOriginal",
+                "xmp:CreateDate",
+                "xmp:ModifyDate",
+                "photoshop:DateCreated",
+            ],
+            Self::Description => &["dc:description"],
+            Self::Artist => &["dc:creator"],
+            Self::Copyright => &["dc:rights"],
+            Self::Keywords => &["dc:subject"],
+            // The XMP location is removed instead (see `MetadataEdit::apply`).
+            Self::Gps => &[],
+        }
+    }
+
+    /// IPTC-IIM datasets (application record) holding a copy of the field.
+    fn iptc_datasets(self) -> &'static [u8] {
+        match self {
+            Self::DateTime => &[IIM_DATE_CREATED, IIM_TIME_CREATED],
+            Self::Description => &[IIM_CAPTION],
+            Self::Artist => &[IIM_BY_LINE],
+            Self::Copyright => &[IIM_COPYRIGHT_NOTICE],
+            Self::Keywords => &[IIM_KEYWORDS],
+            Self::Gps => &[],
+        }
+    }
+
+    /// The values `value` stands for: the EXIF date, each keyword, or the
+    /// text itself. Empty if the field is cleared.
+    fn values(self, value: &str) -> DocResult<Vec<String>> {
+        let value = value.trim();
+        Ok(match self {
+            _ if value.is_empty() => Vec::new(),
+            Self::DateTime => vec![parse_date_time(value)?],
+            Self::Keywords => split_keywords(value)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            _ => vec![value.to_string()],
+        })
+    }
+
+    /// XMP property `name` holding `values`, in the given form (empty if
+    /// there are no values).
+    fn xmp_property(self, name: &str, values: &[String], form: XmpForm) -> String {
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| match self {
+                Self::DateTime => xmp_date(value),
+                _ => xml_escape(value),
+            })
+            .collect();
+        if values.is_empty() {
+            return String::new();
+        }
+        if form == XmpForm::Attribute {
+            return format!("{name}=\"{}\"", values.join("; "));
+        }
+
+        let items = |array: &str, attributes: &str| {
+            let items: String = values
+                .iter()
+                .map(|value| format!("<rdf:li{attributes}>{value}</rdf:li>"))
+                .collect();
+            format!("<{name}><{array}>{items}</{array}></{name}>")
+        };
+        match self {
+            Self::Description | Self::Copyright => items("rdf:Alt", " xml:lang=\"x-default\""),
+            Self::Artist => items("rdf:Seq", ""),
+            Self::Keywords => items("rdf:Bag", ""),
+            _ => format!("<{name}>{}</{name}>", values.join("; ")),
+        }
+    }
+
+    /// Text of IPTC-IIM dataset `dataset` for each of `values`.
+    fn iptc_values(dataset: u8, values: &[String]) -> Vec<String> {
+        match dataset {
+            // `YYYY:MM:DD HH:MM:SS` to `YYYYMMDD` and `HHMMSS`.
+            IIM_DATE_CREATED => values
+                .iter()
+                .map(|date| date[..10].replace(':', ""))
+                .collect(),
+            IIM_TIME_CREATED => values
+                .iter()
+                .map(|date| date[11..].replace(':', ""))
+                .collect(),
+            _ => values.to_vec(),
+        }
+    }
+
+    /// EXIF changes that store `value`; an empty value removes the field.
+    fn exif_edits(self, value: &str) -> DocResult<Vec<ExifFieldEdit>> {
+        let value = value.trim();
+        let edit = |ifd, tag, value| ExifFieldEdit { ifd, tag, value };
+        let text = |tag| {
+            let value = (!value.is_empty()).then(|| ExifValue::Ascii(value.to_string()));
+            vec![edit(ExifIfd::Primary, tag, value)]
+        };
+
+        Ok(match self {
+            Self::DateTime => {
+                let date = if value.is_empty() {
+                    None
+                } else {
+                    Some(ExifValue::Ascii(parse_date_time(value)?))
+                };
+                vec![
+                    edit(ExifIfd::Primary, TAG_DATE_TIME, date.clone()),
+                    edit(ExifIfd::Exif, TAG_DATE_TIME_ORIGINAL, date),
+                ]
+            }
+            Self::Description => text(TAG_IMAGE_DESCRIPTION),
+            Self::Artist => text(TAG_ARTIST),
+            Self::Copyright => text(TAG_COPYRIGHT),
+            Self::Keywords => {
+                let keywords = split_keywords(value).join(";");
+                // XPKeywords is NUL-terminated UTF-16LE stored as bytes.
+                let bytes = (!keywords.is_empty()).then(|| {
+                    ExifValue::Bytes(
+                        keywords
+                            .encode_utf16()
+                            .chain([0])
+                            .flat_map(u16::to_le_bytes)
+                            .collect(),
+                    )
+                });
+                vec![edit(ExifIfd::Primary, TAG_XP_KEYWORDS, bytes)]
+            }
+            // Clearing the location is handled like stripping GPS data.
+            Self::Gps if value.is_empty() => Vec::new(),
+            Self::Gps => {
+                let (lat, lon) = parse_coordinates(value)?;
+                let (lat_ref, lon_ref) = (
+                    if lat < 0.0 { "S" } else { "N" },
+                    if lon < 0.0 { "W" } else { "E" },
+                );
+                vec![
+                    edit(
+                        ExifIfd::Gps,
+                        TAG_GPS_VERSION_ID,
+                        Some(ExifValue::Bytes(GPS_VERSION.to_vec())),
+                    ),
+                    edit(
+                        ExifIfd::Gps,
+                        TAG_GPS_LATITUDE_REF,
+                        Some(ExifValue::Ascii(lat_ref.into())),
+                    ),
+                    edit(ExifIfd::Gps, TAG_GPS_LATITUDE, Some(dms(lat))),
+                    edit(
+                        ExifIfd::Gps,
+                        TAG_GPS_LONGITUDE_REF,
+                        Some(ExifValue::Ascii(lon_ref.into())),
+                    ),
+                    edit(ExifIfd::Gps, TAG_GPS_LONGITUDE, Some(dms(lon))),
+                ]
+            }
+        })
+    }
+}
+
+/// Pending metadata changes for the next save.
+///
+/// Fields are written to EXIF. Copies of them in the XMP packet and the
+/// IPTC data are updated where they exist, so that no stale value is left
+/// behind; everything else is kept byte for byte. `strip_gps` removes the
+/// location from both EXIF and XMP, and so does any location edit from XMP.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataEdit {
+    /// New values of the changed fields (empty = remove the field).
+    pub fields: BTreeMap<MetadataField, String>,
+    /// Remove all GPS data.
+    pub strip_gps: bool,
+}
+
+impl MetadataEdit {
+    /// Whether the edit leaves the metadata unchanged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && !self.strip_gps
+    }
+
+    /// Apply the edit to the metadata blocks of an image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid dates or coordinates and for EXIF data
+    /// that cannot be parsed.
+    pub fn apply(&self, blocks: &mut MetadataBlocks) -> DocResult<()> {
+        let mut edits = Vec::new();
+        for (field, value) in &self.fields {
+            if *field != MetadataField::Gps || !self.strip_gps {
+                edits.extend(field.exif_edits(value)?);
+            }
+        }
+
+        let clear_gps = self.strip_gps
+            || self
+                .fields
+                .get(&MetadataField::Gps)
+                .is_some_and(|value| value.trim().is_empty());
+        if clear_gps && let Some(tiff) = &blocks.exif {
+            edits.extend(
+                container::exif_gps_tags(tiff)?
+                    .into_iter()
+                    .map(|tag| ExifFieldEdit {
+                        ifd: ExifIfd::Gps,
+                        tag,
+                        value: None,
+                    }),
+            );
+        }
+
+        // Removing fields from a file without EXIF is a no-op.
+        if blocks.exif.is_some() || edits.iter().any(|edit| edit.value.is_some()) {
+            let tiff = blocks.exif.as_deref().unwrap_or(EMPTY_EXIF);
+            blocks.exif = Some(container::exif_edit(tiff, &edits)?);
+        }
+
+        if let Some(xmp) = &mut blocks.xmp {
+            for (field, value) in &self.fields {
+                let values = field.values(value)?;
+                for name in field.xmp_properties() {
+                    *xmp = replace_xmp_properties(xmp, name, |found, form| {
+                        (found == *name).then(|| field.xmp_property(name, &values, form))
+                    });
+                }
+            }
+            // A new location is only written to EXIF.
+            if self.strip_gps || self.fields.contains_key(&MetadataField::Gps) {
+                *xmp = strip_xmp_gps(xmp);
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the edit to IPTC-IIM data.
+    ///
+    /// The datasets holding a copy of an edited field are replaced where
+    /// the first of them was; fields the data does not hold are not added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid dates and values too long for a dataset.
+    pub fn apply_iptc(&self, iim: &[u8]) -> DocResult<Vec<u8>> {
+        let mut out = Vec::with_capacity(iim.len());
+        let mut replaced = Vec::new();
+        let mut rest = iim;
+        while rest.len() >= 5 && rest[0] == IIM_TAG_MARKER {
+            let size = usize::from(u16::from_be_bytes([rest[3], rest[4]]));
+            // Extended datasets (size above 32767) carry binary data only.
+            if size & 0x8000 != 0 {
+                break;
+            }
+            let Some(dataset) = rest.get(..5 + size) else {
+                break;
+            };
+            rest = &rest[5 + size..];
+
+            let number = dataset[2];
+            let field = self.fields.iter().find(|(field, _)| {
+                dataset[1] == IIM_APPLICATION_RECORD && field.iptc_datasets().contains(&number)
+            });
+            let Some((field, value)) = field else {
+                out.extend_from_slice(dataset);
+                continue;
+            };
+            if replaced.contains(&number) {
+                continue;
+            }
+            replaced.push(number);
+            for text in MetadataField::iptc_values(number, &field.values(value)?) {
+                let size = u16::try_from(text.len())
+                    .ok()
+                    .filter(|size| size & 0x8000 == 0)
+                    .ok_or_else(|| anyhow::anyhow!("Value too long for IPTC: {text}"))?;
+                out.extend_from_slice(&[IIM_TAG_MARKER, IIM_APPLICATION_RECORD, number]);
+                out.extend_from_slice(&size.to_be_bytes());
+                out.extend_from_slice(text.as_bytes());
+            }
+        }
+        out.extend_from_slice(rest);
+        Ok(out)
+    }
+}
+
+/// Read the editable fields from the metadata blocks of an image.
+#[must_use]
+pub fn read_fields(blocks: &MetadataBlocks) -> BTreeMap<MetadataField, String> {
+    let Some(exif) = blocks
+        .exif
+        .as_ref()
+        .and_then(|tiff| exif::Reader::new().read_raw(tiff.clone()).ok())
+    else {
+        return BTreeMap::new();
+    };
+    MetadataField::ALL
+        .into_iter()
+        .filter_map(|field| Some((field, field.read(&exif)?)))
+        .collect()
+}
+
+/// Change of a single tag between two states of the metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataChange {
+    pub group: TagGroup,
+    pub name: String,
+    /// Value before the change (None = added).
+    pub old: Option<String>,
+    /// Value after the change (None = removed).
+    pub new: Option<String>,
+}
+
+/// Compare two tag listings.
+///
+/// Directory pointers are left out, since their offsets change whenever a
+/// directory is rewritten.
+#[must_use]
+pub fn diff_tags(before: &[MetaTag], after: &[MetaTag]) -> Vec<MetadataChange> {
+    let find = |tags: &[MetaTag], tag: &MetaTag| {
+        tags.iter()
+            .find(|other| other.group == tag.group && other.name == tag.name)
+            .map(|other| other.value.clone())
+    };
+
+    let changed = before.iter().filter_map(|tag| {
+        let new = find(after, tag);
+        (new.as_ref() != Some(&tag.value)).then(|| MetadataChange {
+            group: tag.group,
+            name: tag.name.clone(),
+            old: Some(tag.value.clone()),
+            new,
+        })
+    });
+    let added = after
+        .iter()
+        .filter(|tag| find(before, tag).is_none())
+        .map(|tag| MetadataChange {
+            group: tag.group,
+            name: tag.name.clone(),
+            old: None,
+            new: Some(tag.value.clone()),
+        });
+
+    changed
+        .chain(added)
+        .filter(|change| !POINTER_TAGS.contains(&change.name.as_str()))
+        .collect()
+}
+
+/// Parse a date like `2024-05-01 12:30` into the EXIF form
+/// `2024:05:01 12:30:00`.
+fn parse_date_time(value: &str) -> DocResult<String> {
+    let invalid = || anyhow::anyhow!("Invalid date: {value} (expected YYYY-MM-DD HH:MM:SS)");
+    let numbers: Vec<u32> = value
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| invalid())?;
+
+    let [year, month, day, hour, minute, second] = match numbers[..] {
+        [year, month, day] => [year, month, day, 0, 0, 0],
+        [year, month, day, hour, minute] => [year, month, day, hour, minute, 0],
+        [year, month, day, hour, minute, second] => [year, month, day, hour, minute, second],
+        _ => return Err(invalid()),
+    };
+    if year > 9999 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    if hour > 23 || minute > 59 || second > 59 {
+        return Err(invalid());
+    }
+    Ok(format!(
+        "{year:04}:{month:02}:{day:02} {hour:02}:{minute:02}:{second:02}"
+    ))
+}
+
+/// Parse decimal `latitude, longitude`.
+fn parse_coordinates(value: &str) -> DocResult<(f64, f64)> {
+    let invalid = || anyhow::anyhow!("Invalid location: {value} (expected latitude, longitude)");
+    let (lat, lon) = value.split_once(',').ok_or_else(invalid)?;
+    let lat: f64 = lat.trim().parse().map_err(|_| invalid())?;
+    let lon: f64 = lon.trim().parse().map_err(|_| invalid())?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(invalid());
+    }
+    Ok((lat, lon))
+}
+
+/// Convert decimal degrees to EXIF degrees, minutes and seconds.
+fn dms(degrees: f64) -> ExifValue {
+    // Work in whole milliseconds of arc to avoid 60-second rounding.
+    let millis = (degrees.abs() * 3_600_000.0).round() as u64;
+    let whole_degrees = (millis / 3_600_000) as u32;
+    let minutes = (millis / 60_000 % 60) as u32;
+    let seconds = (millis % 60_000) as u32;
+    ExifValue::Rationals(vec![
+        (whole_degrees, 1),
+        (minutes, 1),
+        (seconds, GPS_SECONDS_DENOMINATOR),
+    ])
+}
+
+/// Split a keyword list at `;` and `,`.
+fn split_keywords(value: &str) -> Vec<&str> {
+    value
+        .split([';', ','])
+        .map(str::trim)
+        .filter(|keyword| !keyword.is_empty())
+        .collect()
+}
+
+/// Remove the EXIF GPS properties from an XMP packet.
+fn strip_xmp_gps(packet: &[u8]) -> Vec<u8> {
+    replace_xmp_properties(packet, XMP_GPS_PREFIX, |_, _| Some(String::new()))
+}
+
+/// How a property is written in an XMP packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum XmpForm {
+    /// `name="value"` in the start tag of its `rdf:Description`.
+    Attribute,
+    /// An element of its own.
+    Element,
+}
+
+/// Replace the XMP properties whose names start with `prefix`.
+///
+/// `replace` gets the name and form of each property and returns its new
+/// text (empty to remove it), or None to keep it. Handles both the attribute
+/// (`exif:GPSLatitude="..."`) and the element form. Names are only matched
+/// in start tags, not in attribute values, element text, comments or CDATA
+/// sections. Packets that are not UTF-8 are returned unchanged.
+fn replace_xmp_properties(
+    packet: &[u8],
+    prefix: &str,
+    replace: impl Fn(&str, XmpForm) -> Option<String>,
+) -> Vec<u8> {
+    let Ok(xml) = std::str::from_utf8(packet) else {
+        return packet.to_vec();
+    };
+
+    // Only ASCII bytes are compared, so every slice starts on a char boundary.
+    let bytes = xml.as_bytes();
+    let mut out = String::with_capacity(xml.len());
+    let mut copied = 0;
+    let mut pos = 0;
+    let mut in_tag = false;
+    let mut quote = None;
+    while pos < bytes.len() {
+        let byte = bytes[pos];
+        if let Some(open) = quote {
+            // Attribute value
+            if byte == open {
+                quote = None;
+            }
+            pos += 1;
+        } else if in_tag {
+            match byte {
+                b'"' | b'\'' => quote = Some(byte),
+                b'>' => in_tag = false,
+                _ if byte.is_ascii_whitespace() && xml[pos + 1..].starts_with(prefix) => {
+                    let start = pos + 1;
+                    if let Some((end, text)) =
+                        replace_property(&xml[start..], XmpForm::Attribute, &replace)
+                    {
+                        out.push_str(xml[copied..start].trim_end());
+                        if !text.is_empty() {
+                            out.push(' ');
+                        }
+                        out.push_str(&text);
+                        pos = start + end;
+                        copied = pos;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            pos += 1;
+        } else if byte == b'<' {
+            let rest = &xml[pos..];
+            let skipped = [("<!--", "-->"), ("<![CDATA[", "]]>")]
+                .into_iter()
+                .find(|(open, _)| rest.starts_with(open));
+            if let Some((_, close)) = skipped {
+                pos += rest.find(close).map_or(rest.len(), |end| end + close.len());
+                continue;
+            }
+            if rest[1..].starts_with(prefix)
+                && let Some((end, text)) = replace_property(&rest[1..], XmpForm::Element, &replace)
+            {
+                out.push_str(&xml[copied..pos]);
+                out.push_str(&text);
+                pos += 1 + end;
+                copied = pos;
+                continue;
+            }
+            in_tag = true;
+            pos += 1;
+        } else {
+            // Element text
+            pos += 1;
+        }
+    }
+    out.push_str(&xml[copied..]);
+    out.into_bytes()
+}
+
+/// Ask `replace` for the new text of the property at the start of `after`
+/// (its name, without the `<` of an element).
+///
+/// Returns the length of the property text and its replacement.
+fn replace_property(
+    after: &str,
+    form: XmpForm,
+    replace: &impl Fn(&str, XmpForm) -> Option<String>,
+) -> Option<(usize, String)> {
+    let name_end = after
+        .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+        .unwrap_or(after.len());
+    let name = &after[..name_end];
+
+    let end = match form {
+        XmpForm::Element => {
+            // Through the end tag (or the self-closing tag).
+            let tag_end = after.find('>').map_or(after.len(), |end| end + 1);
+            if after[..tag_end].ends_with("/>") {
+                tag_end
+            } else {
+                let close = format!("</{name}>");
+                after
+                    .find(&close)
+                    .map_or(after.len(), |end| end + close.len())
+            }
+        }
+        XmpForm::Attribute => {
+            // Through the closing quote of the value.
+            let value =
+                after[name_end..].trim_start_matches(|c: char| c == '=' || c.is_whitespace());
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'');
+            let value_start = after.len() - value.len();
+            quote
+                .and_then(|quote| value[1..].find(quote))
+                .map_or(name_end, |end| value_start + end + 2)
+        }
+    };
+    Some((end, replace(name, form)?))
+}
+
+/// Escape text for XML content and attribute values.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Convert an EXIF date (`YYYY:MM:DD HH:MM:SS`) to XMP (ISO 8601).
+fn xmp_date(date: &str) -> String {
+    format!(
+        "{}-{}-{}T{}",
+        &date[..4],
+        &date[5..7],
+        &date[8..10],
+        &date[11..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::document::core::tags;
+
+    fn edit(fields: &[(MetadataField, &str)]) -> MetadataEdit {
+        MetadataEdit {
+            fields: fields
+                .iter()
+                .map(|(field, value)| (*field, (*value).to_string()))
+                .collect(),
+            strip_gps: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_values() {
+        assert_eq!(
+            parse_date_time("2024-05-01 12:30").unwrap(),
+            "2024:05:01 12:30:00"
+        );
+        assert_eq!(
+            parse_date_time("2024:05:01 12:30:45").unwrap(),
+            "2024:05:01 12:30:45"
+        );
+        assert!(parse_date_time("2024-13-01").is_err());
+        assert!(parse_date_time("yesterday").is_err());
+
+        assert_eq!(
+            parse_coordinates("52.52, -13.405").unwrap(),
+            (52.52, -13.405)
+        );
+        assert!(parse_coordinates("95, 10").is_err());
+        assert_eq!(
+            dms(52.5),
+            ExifValue::Rationals(vec![(52, 1), (30, 1), (0, 1000)])
+        );
+        assert_eq!(
+            split_keywords("owl; night,, forest "),
+            vec!["owl", "night", "forest"]
+        );
+    }
+
+    #[test]
+    fn test_edit_round_trip() {
+        let mut blocks = MetadataBlocks::default();
+        edit(&[
+            (MetadataField::DateTime, "2024-05-01 12:30:00"),
+            (MetadataField::Artist, "Jane Doe"),
+            (MetadataField::Keywords, "owl, night"),
+            (MetadataField::Gps, "52.52, -13.405"),
+        ])
+        .apply(&mut blocks)
+        .unwrap();
+
+        let fields = read_fields(&blocks);
+        assert_eq!(fields[&MetadataField::DateTime], "2024:05:01 12:30:00");
+        assert_eq!(fields[&MetadataField::Artist], "Jane Doe");
+        assert_eq!(fields[&MetadataField::Keywords], "owl; night");
+        assert_eq!(fields[&MetadataField::Gps], "52.520000, -13.405000");
+        assert!(!fields.contains_key(&MetadataField::Copyright));
+
+        // Clearing a field removes it; the others stay.
+        let before = tags::block_tags(&blocks);
+        edit(&[(MetadataField::Artist, "")])
+            .apply(&mut blocks)
+            .unwrap();
+        let fields = read_fields(&blocks);
+        assert!(!fields.contains_key(&MetadataField::Artist));
+        assert_eq!(fields[&MetadataField::DateTime], "2024:05:01 12:30:00");
+
+        let changes = diff_tags(&before, &tags::block_tags(&blocks));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].name, "Artist");
+        assert_eq!(changes[0].new, None);
+    }
+
+    #[test]
+    fn test_edit_updates_copies() {
+        let xmp = br#"<rdf:Description rdf:about="" xmp:CreateDate="2020-01-01T00:00:00"
+    exif:GPSLatitude="52,31.2N">
+   <dc:description><rdf:Alt><rdf:li xml:lang="x-default">Cat</rdf:li></rdf:Alt></dc:description>
+   <dc:creator><rdf:Seq><rdf:li>John Roe</rdf:li></rdf:Seq></dc:creator>
+   <dc:subject><rdf:Bag><rdf:li>cat</rdf:li></rdf:Bag></dc:subject>
+   <dc:title>Owl</dc:title>
+  </rdf:Description>"#;
+        let mut blocks = MetadataBlocks {
+            xmp: Some(xmp.to_vec()),
+            ..MetadataBlocks::default()
+        };
+        let changes = edit(&[
+            (MetadataField::DateTime, "2024-05-01 12:30"),
+            (MetadataField::Description, "Owl & moon"),
+            (MetadataField::Artist, ""),
+            (MetadataField::Copyright, "Jane Doe"),
+            (MetadataField::Keywords, "owl; night"),
+            (MetadataField::Gps, "48.85, 2.35"),
+        ]);
+        changes.apply(&mut blocks).unwrap();
+
+        // Copies are updated or removed; none are added, and the location
+        // is only kept in EXIF.
+        let xmp_tags: Vec<(String, String)> = tags::xmp_tags(blocks.xmp.as_deref().unwrap())
+            .into_iter()
+            .map(|tag| (tag.name, tag.value))
+            .collect();
+        let expected = [
+            ("xmp:CreateDate", "2024-05-01T12:30:00"),
+            ("dc:description", "Owl & moon"),
+            ("dc:subject", "owl, night"),
+            ("dc:title", "Owl"),
+        ];
+        assert_eq!(
+            xmp_tags,
+            expected.map(|(name, value)| (name.to_string(), value.to_string()))
+        );
+
+        let mut iim = Vec::new();
+        for (dataset, value) in [
+            (10, "5"),
+            (25, "cat"),
+            (25, "pet"),
+            (55, "20200101"),
+            (80, "John Roe"),
+            (120, "Cat"),
+        ] {
+            iim.extend_from_slice(&[IIM_TAG_MARKER, IIM_APPLICATION_RECORD, dataset, 0]);
+            iim.push(value.len() as u8);
+            iim.extend_from_slice(value.as_bytes());
+        }
+        let iptc: Vec<(String, String)> = tags::iptc_tags(&changes.apply_iptc(&iim).unwrap())
+            .into_iter()
+            .map(|tag| (tag.name, tag.value))
+            .collect();
+        let expected = [
+            ("Urgency", "5"),
+            ("Keywords", "owl, night"),
+            ("Date Created", "20240501"),
+            ("Caption/Abstract", "Owl & moon"),
+        ];
+        assert_eq!(
+            iptc,
+            expected.map(|(name, value)| (name.to_string(), value.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_strip_gps() {
+        let xmp = br#"<rdf:Description rdf:about="" exif:GPSLatitude="52,31.2N"
+    xmp:Rating="3" exif:GPSLongitude='13,24.3E'>
+   <exif:GPSVersionID>2.2.0.0</exif:GPSVersionID>
+   <exif:GPSAltitude/>
+   <dc:title>Owl</dc:title>
+  </rdf:Description>"#;
+        let mut blocks = MetadataBlocks {
+            xmp: Some(xmp.to_vec()),
+            ..MetadataBlocks::default()
+        };
+        edit(&[(MetadataField::Gps, "52.52, 13.405")])
+            .apply(&mut blocks)
+            .unwrap();
+        assert!(read_fields(&blocks).contains_key(&MetadataField::Gps));
+
+        let strip = MetadataEdit {
+            strip_gps: true,
+            ..MetadataEdit::default()
+        };
+        strip.apply(&mut blocks).unwrap();
+        assert!(!read_fields(&blocks).contains_key(&MetadataField::Gps));
+        assert!(
+            tags::block_tags(&blocks)
+                .iter()
+                .all(|tag| tag.group != TagGroup::Gps && !tag.name.contains("GPS"))
+        );
+        let names: Vec<String> = tags::xmp_tags(blocks.xmp.as_deref().unwrap())
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        assert_eq!(names, vec!["xmp:Rating", "dc:title"]);
+    }
+
+    #[test]
+    fn test_strip_gps_keeps_names_in_text() {
+        let xmp = br#"<rdf:Description rdf:about="" exif:GPSLatitude="52,31.2N"
+    dc:source="see exif:GPSLongitude">
+   <!-- <exif:GPSAltitude>10</exif:GPSAltitude> -->
+   <dc:description> exif:GPSLatitude &lt;exif:GPSAltitude&gt;</dc:description>
+   <exif:GPSVersionID>2.2.0.0</exif:GPSVersionID>
+  </rdf:Description>"#;
+        let stripped = String::from_utf8(strip_xmp_gps(xmp)).unwrap();
+        assert_eq!(
+            stripped,
+            r#"<rdf:Description rdf:about=""
+    dc:source="see exif:GPSLongitude">
+   <!-- <exif:GPSAltitude>10</exif:GPSAltitude> -->
+   <dc:description> exif:GPSLatitude &lt;exif:GPSAltitude&gt;</dc:description>
+   
+  </rdf:Description>"#
+        );
+    }
+}
@@ -1,11 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // src/domain/document/operations/mod.rs
 //
-// Document operations: transformations, rendering, and export.
+// Document operations: transformations, rendering, search, and export.
 
+pub mod color;
+pub mod container;
 pub mod crop;
 pub mod export;
+pub mod histogram;
+pub mod metadata_edit;
 pub mod render;
+pub mod search;
 pub mod transform;
 
 // Re-export CropRegion for convenience
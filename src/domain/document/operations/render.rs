@@ -3,8 +3,81 @@
 //
 // Rendering operations for documents.
 
+use std::path::PathBuf;
+
 use cosmic::widget::image::Handle as ImageHandle;
-use image::{DynamicImage, GenericImageView};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GenericImageView, RgbaImage};
+
+use crate::domain::document::core::document::TransformState;
+use crate::domain::document::core::page::PageLayout;
+
+/// Font families for SVG text (see `vector::configure_fonts`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontFamilies {
+    /// Family for text without a `font-family` attribute.
+    pub default: String,
+    /// Family used for the generic `sans-serif` family.
+    pub sans_serif: String,
+    /// Family used for the generic `serif` family.
+    pub serif: String,
+    /// Family used for the generic `monospace` family.
+    pub monospace: String,
+}
+
+impl Default for FontFamilies {
+    fn default() -> Self {
+        Self {
+            default: "DejaVu Sans".to_string(),
+            sans_serif: "DejaVu Sans".to_string(),
+            serif: "DejaVu Serif".to_string(),
+            monospace: "DejaVu Sans Mono".to_string(),
+        }
+    }
+}
+
+/// Request to make page thumbnails on a worker thread.
+///
+/// Used for comic books, whose pages are decoded from the archive as their
+/// thumbnails come into view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThumbnailJob {
+    /// Archive the pages are read from.
+    pub path: PathBuf,
+    /// Page indices (0-based) with the names of their archive entries.
+    pub pages: Vec<(usize, String)>,
+}
+
+/// Request to render a document page at a new resolution.
+///
+/// Carries everything needed to render the page from the source file, so
+/// it can run on a worker thread while the old raster stays on screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageRenderJob {
+    /// Source file of the document.
+    pub path: PathBuf,
+    /// Password for encrypted documents.
+    pub password: Option<String>,
+    /// Page index (0-based).
+    pub page: usize,
+    /// Arrangement to render with (a spread includes the facing page).
+    pub layout: PageLayout,
+    /// Transformation to render with.
+    pub transform: TransformState,
+    /// Render scale in pixels per document unit.
+    pub scale: f64,
+}
+
+/// Result of a `PageRenderJob`.
+#[derive(Debug, Clone)]
+pub struct RenderedPage {
+    /// The job that produced this raster.
+    pub job: PageRenderJob,
+    /// Rendered page.
+    pub image: DynamicImage,
+    /// Display handle for `image`.
+    pub handle: ImageHandle,
+}
 
 /// Create an image handle from RGBA pixel data.
 ///
@@ -77,6 +150,30 @@ pub fn calculate_fill_scale(width: u32, height: u32, target_width: u32, target_h
     width_scale.max(height_scale)
 }
 
+/// Place two pages side by side, scaled to the height of the taller one.
+///
+/// Used for two-page spreads; `left` and `right` are in display order.
+#[must_use]
+pub fn compose_spread(left: &DynamicImage, right: &DynamicImage) -> DynamicImage {
+    let height = left.height().max(right.height());
+    let fit = |page: &DynamicImage| {
+        if page.height() == height {
+            page.to_rgba8()
+        } else {
+            let scale = f64::from(height) / f64::from(page.height());
+            let (width, _) = scale_dimensions(page.width(), page.height(), scale);
+            page.resize_exact(width, height, FilterType::Triangle)
+                .to_rgba8()
+        }
+    };
+    let (left, right) = (fit(left), fit(right));
+
+    let mut spread = RgbaImage::new(left.width() + right.width(), height);
+    imageops::replace(&mut spread, &left, 0, 0);
+    imageops::replace(&mut spread, &right, i64::from(left.width()), 0);
+    DynamicImage::ImageRgba8(spread)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,4 +202,18 @@ mod tests {
         // Portrait image filling square
         assert_eq!(calculate_fill_scale(100, 200, 100, 100), 1.0);
     }
+
+    #[test]
+    fn test_compose_spread() {
+        let left =
+            DynamicImage::ImageRgb8(image::RgbImage::from_pixel(4, 8, image::Rgb([255, 0, 0])));
+        let right =
+            DynamicImage::ImageRgb8(image::RgbImage::from_pixel(3, 4, image::Rgb([0, 0, 255])));
+
+        // The smaller page is scaled up to the same height.
+        let spread = compose_spread(&left, &right);
+        assert_eq!(spread.dimensions(), (10, 8));
+        assert_eq!(spread.get_pixel(0, 7), image::Rgba([255, 0, 0, 255]));
+        assert_eq!(spread.get_pixel(9, 0), image::Rgba([0, 0, 255, 255]));
+    }
 }
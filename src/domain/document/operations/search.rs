@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/domain/document/operations/search.rs
+//
+// Text search results and their placement on the displayed page.
+
+use std::path::PathBuf;
+
+use crate::domain::document::core::document::{Rotation, RotationMode, TransformState};
+
+/// Rectangle on a page (origin top-left).
+///
+/// Page units are PDF points for hits and logical pixels once mapped onto
+/// the displayed page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl TextRect {
+    /// Build a rectangle from two opposite corners.
+    #[must_use]
+    pub fn from_corners(x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        Self {
+            x: x1.min(x2),
+            y: y1.min(y2),
+            width: (x2 - x1).abs(),
+            height: (y2 - y1).abs(),
+        }
+    }
+
+    /// Multiply position and size by `factor`.
+    #[must_use]
+    pub fn scaled(self, factor: f64) -> Self {
+        Self {
+            x: self.x * factor,
+            y: self.y * factor,
+            width: self.width * factor,
+            height: self.height * factor,
+        }
+    }
+}
+
+/// A single match of a search query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// Page index (0-based).
+    pub page: usize,
+    /// Bounds on the unrotated page, in page units.
+    pub rect: TextRect,
+    /// Text of the line containing the match.
+    pub context: String,
+}
+
+/// Request to search a document for a query.
+///
+/// Like `PageRenderJob`, it carries everything needed to search the source
+/// file, so large documents can be searched on a worker thread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchJob {
+    /// Source file of the document.
+    pub path: PathBuf,
+    /// Password for encrypted documents.
+    pub password: Option<String>,
+    /// Text to find.
+    pub query: String,
+}
+
+/// Hits of a search query with a cursor on the current hit.
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    query: String,
+    hits: Vec<SearchHit>,
+    current: usize,
+}
+
+impl SearchResults {
+    /// Create results for `query`, starting at the first hit on or after
+    /// `page` (wrapping around to the first hit).
+    #[must_use]
+    pub fn new(query: impl Into<String>, hits: Vec<SearchHit>, page: usize) -> Self {
+        let current = hits.iter().position(|hit| hit.page >= page).unwrap_or(0);
+        Self {
+            query: query.into(),
+            hits,
+            current,
+        }
+    }
+
+    /// The query these results belong to.
+    #[must_use]
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// All hits in document order.
+    #[must_use]
+    pub fn hits(&self) -> &[SearchHit] {
+        &self.hits
+    }
+
+    /// Index of the current hit.
+    #[must_use]
+    pub fn current_index(&self) -> Option<usize> {
+        (!self.hits.is_empty()).then_some(self.current)
+    }
+
+    /// The current hit.
+    #[must_use]
+    pub fn current(&self) -> Option<&SearchHit> {
+        self.hits.get(self.current)
+    }
+
+    /// Move to the next hit, wrapping around at the end.
+    pub fn next(&mut self) -> Option<&SearchHit> {
+        if !self.hits.is_empty() {
+            self.current = (self.current + 1) % self.hits.len();
+        }
+        self.current()
+    }
+
+    /// Move to the previous hit, wrapping around at the start.
+    pub fn prev(&mut self) -> Option<&SearchHit> {
+        if !self.hits.is_empty() {
+            self.current = (self.current + self.hits.len() - 1) % self.hits.len();
+        }
+        self.current()
+    }
+
+    /// Make the hit at `index` the current one.
+    pub fn select(&mut self, index: usize) -> Option<&SearchHit> {
+        if index < self.hits.len() {
+            self.current = index;
+        }
+        self.current()
+    }
+}
+
+/// Map a rectangle on the unrotated page onto the displayed page.
+///
+/// The page is rotated and then flipped like the rendered raster (see
+/// `TransformState`). Only 90° steps are supported; fine rotations are
+/// rounded to the nearest step.
+#[must_use]
+pub fn map_to_display(
+    rect: TextRect,
+    page_size: (f64, f64),
+    transform: TransformState,
+) -> TextRect {
+    let (width, height) = page_size;
+    let rotation = match transform.rotation {
+        RotationMode::Standard(rotation) => rotation,
+        RotationMode::Fine(degrees) => {
+            #[allow(clippy::cast_possible_truncation)]
+            let quarter_turns = ((degrees / 90.0).round() as i32).rem_euclid(4);
+            match quarter_turns {
+                1 => Rotation::Cw90,
+                2 => Rotation::Cw180,
+                3 => Rotation::Cw270,
+                _ => Rotation::None,
+            }
+        }
+    };
+
+    let rotate = |x: f64, y: f64| match rotation {
+        Rotation::None => (x, y),
+        Rotation::Cw90 => (height - y, x),
+        Rotation::Cw180 => (width - x, height - y),
+        Rotation::Cw270 => (y, width - x),
+    };
+    let (display_width, display_height) = match rotation {
+        Rotation::Cw90 | Rotation::Cw270 => (height, width),
+        Rotation::None | Rotation::Cw180 => (width, height),
+    };
+    let flip = |(x, y): (f64, f64)| match (transform.flip_h, transform.flip_v) {
+        (false, false) => (x, y),
+        (true, false) => (display_width - x, y),
+        (false, true) => (x, display_height - y),
+        (true, true) => (display_width - x, display_height - y),
+    };
+
+    let (x1, y1) = flip(rotate(rect.x, rect.y));
+    let (x2, y2) = flip(rotate(rect.x + rect.width, rect.y + rect.height));
+    TextRect::from_corners(x1, y1, x2, y2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(page: usize) -> SearchHit {
+        SearchHit {
+            page,
+            rect: TextRect::from_corners(0.0, 0.0, 1.0, 1.0),
+            context: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_search_results_navigation() {
+        let mut results = SearchResults::new("word", vec![hit(0), hit(2), hit(2), hit(5)], 1);
+        assert_eq!(results.current_index(), Some(1));
+
+        assert_eq!(results.next().map(|h| h.page), Some(2));
+        assert_eq!(results.next().map(|h| h.page), Some(5));
+        assert_eq!(results.next().map(|h| h.page), Some(0));
+        assert_eq!(results.prev().map(|h| h.page), Some(5));
+        assert_eq!(results.select(1).map(|h| h.page), Some(2));
+        assert_eq!(results.select(9).map(|h| h.page), Some(2));
+
+        // Searching past the last hit wraps to the first one.
+        let results = SearchResults::new("word", vec![hit(0), hit(2)], 3);
+        assert_eq!(results.current_index(), Some(0));
+
+        let mut results = SearchResults::new("none", Vec::new(), 0);
+        assert_eq!(results.current_index(), None);
+        assert!(results.next().is_none());
+        assert!(results.prev().is_none());
+    }
+
+    #[test]
+    fn test_map_to_display() {
+        // A 10x20 page with a 2x3 rect at (1, 4).
+        let rect = TextRect::from_corners(1.0, 4.0, 3.0, 7.0);
+        let page = (10.0, 20.0);
+        let state = |rotation, flip_h, flip_v| TransformState {
+            rotation: RotationMode::Standard(rotation),
+            flip_h,
+            flip_v,
+        };
+
+        let cases = [
+            (state(Rotation::None, false, false), (1.0, 4.0, 2.0, 3.0)),
+            (state(Rotation::Cw90, false, false), (13.0, 1.0, 3.0, 2.0)),
+            (state(Rotation::Cw180, false, false), (7.0, 13.0, 2.0, 3.0)),
+            (state(Rotation::Cw270, false, false), (4.0, 7.0, 3.0, 2.0)),
+            (state(Rotation::None, true, false), (7.0, 4.0, 2.0, 3.0)),
+            (state(Rotation::None, false, true), (1.0, 13.0, 2.0, 3.0)),
+            (state(Rotation::Cw90, true, false), (4.0, 1.0, 3.0, 2.0)),
+        ];
+
+        for (transform, (x, y, width, height)) in cases {
+            assert_eq!(
+                map_to_display(rect, page, transform),
+                TextRect {
+                    x,
+                    y,
+                    width,
+                    height
+                },
+                "{transform:?}"
+            );
+        }
+    }
+}
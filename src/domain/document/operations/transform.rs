@@ -9,6 +9,7 @@
 //    - `apply_rotation()` - Rotate pixels by 90°, 180°, or 270° [pub(crate)]
 //    - `apply_flip()` - Flip pixels horizontally or vertically [pub(crate)]
 //    - `crop_image()` - Crop to a specific region [pub(crate)]
+//    - `rotate_image_fine()` - Rotate pixels by an arbitrary angle [pub(crate)]
 //    These are used internally by document type implementations only.
 //
 // 2. **High-level operations** that work on any document type (raster, vector, PDF):
@@ -17,6 +18,7 @@
 //    - `flip_document_horizontal()` - Flip any document horizontally
 //    - `flip_document_vertical()` - Flip any document vertically
 //    - `rotate_document_to()` - Rotate to a specific angle
+//    - `rotate_document_fine()` - Straighten by an arbitrary angle
 //    - `reset_document_transforms()` - Reset all transformations
 //
 // ## Usage Example
@@ -38,11 +40,15 @@
 // document types (Raster, Vector, Portable), while low-level operations work
 // directly on pixel data.
 
+use std::sync::Arc;
+
 use image::{DynamicImage, GenericImageView};
 
 use crate::domain::document::core::content::DocumentContent;
+use imageproc::geometric_transformations::{Interpolation, warp_into_with};
+
 use crate::domain::document::core::document::{
-    DocResult, FlipDirection, Rotation, RotationMode, Transformable,
+    DocResult, FlipDirection, InterpolationQuality, Rotation, RotationMode, Transformable,
 };
 
 /// Apply a 90-degree rotation to a raster image.
@@ -64,7 +70,7 @@ pub(crate) fn apply_rotation(img: DynamicImage, rotation: Rotation) -> DynamicIm
 /// Apply a flip transformation to a raster image.
 ///
 /// This function performs the actual pixel manipulation for flip operations.
-/// Used internally by `RasterDocument` and `PortableDocument` implementations.
+/// Used internally by `RasterDocument` implementation.
 #[must_use]
 pub(crate) fn apply_flip(img: DynamicImage, direction: FlipDirection) -> DynamicImage {
     use image::imageops::{flip_horizontal, flip_vertical};
@@ -117,6 +123,169 @@ pub fn dimensions_after_rotation(width: u32, height: u32, rotation: Rotation) ->
     }
 }
 
+/// Rotate a raster image clockwise by an arbitrary angle in degrees.
+///
+/// The canvas grows to the bounding box of the rotated image and the
+/// uncovered corners stay transparent. With `auto_crop` the result is
+/// instead cut down to the largest axis-aligned rectangle that contains
+/// only image pixels (see `largest_inscribed_rect()`).
+/// Used internally by `RasterDocument` implementation.
+#[must_use]
+pub(crate) fn rotate_image_fine(
+    img: &DynamicImage,
+    angle_degrees: f32,
+    quality: InterpolationQuality,
+    auto_crop: bool,
+) -> DynamicImage {
+    // Bicubic sampling reads up to two pixels beyond the sample point.
+    const PAD: u32 = 2;
+
+    let source = img.to_rgba8();
+    let (width, height) = source.dimensions();
+    if width == 0 || height == 0 {
+        return img.clone();
+    }
+
+    // Pad the source with its edge pixels so that the borders get sampled
+    // like the interior. Without auto-crop the padding is transparent and
+    // gives the rotated edges a smooth alpha ramp.
+    let padded = image::RgbaImage::from_fn(width + 2 * PAD, height + 2 * PAD, |x, y| {
+        let sx = x.saturating_sub(PAD).min(width - 1);
+        let sy = y.saturating_sub(PAD).min(height - 1);
+        let mut pixel = *source.get_pixel(sx, sy);
+        let outside = x < PAD || y < PAD || x >= width + PAD || y >= height + PAD;
+        if outside && !auto_crop {
+            pixel[3] = 0;
+        }
+        pixel
+    });
+
+    let (out_width, out_height) = if auto_crop {
+        largest_inscribed_rect(width, height, angle_degrees)
+    } else {
+        dimensions_after_fine_rotation(width, height, angle_degrees)
+    };
+
+    let interpolation = match quality {
+        InterpolationQuality::Fast => Interpolation::Nearest,
+        InterpolationQuality::Balanced => Interpolation::Bilinear,
+        InterpolationQuality::Best => Interpolation::Bicubic,
+    };
+
+    // Map every output pixel center back into the source around the
+    // common center point (inverse rotation).
+    let (sin, cos) = angle_degrees.to_radians().sin_cos();
+    let src_cx = (width + 2 * PAD) as f32 / 2.0;
+    let src_cy = (height + 2 * PAD) as f32 / 2.0;
+    let (out_cx, out_cy) = (out_width as f32 / 2.0, out_height as f32 / 2.0);
+    let mapping = |x: f32, y: f32| {
+        let dx = x + 0.5 - out_cx;
+        let dy = y + 0.5 - out_cy;
+        (
+            dx * cos + dy * sin + src_cx - 0.5,
+            -dx * sin + dy * cos + src_cy - 0.5,
+        )
+    };
+
+    let mut out = image::RgbaImage::new(out_width, out_height);
+    warp_into_with(
+        &padded,
+        mapping,
+        interpolation,
+        image::Rgba([0, 0, 0, 0]),
+        &mut out,
+    );
+
+    DynamicImage::ImageRgba8(out)
+}
+
+/// Request to resample a fine rotation at full resolution.
+///
+/// Carries the pixels underneath the rotation and its settings, so it can
+/// run on a worker thread while a preview is shown.
+#[derive(Debug, Clone)]
+pub struct FineRotationJob {
+    /// Pixels underneath the fine rotation.
+    pub source: Arc<DynamicImage>,
+    /// Angle in degrees (clockwise).
+    pub angle: f32,
+    /// Interpolation quality to sample with.
+    pub quality: InterpolationQuality,
+    /// Crop to the largest rectangle without empty corners.
+    pub auto_crop: bool,
+}
+
+impl FineRotationJob {
+    /// Rotate the source pixels.
+    #[must_use]
+    pub fn run(self) -> RotatedImage {
+        let image = rotate_image_fine(&self.source, self.angle, self.quality, self.auto_crop);
+        RotatedImage { job: self, image }
+    }
+}
+
+/// Result of a `FineRotationJob`.
+#[derive(Debug, Clone)]
+pub struct RotatedImage {
+    /// The job that produced this image.
+    pub job: FineRotationJob,
+    /// Rotated pixels.
+    pub image: DynamicImage,
+}
+
+/// Calculate the bounding box dimensions after rotating by an arbitrary angle.
+#[must_use]
+pub fn dimensions_after_fine_rotation(width: u32, height: u32, angle_degrees: f32) -> (u32, u32) {
+    let (sin, cos) = angle_degrees.to_radians().sin_cos();
+    let (sin, cos) = (sin.abs(), cos.abs());
+    let (w, h) = (width as f32, height as f32);
+
+    // Shave off float noise so exact right angles do not grow by a pixel.
+    let out_width = (w * cos + h * sin - 1e-3).ceil().max(1.0);
+    let out_height = (w * sin + h * cos - 1e-3).ceil().max(1.0);
+
+    (out_width as u32, out_height as u32)
+}
+
+/// Calculate the largest axis-aligned rectangle that fits inside an image
+/// of the given size after rotating it by an arbitrary angle.
+///
+/// The rectangle is centered on the rotated image.
+#[must_use]
+pub fn largest_inscribed_rect(width: u32, height: u32, angle_degrees: f32) -> (u32, u32) {
+    if width == 0 || height == 0 {
+        return (0, 0);
+    }
+
+    let (sin, cos) = angle_degrees.to_radians().sin_cos();
+    let (sin, cos) = (f64::from(sin.abs()), f64::from(cos.abs()));
+    let (w, h) = (f64::from(width), f64::from(height));
+
+    let width_is_longer = w >= h;
+    let (long_side, short_side) = if width_is_longer { (w, h) } else { (h, w) };
+
+    let (crop_width, crop_height) =
+        if short_side <= 2.0 * sin * cos * long_side || (sin - cos).abs() < 1e-10 {
+            // Half constrained: two crop corners touch the longer sides.
+            let x = 0.5 * short_side;
+            if width_is_longer {
+                (x / sin, x / cos)
+            } else {
+                (x / cos, x / sin)
+            }
+        } else {
+            // Fully constrained: the crop touches all four sides.
+            let cos_2a = cos * cos - sin * sin;
+            ((w * cos - h * sin) / cos_2a, (h * cos - w * sin) / cos_2a)
+        };
+
+    // Round down (with some tolerance) so no transparent corner pixel remains.
+    let crop_width = ((crop_width + 1e-3).floor() as u32).clamp(1, width.max(height));
+    let crop_height = ((crop_height + 1e-3).floor() as u32).clamp(1, width.max(height));
+
+    (crop_width, crop_height)
+}
+
 // ============================================================================
 // High-Level Document Operations (Type-agnostic)
 // ============================================================================
@@ -151,7 +320,7 @@ pub fn dimensions_after_rotation(width: u32, height: u32, rotation: Rotation) ->
 /// - Vector: Viewport matrix transformation (lossless)
 /// - Portable: View rotation, rendered by backend
 pub fn rotate_document_cw(document: &mut DocumentContent) -> DocResult<()> {
-    let new_rotation_mode = document.transform_state().rotation.rotate_cw();
+    let new_rotation_mode = document.transform_state().rotated_cw();
 
     match new_rotation_mode {
         RotationMode::Standard(rot) => {
@@ -187,7 +356,7 @@ pub fn rotate_document_cw(document: &mut DocumentContent) -> DocResult<()> {
 /// rotate_document_ccw(&mut document)?;
 /// ```
 pub fn rotate_document_ccw(document: &mut DocumentContent) -> DocResult<()> {
-    let new_rotation_mode = document.transform_state().rotation.rotate_ccw();
+    let new_rotation_mode = document.transform_state().rotated_ccw();
 
     match new_rotation_mode {
         RotationMode::Standard(rot) => {
@@ -268,9 +437,32 @@ pub fn rotate_document_to(document: &mut DocumentContent, rotation: Rotation) ->
     Ok(())
 }
 
+/// Straighten a document by an arbitrary angle in degrees (clockwise).
+///
+/// The angle is applied on top of the 90° rotation and flips and replaces
+/// any previous fine rotation. Only raster documents support this so far;
+/// other document types ignore it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crate::domain::document::operations::transform::rotate_document_fine;
+///
+/// // Level a horizon that leans 1.5° to the left
+/// rotate_document_fine(&mut document, 1.5)?;
+/// ```
+pub fn rotate_document_fine(document: &mut DocumentContent, angle_degrees: f32) -> DocResult<()> {
+    if !angle_degrees.is_finite() {
+        return Err(anyhow::anyhow!("Invalid rotation angle: {angle_degrees}"));
+    }
+    document.rotate_fine(angle_degrees);
+    Ok(())
+}
+
 /// Reset all transformations on a document.
 ///
-/// This resets the document to its original state (no rotation, no flips).
+/// This resets the document to its original state (no rotation, no flips,
+/// no fine rotation).
 /// Useful for implementing "Reset View" functionality.
 ///
 /// # Examples
@@ -282,7 +474,8 @@ pub fn rotate_document_to(document: &mut DocumentContent, rotation: Rotation) ->
 /// reset_document_transforms(&mut document)?;
 /// ```
 pub fn reset_document_transforms(document: &mut DocumentContent) -> DocResult<()> {
-    // Reset to no rotation
+    // Drop the fine rotation first, then reset to no rotation
+    document.reset_fine_rotation();
     document.rotate(Rotation::None);
 
     // Reset flips by checking current state and flipping back if needed
@@ -320,4 +513,59 @@ mod tests {
             (200, 100)
         );
     }
+
+    #[test]
+    fn test_dimensions_after_fine_rotation() {
+        assert_eq!(dimensions_after_fine_rotation(100, 200, 0.0), (100, 200));
+        assert_eq!(dimensions_after_fine_rotation(100, 200, 90.0), (200, 100));
+        assert_eq!(dimensions_after_fine_rotation(100, 100, 45.0), (142, 142));
+        assert_eq!(
+            dimensions_after_fine_rotation(100, 200, -10.0),
+            dimensions_after_fine_rotation(100, 200, 10.0)
+        );
+    }
+
+    #[test]
+    fn test_largest_inscribed_rect() {
+        assert_eq!(largest_inscribed_rect(300, 200, 0.0), (300, 200));
+        assert_eq!(largest_inscribed_rect(300, 200, 90.0), (200, 300));
+        // A square turned by 45° holds a square of half its diagonal.
+        assert_eq!(largest_inscribed_rect(100, 100, 45.0), (70, 70));
+
+        let (w, h) = largest_inscribed_rect(4000, 3000, 5.0);
+        assert!(w < 4000 && h < 3000);
+        assert_eq!((w, h), largest_inscribed_rect(4000, 3000, -5.0));
+    }
+
+    #[test]
+    fn test_rotate_image_fine() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(40, 20, |x, y| {
+            image::Rgba([(x * 6) as u8, (y * 12) as u8, 128, 255])
+        }));
+
+        // A quarter turn through the generic path matches the lossless one.
+        let rotated = rotate_image_fine(&img, 90.0, InterpolationQuality::Fast, false);
+        assert_eq!(
+            rotated.to_rgba8(),
+            apply_rotation(img.clone(), Rotation::Cw90).to_rgba8()
+        );
+
+        // Uncovered corners are transparent unless cropped away.
+        for quality in [
+            InterpolationQuality::Fast,
+            InterpolationQuality::Balanced,
+            InterpolationQuality::Best,
+        ] {
+            let rotated = rotate_image_fine(&img, 10.0, quality, false).to_rgba8();
+            assert_eq!(
+                rotated.dimensions(),
+                dimensions_after_fine_rotation(40, 20, 10.0)
+            );
+            assert_eq!(rotated.get_pixel(0, 0)[3], 0);
+
+            let cropped = rotate_image_fine(&img, 10.0, quality, true).to_rgba8();
+            assert_eq!(cropped.dimensions(), largest_inscribed_rect(40, 20, 10.0));
+            assert!(cropped.pixels().all(|p| p[3] > 0));
+        }
+    }
 }
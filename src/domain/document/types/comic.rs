@@ -0,0 +1,579 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/domain/document/types/comic.rs
+//
+// Comic book archives (CBZ/ZIP of images) read page by page.
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Longest side of a page thumbnail in pixels.
+const THUMBNAIL_SIZE: u32 = 200;
+
+/// Largest archive entry decoded as a page (guards against zip bombs).
+const MAX_PAGE_SIZE: u64 = 256 * 1024 * 1024;
+
+use cosmic::widget::image::Handle as ImageHandle;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use zip::ZipArchive;
+
+use crate::domain::document::core::document::{
+    DocResult, DocumentInfo, FlipDirection, InterpolationQuality, MultiPage, MultiPageThumbnails,
+    RenderOutput, Renderable, Rotation, TransformState, Transformable,
+};
+use crate::domain::document::core::page::PageLayout;
+use crate::domain::document::operations::render::{self, ThumbnailJob};
+use crate::domain::document::types::raster::RasterDocument;
+
+type Archive = ZipArchive<BufReader<File>>;
+
+/// Represents a comic book archive (CBZ/ZIP).
+///
+/// Pages are decoded on demand; the page or spread on screen is a
+/// `RasterDocument`, which handles all transformations.
+pub struct ComicDocument {
+    /// Path to the archive.
+    source_path: PathBuf,
+    /// Names of the image entries, in reading order.
+    pages: Vec<String>,
+    /// First page of the current view (0-based).
+    page_index: usize,
+    /// Spread and reading direction.
+    layout: PageLayout,
+    /// The page or spread on screen.
+    view: RasterDocument,
+    /// Thumbnail handle of each page (None = not yet generated).
+    ///
+    /// Thumbnails are made on demand for the pages in view of the pages
+    /// panel (see `thumbnail_job`), not when the archive is opened.
+    thumbnail_cache: Vec<Option<ImageHandle>>,
+}
+
+impl ComicDocument {
+    /// Open an archive and show its first page.
+    ///
+    /// Fails if the archive holds no images.
+    pub fn open(path: &Path) -> DocResult<Self> {
+        let mut archive = Self::archive(path)?;
+        let pages = Self::image_entries(&archive);
+        let first = pages
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Archive contains no images"))?;
+        let image = Self::decode_page(&mut archive, first)?;
+
+        Ok(Self {
+            source_path: path.to_path_buf(),
+            view: RasterDocument::from_decoded(path, image),
+            thumbnail_cache: vec![None; pages.len()],
+            pages,
+            page_index: 0,
+            layout: PageLayout::default(),
+        })
+    }
+
+    /// Current spread and reading direction.
+    #[must_use]
+    pub fn layout(&self) -> PageLayout {
+        self.layout
+    }
+
+    /// Change the spread or reading direction, staying on the current page.
+    ///
+    /// Comic books have no continuous view, only the spread settings apply.
+    pub fn set_layout(&mut self, layout: PageLayout) -> DocResult<()> {
+        let layout = PageLayout {
+            continuous: false,
+            ..layout
+        };
+        if layout == self.layout {
+            return Ok(());
+        }
+
+        let before = self.visible_pages();
+        self.layout = layout;
+        // The reading direction only matters when two pages are shown.
+        if self.visible_pages() != before || before.len() > 1 {
+            self.show(self.page_index)?;
+        }
+        Ok(())
+    }
+
+    /// Pages on screen, in reading order.
+    #[must_use]
+    pub fn visible_pages(&self) -> Range<usize> {
+        self.layout.visible_pages(self.page_index, self.pages.len())
+    }
+
+    /// Returns the current pixel dimensions (width, height) after transforms.
+    #[must_use]
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.view.dimensions()
+    }
+
+    /// Get the current image handle.
+    #[must_use]
+    pub fn handle(&self) -> ImageHandle {
+        self.view.handle()
+    }
+
+    /// Get the page or spread on screen with all transformations applied.
+    #[must_use]
+    pub fn image(&self) -> &DynamicImage {
+        self.view.image()
+    }
+
+    /// The page or spread on screen.
+    #[must_use]
+    pub fn view(&self) -> &RasterDocument {
+        &self.view
+    }
+
+    /// Mutable access to the page or spread on screen.
+    pub fn view_mut(&mut self) -> &mut RasterDocument {
+        &mut self.view
+    }
+
+    /// Crop the page or spread on screen.
+    pub fn crop(&mut self, x: u32, y: u32, width: u32, height: u32) -> Result<(), String> {
+        self.view.crop(x, y, width, height)
+    }
+
+    /// Get count of thumbnails currently loaded.
+    #[must_use]
+    pub fn thumbnails_loaded(&self) -> usize {
+        self.thumbnail_cache.iter().flatten().count()
+    }
+
+    /// Get thumbnail handle for a specific page (read-only access).
+    /// Returns None if the thumbnail hasn't been generated yet.
+    #[must_use]
+    pub fn get_thumbnail_handle(&self, page: usize) -> Option<ImageHandle> {
+        self.thumbnail_cache.get(page)?.clone()
+    }
+
+    /// Prepare thumbnails of the pages in `pages` that have none yet.
+    ///
+    /// Returns None if all of them have a thumbnail.
+    #[must_use]
+    pub fn thumbnail_job(&self, pages: Range<usize>) -> Option<ThumbnailJob> {
+        let pages: Vec<(usize, String)> = pages
+            .filter(|&page| matches!(self.thumbnail_cache.get(page), Some(None)))
+            .map(|page| (page, self.pages[page].clone()))
+            .collect();
+        (!pages.is_empty()).then(|| ThumbnailJob {
+            path: self.source_path.clone(),
+            pages,
+        })
+    }
+
+    /// Run a `ThumbnailJob`. Does not need the document, so it can be called
+    /// from a worker thread.
+    ///
+    /// Pages that fail to decode get an empty thumbnail.
+    pub fn run_thumbnail_job(job: &ThumbnailJob) -> DocResult<Vec<(usize, ImageHandle)>> {
+        let mut archive = Self::archive(&job.path)?;
+        Ok(job
+            .pages
+            .iter()
+            .map(|(page, name)| {
+                let handle = match Self::decode_page(&mut archive, name) {
+                    Ok(img) => render::create_image_handle_from_image(
+                        &img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE),
+                    ),
+                    Err(e) => {
+                        log::warn!("Failed to generate thumbnail for page {page}: {e}");
+                        ImageHandle::from_rgba(1, 1, vec![0, 0, 0, 0])
+                    }
+                };
+                (*page, handle)
+            })
+            .collect())
+    }
+
+    /// Store thumbnails made by `run_thumbnail_job`.
+    pub fn set_thumbnails(&mut self, thumbnails: Vec<(usize, ImageHandle)>) {
+        for (page, handle) in thumbnails {
+            if let Some(slot) = self.thumbnail_cache.get_mut(page) {
+                *slot = Some(handle);
+            }
+        }
+    }
+
+    /// Extract metadata for this archive.
+    pub fn extract_meta(
+        &self,
+        path: &Path,
+    ) -> crate::domain::document::core::metadata::DocumentMeta {
+        use crate::domain::document::core::metadata::{BasicMeta, DocumentMeta};
+
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let file_path = path.to_string_lossy().to_string();
+        let file_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+
+        let (width, height) = self.view.native_dimensions();
+        let format = format!("CBZ ({} pages)", self.pages.len());
+
+        let basic = BasicMeta {
+            file_name,
+            file_path,
+            format,
+            width,
+            height,
+            file_size,
+            color_type: format!("{:?}", self.view.image().color()),
+            format_mismatch: None,
+        };
+
+        DocumentMeta {
+            basic,
+            exif: None,
+            pdf: None,
+            tags: Vec::new(),
+        }
+    }
+
+    // Helper functions
+
+    fn archive(path: &Path) -> DocResult<Archive> {
+        Ok(ZipArchive::new(BufReader::new(File::open(path)?))?)
+    }
+
+    /// Names of the decodable images in the archive, sorted naturally.
+    ///
+    /// Hidden files and macOS resource forks are skipped.
+    fn image_entries(archive: &Archive) -> Vec<String> {
+        let mut names: Vec<String> = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/'))
+            .filter(|name| {
+                !name
+                    .split('/')
+                    .any(|part| part.starts_with('.') || part == "__MACOSX")
+            })
+            .filter(|name| {
+                ImageFormat::from_path(name).is_ok_and(|format| format.reading_enabled())
+            })
+            .map(str::to_string)
+            .collect();
+
+        names.sort_by(|a, b| natural_cmp(a, b).then_with(|| a.cmp(b)));
+        names
+    }
+
+    /// Decode the image stored under `name`.
+    fn decode_page(archive: &mut Archive, name: &str) -> DocResult<DynamicImage> {
+        let entry = archive.by_name(name)?;
+        if entry.size() > MAX_PAGE_SIZE {
+            return Err(anyhow::anyhow!("Page {name} is too large"));
+        }
+
+        // The declared size may be wrong, so the read is capped as well.
+        let mut data = Vec::with_capacity(usize::try_from(entry.size())?);
+        entry.take(MAX_PAGE_SIZE).read_to_end(&mut data)?;
+
+        // The content decides, the extension is only a fallback.
+        let format = image::guess_format(&data).or_else(|_| ImageFormat::from_path(name))?;
+        Ok(image::load_from_memory_with_format(&data, format)?)
+    }
+
+    /// Show the view containing `page`.
+    ///
+    /// The rotation and flips carry over from the previous view, the fine
+    /// rotation and crops start over.
+    fn show(&mut self, page: usize) -> DocResult<()> {
+        let pages = self.layout.visible_pages(page, self.pages.len());
+        let mut archive = Self::archive(&self.source_path)?;
+        let mut images = pages
+            .clone()
+            .map(|page| Self::decode_page(&mut archive, &self.pages[page]))
+            .collect::<DocResult<Vec<_>>>()?;
+        if self.layout.right_to_left {
+            images.reverse();
+        }
+
+        let image = match images.as_slice() {
+            [left, right] => render::compose_spread(left, right),
+            _ => images.swap_remove(0),
+        };
+
+        self.view.reset_fine_rotation();
+        let transform = self.view.transform_state();
+        self.view = RasterDocument::from_page(&self.source_path, image, transform);
+        self.page_index = pages.start;
+        Ok(())
+    }
+
+    /// Generate the missing thumbnails of `pages` right away.
+    fn generate_thumbnails(&mut self, pages: Range<usize>) -> DocResult<()> {
+        if let Some(job) = self.thumbnail_job(pages) {
+            let thumbnails = Self::run_thumbnail_job(&job)?;
+            self.set_thumbnails(thumbnails);
+        }
+        Ok(())
+    }
+}
+
+/// Compare names with embedded numbers by value ("page2" before "page10"),
+/// ignoring case.
+#[must_use]
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let ordering = match (a.peek(), b.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_number(&mut a);
+                let y = take_number(&mut b);
+                // Without leading zeros, the longer number is the larger one.
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                a.next();
+                b.next();
+                ordering
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Consume a run of ASCII digits.
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut number = String::new();
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        number.push(digit);
+    }
+    number
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+
+impl Renderable for ComicDocument {
+    fn render(&mut self, scale: f64) -> DocResult<RenderOutput> {
+        self.view.render(scale)
+    }
+
+    fn info(&self) -> DocumentInfo {
+        let (width, height) = self.view.native_dimensions();
+        DocumentInfo {
+            width,
+            height,
+            format: "CBZ".to_string(),
+        }
+    }
+}
+
+impl Transformable for ComicDocument {
+    fn rotate(&mut self, rotation: Rotation) {
+        self.view.rotate(rotation);
+    }
+
+    fn flip(&mut self, direction: FlipDirection) {
+        self.view.flip(direction);
+    }
+
+    fn transform_state(&self) -> TransformState {
+        self.view.transform_state()
+    }
+
+    fn rotate_fine(&mut self, angle_degrees: f32) {
+        self.view.rotate_fine(angle_degrees);
+    }
+
+    fn reset_fine_rotation(&mut self) {
+        self.view.reset_fine_rotation();
+    }
+
+    fn fine_rotation(&self) -> f32 {
+        self.view.fine_rotation()
+    }
+
+    fn set_interpolation_quality(&mut self, quality: InterpolationQuality) {
+        self.view.set_interpolation_quality(quality);
+    }
+
+    fn set_auto_crop(&mut self, enabled: bool) {
+        self.view.set_auto_crop(enabled);
+    }
+}
+
+impl MultiPage for ComicDocument {
+    fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn current_page(&self) -> usize {
+        self.page_index
+    }
+
+    fn go_to_page(&mut self, page: usize) -> DocResult<()> {
+        if page >= self.pages.len() {
+            return Err(anyhow::anyhow!(
+                "Page {} out of range (0-{})",
+                page,
+                self.pages.len() - 1
+            ));
+        }
+        self.show(page)
+    }
+}
+
+impl MultiPageThumbnails for ComicDocument {
+    fn thumbnails_ready(&self) -> bool {
+        self.thumbnail_cache.iter().all(Option::is_some)
+    }
+
+    fn thumbnails_loaded(&self) -> bool {
+        ComicDocument::thumbnails_loaded(self) >= self.pages.len()
+    }
+
+    fn generate_thumbnail_page(&mut self, page: usize) -> DocResult<()> {
+        self.generate_thumbnails(page..page + 1)
+    }
+
+    fn generate_all_thumbnails(&mut self) -> DocResult<()> {
+        self.generate_thumbnails(0..self.pages.len())
+    }
+
+    fn get_thumbnail(&mut self, page: usize) -> DocResult<Option<ImageHandle>> {
+        Ok(self.get_thumbnail_handle(page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use std::io::{Cursor, Write};
+
+    use image::{Rgb, RgbImage};
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    /// Encode a solid-color PNG page.
+    fn page(width: u32, height: u32, color: [u8; 3]) -> Vec<u8> {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb(color)));
+        let mut data = Vec::new();
+        img.write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    /// Write an archive with the given entries.
+    fn write_archive(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names = vec![
+            "page10.png",
+            "Page2.png",
+            "page1.png",
+            "page02b.png",
+            "cover.png",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            [
+                "cover.png",
+                "page1.png",
+                "Page2.png",
+                "page02b.png",
+                "page10.png"
+            ]
+        );
+        assert_eq!(natural_cmp("ch2/p9.jpg", "ch10/p1.jpg"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_open_comic() {
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let path = dir.join("issue.cbz");
+
+        let red = page(4, 6, [255, 0, 0]);
+        let green = page(4, 6, [0, 255, 0]);
+        let blue = page(2, 3, [0, 0, 255]);
+        write_archive(
+            &path,
+            &[
+                ("issue/10.png", &blue),
+                ("issue/2.png", &green),
+                ("issue/1.png", &red),
+                ("issue/notes.txt", b"not a page"),
+                ("__MACOSX/issue/._1.png", b"resource fork"),
+            ],
+        );
+
+        let mut doc = ComicDocument::open(&path).unwrap();
+        assert_eq!(doc.page_count(), 3);
+        assert_eq!(doc.image().get_pixel(0, 0), image::Rgba([255, 0, 0, 255]));
+
+        doc.go_to_page(2).unwrap();
+        assert_eq!(doc.dimensions(), (2, 3));
+
+        // Spreads keep the cover alone and pair the following pages.
+        let mut layout = PageLayout {
+            two_page_spread: true,
+            ..PageLayout::default()
+        };
+        doc.set_layout(layout).unwrap();
+        assert_eq!(doc.visible_pages(), 1..3);
+        assert_eq!(doc.current_page(), 1);
+        assert_eq!(doc.dimensions(), (8, 6));
+        assert_eq!(doc.image().get_pixel(0, 0), image::Rgba([0, 255, 0, 255]));
+
+        // Right to left puts the first page of the spread on the right.
+        layout.right_to_left = true;
+        doc.set_layout(layout).unwrap();
+        assert_eq!(doc.image().get_pixel(0, 0), image::Rgba([0, 0, 255, 255]));
+        assert_eq!(doc.image().get_pixel(7, 0), image::Rgba([0, 255, 0, 255]));
+
+        // Rotations carry over to the next view.
+        doc.rotate(Rotation::Cw90);
+        doc.go_to_page(0).unwrap();
+        assert_eq!(doc.dimensions(), (6, 4));
+
+        // Thumbnails are only made for the pages asked for.
+        assert_eq!(ComicDocument::thumbnails_loaded(&doc), 0);
+        let job = doc.thumbnail_job(1..5).unwrap();
+        assert_eq!(
+            job.pages,
+            [
+                (1, "issue/2.png".to_string()),
+                (2, "issue/10.png".to_string())
+            ]
+        );
+        doc.set_thumbnails(ComicDocument::run_thumbnail_job(&job).unwrap());
+        assert!(doc.get_thumbnail_handle(0).is_none());
+        assert!(doc.get_thumbnail_handle(2).is_some());
+        assert_eq!(doc.thumbnail_job(1..3), None);
+
+        doc.generate_all_thumbnails().unwrap();
+        assert_eq!(ComicDocument::thumbnails_loaded(&doc), 3);
+
+        write_archive(&path, &[("readme.txt", b"no pages")]);
+        assert!(ComicDocument::open(&path).is_err());
+    }
+}
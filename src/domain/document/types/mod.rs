@@ -8,3 +8,5 @@ pub mod raster;
 pub mod vector;
 #[cfg(feature = "portable")]
 pub mod portable;
+#[cfg(feature = "comic")]
+pub mod comic;
@@ -3,25 +3,44 @@
 //
 // Portable documents (PDF) with poppler backend.
 
+use std::collections::HashMap;
+use std::ffi::CStr;
 use std::io::Cursor;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
-/// PDF page render quality multiplier (2.0 = double resolution for sharp display).
-const PDF_RENDER_QUALITY: f64 = 2.0;
+/// Logical pixels per PDF point at 100% zoom (2.0 = double resolution).
+const PDF_BASE_SCALE: f64 = 2.0;
+
+/// Lowest render scale (pixels per point) used when zoomed far out.
+const PDF_MIN_RENDER_SCALE: f64 = 0.25;
+
+/// Longest side of a page raster in pixels (common GPU texture limit).
+const PDF_MAX_RENDER_SIZE: f64 = 8192.0;
+
+/// Relative resolution change below which a page is not re-rendered.
+const PDF_RERENDER_THRESHOLD: f64 = 0.1;
 
 /// PDF thumbnail size multiplier (0.25 = 25% for fast preview generation).
 const PDF_THUMBNAIL_SIZE: f64 = 0.25;
 
 use cairo::{Context, Format, ImageSurface};
+use glib::translate::{ToGlibPtr, ToGlibPtrMut, from_glib_full};
 use image::{DynamicImage, GenericImageView, ImageReader};
-use poppler::PopplerDocument;
+use poppler::{Document as PopplerDocument, IndexIter, Page as PopplerPage, Permissions, ffi};
 
 use cosmic::widget::image::Handle as ImageHandle;
 
 use crate::domain::document::core::document::{
-    DocResult, DocumentInfo, FlipDirection, MultiPage, MultiPageThumbnails, Renderable,
-    RenderOutput, Rotation, RotationMode, TransformState, Transformable,
+    DocResult, DocumentInfo, FlipDirection, MultiPage, MultiPageThumbnails, PasswordError,
+    Renderable, RenderOutput, Rotation, RotationMode, SearchableText, TransformState,
+    Transformable,
 };
+use crate::domain::document::core::metadata::{PageSize, PdfMeta, PdfPermissions};
+use crate::domain::document::core::page::{LinkTarget, OutlineEntry, PageLayout, PageLink};
+use crate::domain::document::core::tags;
+use crate::domain::document::operations::render::{self, PageRenderJob, RenderedPage};
+use crate::domain::document::operations::search::{self, SearchHit, SearchJob, TextRect};
 
 /// Represents a portable document (PDF).
 pub struct PortableDocument {
@@ -29,50 +48,335 @@ pub struct PortableDocument {
     document: PopplerDocument,
     /// Path to the source file (for caching).
     source_path: PathBuf,
+    /// Password the document was unlocked with (for background renders).
+    password: Option<String>,
     /// Total number of pages.
     num_pages: usize,
+    /// Unrotated size of each page in PDF points.
+    page_points: Vec<(f64, f64)>,
     /// Current page index (0-based).
     page_index: usize,
+    /// Spread, reading direction and continuous view.
+    layout: PageLayout,
     /// Current transformation state.
     transform: TransformState,
+    /// Resolution of `rendered` in pixels per PDF point.
+    render_scale: f64,
     /// Current rendered page as image.
     pub rendered: DynamicImage,
     /// Image handle for display.
     pub handle: ImageHandle,
+    /// Pages rendered for the continuous view, with their render scale.
+    page_rasters: HashMap<usize, (f64, ImageHandle)>,
     /// Cached thumbnail handles for each page (None = not yet generated).
     thumbnail_cache: Option<Vec<ImageHandle>>,
+    /// Document outline (table of contents).
+    outline: Vec<OutlineEntry>,
+    /// Links on the pages in view.
+    links: Vec<PageLink>,
 }
 
 impl PortableDocument {
     /// Open a PDF document and render the first page.
-    pub fn open(path: &Path) -> anyhow::Result<Self> {
-        let document = PopplerDocument::new_from_file(path, None)
-            .map_err(|e| anyhow::anyhow!("Failed to parse PDF: {e}"))?;
+    ///
+    /// `password` unlocks encrypted documents. Fails with a `PasswordError`
+    /// if the document is encrypted and the password is missing or wrong.
+    pub fn open(path: &Path, password: Option<&str>) -> anyhow::Result<Self> {
+        let document = Self::load_document(path, password)?;
 
-        let num_pages = document.get_n_pages();
+        let num_pages = usize::try_from(document.n_pages()).unwrap_or(0);
         if num_pages == 0 {
             return Err(anyhow::anyhow!("PDF has no pages"));
         }
 
-        let rendered = Self::render_page(&document, 0, RotationMode::Standard(Rotation::None))?;
+        let page_points = (0..num_pages)
+            .map(|index| Self::page(&document, index).map_or((0.0, 0.0), |page| page.size()))
+            .collect::<Vec<_>>();
+
+        let transform = TransformState::default();
+        let render_scale = Self::clamp_render_scale(&page_points, 0..1, PDF_BASE_SCALE);
+        let rendered = Self::render_transformed(&document, 0, transform, render_scale)?;
         let handle = Self::create_image_handle_from_image(&rendered);
+        let outline = Self::read_outline(&document);
+        let links = Self::read_links(&document, 0);
 
         Ok(Self {
             document,
             source_path: path.to_path_buf(),
+            password: password.map(str::to_string),
             num_pages,
+            page_points,
             page_index: 0,
-            transform: TransformState::default(),
+            layout: PageLayout::default(),
+            transform,
+            render_scale,
             rendered,
             handle,
+            page_rasters: HashMap::new(),
             thumbnail_cache: None,
+            outline,
+            links,
         })
     }
 
-    /// Returns the current pixel dimensions (width, height).
+    /// Current spread, reading direction and continuous view.
+    #[must_use]
+    pub fn layout(&self) -> PageLayout {
+        self.layout
+    }
+
+    /// Change the page arrangement, staying on the current page.
+    pub fn set_layout(&mut self, layout: PageLayout) -> DocResult<()> {
+        if layout == self.layout {
+            return Ok(());
+        }
+
+        self.layout = layout;
+        self.page_index = self.visible_pages().start;
+        self.page_rasters.clear();
+        self.rerender();
+        Ok(())
+    }
+
+    /// Pages on screen, in reading order.
+    #[must_use]
+    pub fn visible_pages(&self) -> Range<usize> {
+        self.layout.visible_pages(self.page_index, self.num_pages)
+    }
+
+    /// Size of each page at 100% zoom as shown, with rotation applied.
+    #[must_use]
+    pub fn page_sizes(&self) -> Vec<(f64, f64)> {
+        let quarter_turn = matches!(
+            self.transform.rotation,
+            RotationMode::Standard(Rotation::Cw90 | Rotation::Cw270)
+        );
+        self.page_points
+            .iter()
+            .map(|&(width, height)| {
+                let (width, height) = if quarter_turn {
+                    (height, width)
+                } else {
+                    (width, height)
+                };
+                (width * PDF_BASE_SCALE, height * PDF_BASE_SCALE)
+            })
+            .collect()
+    }
+
+    /// Links on the pages in view with their bounds on the displayed page.
+    #[must_use]
+    pub fn links(&self) -> Vec<(TextRect, LinkTarget)> {
+        self.links
+            .iter()
+            .filter_map(|link| {
+                Some((
+                    self.place_on_view(link.page, link.rect)?,
+                    link.target.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Rendered page for the continuous view (None until rendered).
+    #[must_use]
+    pub fn page_raster(&self, page: usize) -> Option<ImageHandle> {
+        self.page_rasters
+            .get(&page)
+            .map(|(_, handle)| handle.clone())
+    }
+
+    /// Prepare renders of `pages` for the continuous view at `zoom`.
+    ///
+    /// `zoom` is relative to `page_sizes()`. Pages whose raster already has
+    /// about the right resolution are skipped.
+    #[must_use]
+    pub fn page_render_jobs(&self, pages: Range<usize>, zoom: f64) -> Vec<PageRenderJob> {
+        if !self.layout.continuous {
+            return Vec::new();
+        }
+
+        pages
+            .filter(|&page| page < self.num_pages)
+            .filter_map(|page| {
+                let scale = Self::clamp_render_scale(
+                    &self.page_points,
+                    page..page + 1,
+                    zoom * PDF_BASE_SCALE,
+                );
+                if let Some(&(current, _)) = self.page_rasters.get(&page)
+                    && ((scale - current) / current).abs() < PDF_RERENDER_THRESHOLD
+                {
+                    return None;
+                }
+
+                Some(PageRenderJob {
+                    path: self.source_path.clone(),
+                    password: self.password.clone(),
+                    page,
+                    layout: self.layout,
+                    transform: self.transform,
+                    scale,
+                })
+            })
+            .collect()
+    }
+
+    /// Forget the continuous view rasters of pages outside `pages`.
+    pub fn retain_page_rasters(&mut self, pages: Range<usize>) {
+        self.page_rasters.retain(|page, _| pages.contains(page));
+    }
+
+    /// Returns the current page dimensions (width, height) at 100% zoom.
+    ///
+    /// The page raster itself may be larger or smaller, depending on the
+    /// zoom it was rendered for (see `pixel_density()`).
     #[must_use]
     pub fn dimensions(&self) -> (u32, u32) {
-        self.rendered.dimensions()
+        let (width, height) = self.rendered.dimensions();
+        let density = self.render_scale / PDF_BASE_SCALE;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        (
+            (f64::from(width) / density).round() as u32,
+            (f64::from(height) / density).round() as u32,
+        )
+    }
+
+    /// Raster pixels per logical pixel of the current page render.
+    #[must_use]
+    pub fn pixel_density(&self) -> f32 {
+        #[allow(clippy::cast_possible_truncation)]
+        let density = (self.render_scale / PDF_BASE_SCALE) as f32;
+        density
+    }
+
+    /// Prepare a re-render of the current page (or spread) for the given zoom.
+    ///
+    /// `zoom` is the displayed size relative to `dimensions()`, multiplied
+    /// by the device scale factor. Returns `None` if the current raster
+    /// already has about the right resolution, and in the continuous view
+    /// (see `page_render_jobs()`).
+    #[must_use]
+    pub fn render_job(&self, zoom: f64) -> Option<PageRenderJob> {
+        if self.layout.continuous {
+            return None;
+        }
+
+        let scale = Self::clamp_render_scale(
+            &self.page_points,
+            self.visible_pages(),
+            zoom * PDF_BASE_SCALE,
+        );
+        if ((scale - self.render_scale) / self.render_scale).abs() < PDF_RERENDER_THRESHOLD {
+            return None;
+        }
+
+        Some(PageRenderJob {
+            path: self.source_path.clone(),
+            password: self.password.clone(),
+            page: self.page_index,
+            layout: self.layout,
+            transform: self.transform,
+            scale,
+        })
+    }
+
+    /// Render a page for a `PageRenderJob`.
+    ///
+    /// Poppler documents cannot be shared between threads, so the worker
+    /// opens its own copy of the file.
+    pub fn run_render_job(job: PageRenderJob) -> DocResult<RenderedPage> {
+        let document = Self::load_document(&job.path, job.password.as_deref())?;
+        let image = Self::render_view(&document, job.page, job.layout, job.transform, job.scale)?;
+        let handle = Self::create_image_handle_from_image(&image);
+
+        Ok(RenderedPage { job, image, handle })
+    }
+
+    /// Prepare a background search for `query`.
+    #[must_use]
+    pub fn search_job(&self, query: &str) -> SearchJob {
+        SearchJob {
+            path: self.source_path.clone(),
+            password: self.password.clone(),
+            query: query.to_string(),
+        }
+    }
+
+    /// Search the document for a `SearchJob` on a worker thread.
+    pub fn run_search_job(job: &SearchJob) -> DocResult<Vec<SearchHit>> {
+        let document = Self::load_document(&job.path, job.password.as_deref())?;
+        Self::find_text(&document, &job.query)
+    }
+
+    /// Find all case-insensitive matches of `query` on every page of
+    /// `document`, in document order.
+    fn find_text(document: &PopplerDocument, query: &str) -> DocResult<Vec<SearchHit>> {
+        let mut hits = Vec::new();
+        if query.trim().is_empty() {
+            return Ok(hits);
+        }
+
+        let num_pages = usize::try_from(document.n_pages()).unwrap_or(0);
+        for page_index in 0..num_pages {
+            let page = Self::page(document, page_index)
+                .ok_or_else(|| anyhow::anyhow!("Failed to get page {page_index}"))?;
+            let (page_width, page_height) = page.size();
+
+            // Poppler reports matches with the origin at the bottom-left.
+            for found in page.find_text(query) {
+                let rect = TextRect::from_corners(
+                    found.x1(),
+                    page_height - found.y2(),
+                    found.x2(),
+                    page_height - found.y1(),
+                );
+
+                // The whole line around the match, for the hit list.
+                let mut line = poppler::Rectangle::new();
+                line.set_x1(0.0);
+                line.set_y1(rect.y);
+                line.set_x2(page_width);
+                line.set_y2(rect.y + rect.height);
+                let context = page
+                    .text_for_area(&mut line)
+                    .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .unwrap_or_default();
+
+                hits.push(SearchHit {
+                    page: page_index,
+                    rect,
+                    context,
+                });
+            }
+        }
+
+        Ok(hits)
+    }
+
+    /// Replace the page raster with a background render.
+    ///
+    /// Returns `false` (and keeps the current raster) if the page, the
+    /// layout or the transformation changed while the job was running. In
+    /// the continuous view the render is kept for its page.
+    pub fn apply_rendered_page(&mut self, page: RenderedPage) -> bool {
+        if page.job.layout != self.layout || page.job.transform != self.transform {
+            return false;
+        }
+        if self.layout.continuous {
+            self.page_rasters
+                .insert(page.job.page, (page.job.scale, page.handle));
+            return true;
+        }
+        if page.job.page != self.page_index {
+            return false;
+        }
+
+        self.render_scale = page.job.scale;
+        self.rendered = page.image;
+        self.handle = page.handle;
+        true
     }
 
     /// Get the current image handle.
@@ -84,7 +388,7 @@ impl PortableDocument {
     /// Get native dimensions of current page.
     #[must_use]
     pub fn native_dimensions(&self) -> (u32, u32) {
-        self.rendered.dimensions()
+        self.dimensions()
     }
 
     /// Get the number of thumbnails currently loaded.
@@ -127,9 +431,62 @@ impl PortableDocument {
             height,
             file_size,
             color_type: "Rendered".to_string(),
+            format_mismatch: None,
         };
 
-        DocumentMeta { basic, exif: None }
+        DocumentMeta {
+            basic,
+            exif: None,
+            pdf: Some(self.pdf_meta()),
+            tags: self
+                .document
+                .metadata()
+                .map(|xmp| tags::xmp_tags(xmp.as_bytes()))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Read the document information dictionary, security and page sizes.
+    fn pdf_meta(&self) -> PdfMeta {
+        let text = |value: Option<glib::GString>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let date = |value: Option<glib::DateTime>| {
+            value
+                .and_then(|date| date.format("%Y-%m-%d %H:%M").ok())
+                .map(String::from)
+        };
+
+        let permissions = self.document.permissions();
+
+        PdfMeta {
+            title: text(self.document.title()),
+            author: text(self.document.author()),
+            subject: text(self.document.subject()),
+            keywords: text(self.document.keywords()),
+            creator: text(self.document.creator()),
+            producer: text(self.document.producer()),
+            creation_date: date(self.document.creation_date_time()),
+            modification_date: date(self.document.modification_date_time()),
+            version: text(self.document.pdf_version_string()),
+            // poppler does not tell whether a document is encrypted; it needed
+            // a password or restricts something only if it is.
+            encrypted: self.password.is_some() || !permissions.contains(Permissions::FULL),
+            permissions: PdfPermissions {
+                print: permissions.contains(Permissions::OK_TO_PRINT),
+                modify: permissions.contains(Permissions::OK_TO_MODIFY),
+                copy: permissions.contains(Permissions::OK_TO_COPY),
+                annotate: permissions.contains(Permissions::OK_TO_ADD_NOTES),
+                fill_forms: permissions.contains(Permissions::OK_TO_FILL_FORM),
+            },
+            page_sizes: self
+                .page_points
+                .iter()
+                .map(|&(width, height)| PageSize { width, height })
+                .collect(),
+        }
     }
 
     /// Crop the current page to the specified rectangle.
@@ -161,6 +518,178 @@ impl PortableDocument {
 
         Ok(())
     }
+
+    /// Load a PDF file with poppler.
+    fn load_document(path: &Path, password: Option<&str>) -> DocResult<PopplerDocument> {
+        let uri = glib::filename_to_uri(path, None)
+            .map_err(|e| anyhow::anyhow!("Invalid PDF path {}: {e}", path.display()))?;
+
+        PopplerDocument::from_file(&uri, password).map_err(|e| {
+            // Poppler reports a missing and a wrong password the same way.
+            if e.matches(poppler::Error::Encrypted) {
+                if password.is_some() {
+                    PasswordError::Incorrect.into()
+                } else {
+                    PasswordError::Required.into()
+                }
+            } else {
+                anyhow::anyhow!("Failed to parse PDF: {e}")
+            }
+        })
+    }
+
+    /// Get a page by its 0-based index.
+    fn page(document: &PopplerDocument, page_index: usize) -> Option<PopplerPage> {
+        document.page(i32::try_from(page_index).ok()?)
+    }
+
+    /// Read the document outline (table of contents).
+    fn read_outline(document: &PopplerDocument) -> Vec<OutlineEntry> {
+        // SAFETY: `poppler_index_iter_new` returns a new iterator or NULL
+        // for documents without an outline.
+        let iter: Option<IndexIter> =
+            unsafe { from_glib_full(ffi::poppler_index_iter_new(document.to_glib_none().0)) };
+
+        iter.map(|mut iter| Self::read_outline_level(document, &mut iter))
+            .unwrap_or_default()
+    }
+
+    /// Read the entries of one outline level, including their children.
+    fn read_outline_level(document: &PopplerDocument, iter: &mut IndexIter) -> Vec<OutlineEntry> {
+        let mut entries = Vec::new();
+        loop {
+            let (title, page) = Self::outline_action(document, iter);
+            let children = iter
+                .child()
+                .map(|mut child| Self::read_outline_level(document, &mut child))
+                .unwrap_or_default();
+
+            entries.push(OutlineEntry {
+                title,
+                page,
+                open: iter.is_open(),
+                children,
+            });
+
+            if !iter.next() {
+                return entries;
+            }
+        }
+    }
+
+    /// Title and target page of the outline entry at `iter`.
+    ///
+    /// Poppler's action type is a C union without safe bindings.
+    fn outline_action(document: &PopplerDocument, iter: &mut IndexIter) -> (String, Option<usize>) {
+        // SAFETY: the action is owned by us and freed below. The union is
+        // only read through the variant that matches its type tag.
+        unsafe {
+            let action = ffi::poppler_index_iter_get_action(iter.to_glib_none_mut().0);
+            if action.is_null() {
+                return (String::new(), None);
+            }
+
+            let title = c_string((*action).any.title);
+            let page = if (*action).type_ == ffi::POPPLER_ACTION_GOTO_DEST {
+                Self::dest_page(document, (*action).goto_dest.dest)
+            } else {
+                None
+            };
+
+            ffi::poppler_action_free(action);
+            (title.unwrap_or_default(), page)
+        }
+    }
+
+    /// Read the links of a page.
+    fn read_links(document: &PopplerDocument, page_index: usize) -> Vec<PageLink> {
+        let Some(page) = Self::page(document, page_index) else {
+            return Vec::new();
+        };
+        let (_, page_height) = page.size();
+
+        let mut links = Vec::new();
+        // SAFETY: the mapping list is owned by us and freed below. Its nodes
+        // hold `PopplerLinkMapping`s with actions owned by the list.
+        unsafe {
+            let list = ffi::poppler_page_get_link_mapping(page.to_glib_none().0);
+            let mut node = list;
+            while !node.is_null() {
+                let mapping = (*node).data.cast::<ffi::PopplerLinkMapping>();
+                if let Some(target) = Self::link_target(document, (*mapping).action) {
+                    // Poppler reports areas with the origin at the bottom-left.
+                    let area = (*mapping).area;
+                    links.push(PageLink {
+                        page: page_index,
+                        rect: TextRect::from_corners(
+                            area.x1,
+                            page_height - area.y2,
+                            area.x2,
+                            page_height - area.y1,
+                        ),
+                        target,
+                    });
+                }
+                node = (*node).next;
+            }
+            ffi::poppler_page_free_link_mapping(list);
+        }
+        links
+    }
+
+    /// Where a link action leads (None for unsupported actions).
+    ///
+    /// # Safety
+    ///
+    /// `action` must be NULL or point to a valid `PopplerAction`.
+    unsafe fn link_target(
+        document: &PopplerDocument,
+        action: *const ffi::PopplerAction,
+    ) -> Option<LinkTarget> {
+        if action.is_null() {
+            return None;
+        }
+
+        // SAFETY: checked for NULL above. The union is only read through
+        // the variant that matches its type tag.
+        unsafe {
+            match (*action).type_ {
+                ffi::POPPLER_ACTION_GOTO_DEST => {
+                    Self::dest_page(document, (*action).goto_dest.dest).map(LinkTarget::Page)
+                }
+                ffi::POPPLER_ACTION_URI => c_string((*action).uri.uri).map(LinkTarget::Uri),
+                _ => None,
+            }
+        }
+    }
+
+    /// Resolve a link destination to a 0-based page index.
+    ///
+    /// # Safety
+    ///
+    /// `dest` must be NULL or point to a valid `PopplerDest`.
+    unsafe fn dest_page(
+        document: &PopplerDocument,
+        dest: *const ffi::PopplerDest,
+    ) -> Option<usize> {
+        if dest.is_null() {
+            return None;
+        }
+
+        // SAFETY: checked for NULL above, valid per the caller's contract.
+        let dest = unsafe { &*dest };
+        if dest.type_ == ffi::POPPLER_DEST_NAMED {
+            // SAFETY: `named_dest` is NULL or a NUL-terminated string.
+            let name = unsafe { c_string(dest.named_dest) }?;
+            let named = document.find_dest(&name)?;
+            // SAFETY: `named` is a valid destination owned by us.
+            return unsafe { Self::dest_page(document, named.as_ptr()) };
+        }
+
+        // Poppler counts pages from 1.
+        usize::try_from(dest.page_num).ok()?.checked_sub(1)
+    }
+
     fn create_image_handle_from_image(img: &DynamicImage) -> ImageHandle {
         let (width, height) = img.dimensions();
         let pixels = img.to_rgba8().into_raw();
@@ -226,13 +755,78 @@ impl PortableDocument {
         }
     }
 
-    /// Render a specific page from the document to an image.
-    fn render_page(
+    /// Render a specific page with rotation and flips applied.
+    fn render_transformed(
         document: &PopplerDocument,
         page_index: usize,
-        rotation: RotationMode,
+        transform: TransformState,
+        scale: f64,
     ) -> anyhow::Result<DynamicImage> {
-        Self::render_page_at_scale(document, page_index, rotation, PDF_RENDER_QUALITY)
+        let mut rendered =
+            Self::render_page_at_scale(document, page_index, transform.rotation, scale)?;
+
+        // Apply flip transformations to the rendered result
+        if transform.flip_h {
+            rendered = Self::apply_flip(rendered, FlipDirection::Horizontal);
+        }
+        if transform.flip_v {
+            rendered = Self::apply_flip(rendered, FlipDirection::Vertical);
+        }
+        Ok(rendered)
+    }
+
+    /// Limit a render scale (pixels per point) to sane raster sizes.
+    ///
+    /// `pages` are shown side by side, so their widths add up.
+    fn clamp_render_scale(page_points: &[(f64, f64)], pages: Range<usize>, scale: f64) -> f64 {
+        let pages = page_points.get(pages).unwrap_or_default();
+        let width: f64 = pages.iter().map(|&(width, _)| width).sum();
+        let height = pages.iter().map(|&(_, height)| height).fold(0.0, f64::max);
+        let longest_side = width.max(height).max(1.0);
+
+        let max_scale = (PDF_MAX_RENDER_SIZE / longest_side).max(PDF_MIN_RENDER_SCALE);
+        scale.clamp(PDF_MIN_RENDER_SCALE, max_scale)
+    }
+
+    /// Render the pages shown with `page` in `layout`.
+    ///
+    /// Pages of a spread are composed first, so that rotation and flips
+    /// apply to the spread as a whole.
+    fn render_view(
+        document: &PopplerDocument,
+        page_index: usize,
+        layout: PageLayout,
+        transform: TransformState,
+        scale: f64,
+    ) -> anyhow::Result<DynamicImage> {
+        let num_pages = usize::try_from(document.n_pages()).unwrap_or(0);
+        let pages = layout.visible_pages(page_index, num_pages);
+        if pages.len() < 2 {
+            return Self::render_transformed(document, page_index, transform, scale);
+        }
+
+        let unrotated = RotationMode::Standard(Rotation::None);
+        let first = Self::render_page_at_scale(document, pages.start, unrotated, scale)?;
+        let second = Self::render_page_at_scale(document, pages.start + 1, unrotated, scale)?;
+        let mut spread = if layout.right_to_left {
+            render::compose_spread(&second, &first)
+        } else {
+            render::compose_spread(&first, &second)
+        };
+
+        spread = match transform.rotation {
+            RotationMode::Standard(Rotation::Cw90) => spread.rotate90(),
+            RotationMode::Standard(Rotation::Cw180) => spread.rotate180(),
+            RotationMode::Standard(Rotation::Cw270) => spread.rotate270(),
+            _ => spread,
+        };
+        if transform.flip_h {
+            spread = Self::apply_flip(spread, FlipDirection::Horizontal);
+        }
+        if transform.flip_v {
+            spread = Self::apply_flip(spread, FlipDirection::Vertical);
+        }
+        Ok(spread)
     }
 
     /// Render a specific page at a given scale.
@@ -242,11 +836,10 @@ impl PortableDocument {
         rotation: RotationMode,
         scale: f64,
     ) -> anyhow::Result<DynamicImage> {
-        let page = document
-            .get_page(page_index)
+        let page = Self::page(document, page_index)
             .ok_or_else(|| anyhow::anyhow!("Failed to get page {page_index}"))?;
 
-        let (page_width, page_height) = page.get_size();
+        let (page_width, page_height) = page.size();
         let rotation_degrees = rotation.to_degrees() as i16;
 
         let (width, height) = if rotation_degrees == 90 || rotation_degrees == 270 {
@@ -299,19 +892,28 @@ impl PortableDocument {
         Ok(image)
     }
 
-    /// Re-render the current page with current transform.
+    /// Re-render the current page (or spread) with current transform.
+    ///
+    /// Keeps the resolution of the previous render, so a page change does
+    /// not fall back to a blurry raster while zoomed in.
     fn rerender(&mut self) {
-        match Self::render_page(&self.document, self.page_index, self.transform.rotation) {
-            Ok(mut rendered) => {
-                // Apply flip transformations to the rendered result
-                if self.transform.flip_h {
-                    rendered = Self::apply_flip(rendered, FlipDirection::Horizontal);
-                }
-                if self.transform.flip_v {
-                    rendered = Self::apply_flip(rendered, FlipDirection::Vertical);
-                }
+        let scale =
+            Self::clamp_render_scale(&self.page_points, self.visible_pages(), self.render_scale);
+        match Self::render_view(
+            &self.document,
+            self.page_index,
+            self.layout,
+            self.transform,
+            scale,
+        ) {
+            Ok(rendered) => {
+                self.render_scale = scale;
                 self.rendered = rendered;
                 self.handle = Self::create_image_handle_from_image(&self.rendered);
+                self.links = self
+                    .visible_pages()
+                    .flat_map(|page| Self::read_links(&self.document, page))
+                    .collect();
             }
             Err(e) => {
                 log::error!("Failed to render PDF page: {e}");
@@ -319,6 +921,46 @@ impl PortableDocument {
         }
     }
 
+    /// Map a rectangle on an unrotated page in view onto the displayed
+    /// page or spread (None if the page is not in view).
+    fn place_on_view(&self, page: usize, rect: TextRect) -> Option<TextRect> {
+        let pages = self.visible_pages();
+        if !pages.contains(&page) {
+            return None;
+        }
+
+        // Pages of a spread are scaled to the same height and placed side
+        // by side before the view is rotated and flipped.
+        let height = self.page_points[pages.clone()]
+            .iter()
+            .map(|&(_, height)| height)
+            .fold(0.0, f64::max);
+        let mut order: Vec<usize> = pages.collect();
+        if self.layout.right_to_left {
+            order.reverse();
+        }
+
+        let mut width = 0.0;
+        let mut placement = None;
+        for index in order {
+            let (page_width, page_height) = self.page_points[index];
+            let factor = height / page_height;
+            if index == page {
+                placement = Some((width, factor));
+            }
+            width += page_width * factor;
+        }
+
+        let (offset, factor) = placement?;
+        let rect = rect.scaled(factor);
+        let rect = TextRect {
+            x: rect.x + offset,
+            ..rect
+        };
+        let rect = search::map_to_display(rect, (width, height), self.transform);
+        Some(rect.scaled(PDF_BASE_SCALE))
+    }
+
     fn apply_flip(img: DynamicImage, direction: FlipDirection) -> DynamicImage {
         use image::imageops::{flip_horizontal, flip_vertical};
         match direction {
@@ -358,8 +1000,9 @@ impl PortableDocument {
 
 impl Renderable for PortableDocument {
     fn render(&mut self, _scale: f64) -> DocResult<RenderOutput> {
-        // PDF rendering quality is fixed for now (PDF_RENDER_QUALITY)
-        let (width, height) = self.dimensions();
+        // Zoom-dependent resolution is rendered in the background through
+        // `render_job()`, here we hand out the latest raster.
+        let (width, height) = self.rendered.dimensions();
         Ok(RenderOutput {
             handle: self.handle.clone(),
             width,
@@ -380,6 +1023,7 @@ impl Renderable for PortableDocument {
 impl Transformable for PortableDocument {
     fn rotate(&mut self, rotation: Rotation) {
         self.transform.rotation = RotationMode::Standard(rotation);
+        self.page_rasters.clear();
         self.rerender();
     }
 
@@ -388,6 +1032,7 @@ impl Transformable for PortableDocument {
             FlipDirection::Horizontal => self.transform.flip_h = !self.transform.flip_h,
             FlipDirection::Vertical => self.transform.flip_v = !self.transform.flip_v,
         }
+        self.page_rasters.clear();
         self.rerender();
     }
 
@@ -413,10 +1058,24 @@ impl MultiPage for PortableDocument {
                 self.num_pages - 1
             ));
         }
-        self.page_index = page;
-        self.rerender();
+        self.page_index = self.layout.visible_pages(page, self.num_pages).start;
+        // The continuous view renders its pages separately, scrolling
+        // through it only moves the current page.
+        if !self.layout.continuous {
+            self.rerender();
+        }
         Ok(())
     }
+
+    fn outline(&self) -> &[OutlineEntry] {
+        &self.outline
+    }
+}
+
+impl SearchableText for PortableDocument {
+    fn hit_bounds(&self, hit: &SearchHit) -> Option<TextRect> {
+        self.place_on_view(hit.page, hit.rect)
+    }
 }
 
 impl MultiPageThumbnails for PortableDocument {
@@ -454,3 +1113,17 @@ impl MultiPageThumbnails for PortableDocument {
             .and_then(|cache| cache.get(page).cloned()))
     }
 }
+
+/// Copy a C string owned by poppler.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or point to a NUL-terminated string.
+unsafe fn c_string(ptr: *const std::ffi::c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    // SAFETY: checked for NULL above, NUL-terminated per the caller's contract.
+    let text = unsafe { CStr::from_ptr(ptr) };
+    Some(text.to_string_lossy().into_owned())
+}
@@ -3,16 +3,75 @@
 //
 // Raster image document support (PNG, JPEG, WebP, etc.).
 
-use std::path::Path;
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
-use image::{DynamicImage, GenericImageView, ImageReader};
+/// Longest side of a page thumbnail in pixels (multi-page TIFF, animations).
+const THUMBNAIL_SIZE: u32 = 200;
+
+/// Shortest frame delay honoured; browsers treat faster GIFs the same way.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// Frame delay used instead of delays below `MIN_FRAME_DELAY`.
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// Longest side of the downscaled copy a fine rotation is previewed on.
+const FINE_ROTATION_PREVIEW_SIZE: u32 = 1024;
+
+/// Most memory the decoded frames of an animation may take (a 1080p frame
+/// takes about 8 MiB); longer animations play their first frames only.
+const ANIMATION_MEMORY_LIMIT: usize = 512 * 1024 * 1024;
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{
+    AnimationDecoder, DynamicImage, Frames, GenericImageView, ImageFormat, ImageReader, RgbaImage,
+};
 
 use cosmic::widget::image::Handle as ImageHandle;
 
 use crate::domain::document::core::document::{
-    DocResult, DocumentInfo, FlipDirection, InterpolationQuality, Renderable, RenderOutput,
-    Rotation, RotationMode, TransformState, Transformable,
+    DocResult, DocumentInfo, FlipDirection, InterpolationQuality, MultiPage, MultiPageThumbnails,
+    Renderable, RenderOutput, Rotation, RotationMode, TransformState, Transformable,
 };
+use crate::domain::document::core::format::DetectedFormat;
+use crate::domain::document::core::metadata::ExifMeta;
+use crate::domain::document::operations::transform::{FineRotationJob, RotatedImage};
+use crate::domain::document::operations::{container, transform};
+
+/// A frame of an animated image.
+struct AnimationFrame {
+    /// Composited frame (full canvas).
+    image: RgbaImage,
+    /// How long the frame is shown.
+    delay: Duration,
+}
+
+/// Images of a raster document with more than one of them.
+enum RasterPages {
+    /// A single still image.
+    Single,
+    /// Directory offsets of the pages of a multi-page TIFF, decoded on demand.
+    Tiff(Vec<u32>),
+    /// Frames of an animated GIF, APNG or WebP, decoded up front up to
+    /// `ANIMATION_MEMORY_LIMIT`.
+    Animation(Vec<AnimationFrame>),
+}
+
+impl RasterPages {
+    /// Number of pages or frames.
+    fn len(&self) -> usize {
+        match self {
+            Self::Single => 1,
+            Self::Tiff(offsets) => offsets.len(),
+            Self::Animation(frames) => frames.len(),
+        }
+    }
+}
 
 /// Represents a raster image document (PNG, JPEG, WebP, ...).
 pub struct RasterDocument {
@@ -26,28 +85,189 @@ pub struct RasterDocument {
     transform: TransformState,
     /// Cached handle for rendering.
     handle: ImageHandle,
-    /// Accumulated fine rotation angle in degrees.
+    /// Fine rotation angle in degrees, applied on top of `transform`.
     fine_rotation_angle: f32,
+    /// Pixels underneath the fine rotation, so that every new angle is
+    /// resampled from the same source instead of piling up blur.
+    unrotated: Option<Arc<DynamicImage>>,
+    /// Downscaled copy of `unrotated` for previews while the angle changes.
+    preview_source: Option<DynamicImage>,
+    /// Whether `handle` shows a preview of the fine rotation that `document`
+    /// does not have yet (see `preview_fine_rotation`).
+    rotation_pending: bool,
     /// Interpolation quality for fine rotation and resize operations.
     interpolation_quality: InterpolationQuality,
+    /// Crop fine rotations to the largest rectangle without empty corners.
+    auto_crop: bool,
+    /// Whether the pixels are still the decoded source up to `transform`
+    /// (no crop, resize or fine rotation applied).
+    source_pixels: bool,
+    /// Source file, read again when switching pages.
+    source_path: PathBuf,
+    /// Pages of multi-page TIFFs or frames of animations.
+    pages: RasterPages,
+    /// Current page index (0-based).
+    page_index: usize,
+    /// Display handles of the animation frames with `frame_transform`
+    /// applied, built as the frames are first shown.
+    frame_handles: Vec<Option<ImageHandle>>,
+    /// Rotation and flips of the cached frame handles.
+    frame_transform: TransformState,
+    /// Whether `handle` shows an animation frame whose pixels are not in
+    /// `document` yet (see `show_frame`).
+    frame_pending: bool,
+    /// Cached thumbnail handles for each page (None = not yet generated).
+    thumbnail_cache: Option<Vec<ImageHandle>>,
 }
 
 impl RasterDocument {
     /// Load a raster document from disk.
-    pub fn open(path: &Path) -> image::ImageResult<Self> {
-        let document = ImageReader::open(path)?.decode()?;
-        let (native_width, native_height) = document.dimensions();
+    ///
+    /// The EXIF Orientation tag is applied right away and becomes the
+    /// initial transformation state. TIFF files with several pages open
+    /// on the first one, animations on their first frame.
+    pub fn open(path: &Path) -> DocResult<Self> {
+        let bytes = std::fs::read(path).ok();
+        // The content decides, the extension is only a hint for formats
+        // without magic bytes.
+        let format = bytes
+            .as_deref()
+            .and_then(DetectedFormat::sniff)
+            .and_then(DetectedFormat::image_format)
+            .or_else(|| ImageFormat::from_path(path).ok());
+
+        let pages = match (&bytes, format) {
+            (Some(bytes), Some(ImageFormat::Tiff)) => {
+                let offsets = container::tiff_page_offsets(bytes).unwrap_or_default();
+                if offsets.len() > 1 {
+                    RasterPages::Tiff(offsets)
+                } else {
+                    RasterPages::Single
+                }
+            }
+            (_, Some(format)) => match Self::decode_animation(path, format, ANIMATION_MEMORY_LIMIT)
+            {
+                Ok(Some(frames)) => RasterPages::Animation(frames),
+                Ok(None) => RasterPages::Single,
+                Err(e) => {
+                    log::warn!("Failed to decode animation frames, showing a still: {e}");
+                    RasterPages::Single
+                }
+            },
+            _ => RasterPages::Single,
+        };
+
+        let document = match (&bytes, &pages) {
+            (Some(bytes), RasterPages::Tiff(offsets)) => Self::decode_page(bytes, offsets[0])?,
+            (_, RasterPages::Animation(frames)) => {
+                DynamicImage::ImageRgba8(frames[0].image.clone())
+            }
+            _ => {
+                let mut reader = ImageReader::open(path)?;
+                if let Some(format) = format {
+                    reader.set_format(format);
+                }
+                reader.decode()?
+            }
+        };
+
+        let transform = bytes
+            .as_deref()
+            .map_or_else(TransformState::default, Self::exif_transform);
+
+        Ok(Self::from_image(path, document, pages, transform))
+    }
+
+    /// Load the largest embedded JPEG preview of a TIFF-based camera RAW
+    /// file (DNG, CR2, NEF, ARW, ...).
+    ///
+    /// The previews are stored in sensor orientation, so the EXIF
+    /// Orientation tag of the RAW file is applied like in `open`.
+    pub fn open_raw_preview(path: &Path) -> DocResult<Self> {
+        let bytes = std::fs::read(path)?;
+        // Lossless JPEG sensor data fails to decode and is skipped.
+        let image = container::tiff_jpeg_previews(&bytes)?
+            .into_iter()
+            .find_map(|jpeg| image::load_from_memory_with_format(jpeg, ImageFormat::Jpeg).ok())
+            .ok_or_else(|| anyhow::anyhow!("No embedded preview found"))?;
+
+        let transform = Self::exif_transform(&bytes);
+        Ok(Self::from_image(
+            path,
+            image,
+            RasterPages::Single,
+            transform,
+        ))
+    }
+
+    /// Create a document from an image decoded outside of image-rs (HEIF,
+    /// JPEG XL).
+    ///
+    /// Those decoders already apply the orientation stored in the file, so
+    /// the EXIF Orientation tag is not applied again.
+    #[must_use]
+    pub fn from_decoded(path: &Path, image: DynamicImage) -> Self {
+        Self::from_image(path, image, RasterPages::Single, TransformState::default())
+    }
+
+    /// Create a document for a page decoded from a container (comic book
+    /// archives), shown with the rotation and flips of the previous page.
+    #[must_use]
+    pub fn from_page(path: &Path, image: DynamicImage, transform: TransformState) -> Self {
+        Self::from_image(path, image, RasterPages::Single, transform)
+    }
+
+    /// Initial transformation from the EXIF Orientation tag in `bytes`.
+    fn exif_transform(bytes: &[u8]) -> TransformState {
+        ExifMeta::from_bytes(bytes)
+            .and_then(|exif| exif.orientation)
+            .map_or_else(
+                TransformState::default,
+                TransformState::from_exif_orientation,
+            )
+    }
+
+    /// Build the document around the decoded first page.
+    fn from_image(
+        path: &Path,
+        image: DynamicImage,
+        pages: RasterPages,
+        transform: TransformState,
+    ) -> Self {
+        let (native_width, native_height) = image.dimensions();
+        let document = Self::apply_transform(image, transform);
         let handle = Self::create_image_handle_from_image(&document);
 
-        Ok(Self {
+        Self {
             document,
             native_width,
             native_height,
-            transform: TransformState::default(),
+            transform,
             handle,
             fine_rotation_angle: 0.0,
+            unrotated: None,
+            preview_source: None,
+            rotation_pending: false,
             interpolation_quality: InterpolationQuality::default(),
-        })
+            auto_crop: false,
+            source_pixels: true,
+            source_path: path.to_path_buf(),
+            pages,
+            page_index: 0,
+            frame_handles: Vec::new(),
+            frame_transform: transform,
+            frame_pending: false,
+            thumbnail_cache: None,
+        }
+    }
+
+    /// Get the transformation relative to the source file.
+    ///
+    /// Returns `None` once the pixels were edited beyond 90° rotations and
+    /// flips, i.e. when the source file can no longer be reused as-is.
+    #[must_use]
+    pub fn source_transform(&self) -> Option<TransformState> {
+        (self.source_pixels && self.unrotated.is_none()).then_some(self.transform)
     }
 
     /// Returns the current pixel dimensions (width, height) after transforms.
@@ -94,6 +314,8 @@ impl RasterDocument {
     ///
     /// Returns an error if the crop region is completely outside the image bounds.
     pub fn crop(&mut self, x: u32, y: u32, width: u32, height: u32) -> Result<(), String> {
+        self.settle_frame();
+        self.settle_fine_rotation();
         let (img_width, img_height) = self.document.dimensions();
 
         // Validate crop region
@@ -122,6 +344,9 @@ impl RasterDocument {
         // Reset transformations since we have a new "native" image
         self.transform = TransformState::default();
         self.fine_rotation_angle = 0.0;
+        self.unrotated = None;
+        self.preview_source = None;
+        self.source_pixels = false;
 
         // Regenerate handle
         self.handle = Self::create_image_handle_from_image(&self.document);
@@ -168,6 +393,7 @@ impl RasterDocument {
         path: &Path,
     ) -> crate::domain::document::core::metadata::DocumentMeta {
         use crate::domain::document::core::metadata::{BasicMeta, DocumentMeta, ExifMeta};
+        use crate::domain::document::core::tags;
 
         let file_name = path
             .file_name()
@@ -196,14 +422,20 @@ impl RasterDocument {
             height: self.native_height,
             file_size,
             color_type,
+            format_mismatch: None,
         };
 
-        // Try to extract EXIF data
-        let exif = std::fs::read(path)
-            .ok()
-            .and_then(|bytes| ExifMeta::from_bytes(&bytes));
+        // Try to extract EXIF data and the complete tag set
+        let bytes = std::fs::read(path).unwrap_or_default();
+        let exif = ExifMeta::from_bytes(&bytes);
+        let tags = tags::read_tags(&bytes);
 
-        DocumentMeta { basic, exif }
+        DocumentMeta {
+            basic,
+            exif,
+            pdf: None,
+            tags,
+        }
     }
 
     /// Resize the document to specific dimensions (for format conversion).
@@ -212,6 +444,9 @@ impl RasterDocument {
     pub fn resize_to_format(&mut self, target_width: u32, target_height: u32) {
         use image::imageops::FilterType;
 
+        self.settle_frame();
+        self.settle_fine_rotation();
+
         let filter = match self.interpolation_quality {
             InterpolationQuality::Fast => FilterType::Nearest,
             InterpolationQuality::Balanced => FilterType::Triangle,
@@ -221,32 +456,365 @@ impl RasterDocument {
         self.document = self
             .document
             .resize_exact(target_width, target_height, filter);
+
+        // The fine rotation is baked into the resized pixels.
+        self.fine_rotation_angle = 0.0;
+        self.unrotated = None;
+        self.preview_source = None;
+        self.source_pixels = false;
         self.handle = Self::create_image_handle_from_image(&self.document);
     }
 
+    /// Check if the image is an animation (frames are its pages).
+    #[must_use]
+    pub fn is_animated(&self) -> bool {
+        matches!(self.pages, RasterPages::Animation(_))
+    }
+
+    /// How long the current animation frame is shown (None for stills).
+    #[must_use]
+    pub fn frame_delay(&self) -> Option<Duration> {
+        let RasterPages::Animation(frames) = &self.pages else {
+            return None;
+        };
+        let delay = frames.get(self.page_index)?.delay;
+        Some(if delay < MIN_FRAME_DELAY {
+            DEFAULT_FRAME_DELAY
+        } else {
+            delay
+        })
+    }
+
+    /// Show an animation frame during playback.
+    ///
+    /// Only the display handle is swapped; it is built once per frame, and
+    /// again after the rotation or flips change. The pixels follow with
+    /// `settle_frame()` once playback stops.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame does not exist.
+    pub fn show_frame(&mut self, frame: usize) -> DocResult<()> {
+        // Frames start over without edits, which `go_to_page` takes care of.
+        let edited = self.unrotated.is_some() || !self.source_pixels;
+        if edited || !self.is_animated() || frame >= self.pages.len() {
+            return self.go_to_page(frame);
+        }
+
+        match self.cached_frame_handle(frame) {
+            Some(handle) => {
+                self.handle = handle;
+                self.page_index = frame;
+                self.frame_pending = true;
+                Ok(())
+            }
+            None => self.go_to_page(frame),
+        }
+    }
+
+    /// Whether the frame on screen waits for its pixels (see `show_frame`).
+    #[must_use]
+    pub fn frame_pending(&self) -> bool {
+        self.frame_pending
+    }
+
+    /// Copy the pixels of the frame on screen into the document.
+    pub fn settle_frame(&mut self) {
+        if self.frame_pending
+            && let Err(e) = self.go_to_page(self.page_index)
+        {
+            log::error!("Failed to show frame {}: {e}", self.page_index);
+        }
+    }
+
+    /// Display handle of an animation frame with the current rotation and
+    /// flips, if it was built before.
+    fn cached_frame_handle(&mut self, frame: usize) -> Option<ImageHandle> {
+        if self.frame_transform != self.transform {
+            self.frame_handles.clear();
+            self.frame_transform = self.transform;
+        }
+        self.frame_handles.get(frame).cloned().flatten()
+    }
+
+    /// Get count of thumbnails currently loaded.
+    #[must_use]
+    pub fn thumbnails_loaded(&self) -> usize {
+        self.thumbnail_cache.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Get thumbnail handle for a specific page (read-only access).
+    /// Returns None if the thumbnail hasn't been generated yet.
+    #[must_use]
+    pub fn get_thumbnail_handle(&self, page: usize) -> Option<ImageHandle> {
+        self.thumbnail_cache
+            .as_ref()
+            .and_then(|cache| cache.get(page).cloned())
+    }
+
     // Helper functions
+
+    /// Decode one page of a multi-page TIFF.
+    fn decode_page(data: &[u8], offset: u32) -> DocResult<DynamicImage> {
+        let mut data = data.to_vec();
+        container::tiff_select_page(&mut data, offset)?;
+        Ok(ImageReader::with_format(Cursor::new(data), ImageFormat::Tiff).decode()?)
+    }
+
+    /// Decode the frames of an animated GIF, APNG or WebP.
+    ///
+    /// Decoding stops before the frames take more than `limit` bytes.
+    /// Returns `None` for still images, including single-frame animations.
+    fn decode_animation(
+        path: &Path,
+        format: ImageFormat,
+        limit: usize,
+    ) -> DocResult<Option<Vec<AnimationFrame>>> {
+        let reader = BufReader::new(File::open(path)?);
+        let frames: Frames<'_> = match format {
+            ImageFormat::Gif => GifDecoder::new(reader)?.into_frames(),
+            ImageFormat::Png => {
+                let decoder = PngDecoder::new(reader)?;
+                if !decoder.is_apng()? {
+                    return Ok(None);
+                }
+                decoder.apng()?.into_frames()
+            }
+            ImageFormat::WebP => {
+                let decoder = WebPDecoder::new(reader)?;
+                if !decoder.has_animation() {
+                    return Ok(None);
+                }
+                decoder.into_frames()
+            }
+            _ => return Ok(None),
+        };
+
+        let mut decoded = Vec::new();
+        let mut size = 0;
+        for frame in frames {
+            let frame = frame?;
+            size += frame.buffer().as_raw().len();
+            if size > limit {
+                log::warn!(
+                    "Animation takes more than {} MiB, playing its first {} frames",
+                    limit / (1024 * 1024),
+                    decoded.len()
+                );
+                break;
+            }
+            decoded.push(AnimationFrame {
+                delay: frame.delay().into(),
+                image: frame.into_buffer(),
+            });
+        }
+        Ok((decoded.len() > 1).then_some(decoded))
+    }
+
+    /// Decode a page of a multi-page TIFF or copy an animation frame.
+    ///
+    /// `data` holds the file contents for TIFF pages.
+    fn page_image(&self, data: &[u8], page: usize) -> DocResult<DynamicImage> {
+        match &self.pages {
+            RasterPages::Tiff(offsets) => Self::decode_page(data, offsets[page]),
+            RasterPages::Animation(frames) => {
+                Ok(DynamicImage::ImageRgba8(frames[page].image.clone()))
+            }
+            RasterPages::Single => Err(anyhow::anyhow!("Image has a single page")),
+        }
+    }
+
+    /// Read the source file if pages are decoded from it.
+    fn page_data(&self) -> DocResult<Vec<u8>> {
+        match self.pages {
+            RasterPages::Tiff(_) => Ok(std::fs::read(&self.source_path)?),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Generate thumbnails for the pages after the cached ones, up to `last`.
+    fn extend_thumbnails(&mut self, last: usize) -> DocResult<()> {
+        let loaded = self.thumbnails_loaded();
+        if matches!(self.pages, RasterPages::Single) || loaded > last || last >= self.pages.len() {
+            return Ok(());
+        }
+
+        let data = self.page_data()?;
+        let mut handles = Vec::with_capacity(last + 1 - loaded);
+        for page in loaded..=last {
+            let handle = match self.page_image(&data, page) {
+                Ok(img) => Self::create_image_handle_from_image(
+                    &img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE),
+                ),
+                Err(e) => {
+                    log::warn!("Failed to generate thumbnail for page {page}: {e}");
+                    ImageHandle::from_rgba(1, 1, vec![0, 0, 0, 0])
+                }
+            };
+            handles.push(handle);
+        }
+        self.thumbnail_cache
+            .get_or_insert_with(Vec::new)
+            .extend(handles);
+        Ok(())
+    }
+
+    /// Drop the fine rotation and restore the pixels underneath it.
+    fn clear_fine_rotation(&mut self) {
+        if let Some(unrotated) = self.unrotated.take() {
+            self.document = Arc::unwrap_or_clone(unrotated);
+        }
+        self.preview_source = None;
+        self.rotation_pending = false;
+        self.fine_rotation_angle = 0.0;
+    }
+
+    /// Resample the pixels underneath the fine rotation at the current angle.
+    fn render_fine_rotation(&mut self) {
+        if let Some(job) = self.fine_rotation_job() {
+            self.document = job.run().image;
+        }
+        self.rotation_pending = false;
+    }
+
+    /// Show the fine rotation at `angle_degrees` without resampling the full
+    /// image: only a downscaled copy is rotated, with fast sampling.
+    ///
+    /// `document` keeps the previous angle until the result of
+    /// `fine_rotation_job()` is applied with `apply_fine_rotation()`.
+    /// Returns whether such a job is needed.
+    pub fn preview_fine_rotation(&mut self, angle_degrees: f32) -> bool {
+        self.settle_frame();
+        let angle = Self::normalize_fine_angle(angle_degrees);
+        if angle == 0.0 {
+            self.reset_fine_rotation();
+            return false;
+        }
+
+        let unrotated = self
+            .unrotated
+            .get_or_insert_with(|| Arc::new(self.document.clone()));
+        let preview_source = self.preview_source.get_or_insert_with(|| {
+            let (width, height) = unrotated.dimensions();
+            if width.max(height) > FINE_ROTATION_PREVIEW_SIZE {
+                unrotated.thumbnail(FINE_ROTATION_PREVIEW_SIZE, FINE_ROTATION_PREVIEW_SIZE)
+            } else {
+                unrotated.as_ref().clone()
+            }
+        });
+
+        self.fine_rotation_angle = angle;
+        let preview = transform::rotate_image_fine(
+            preview_source,
+            angle,
+            InterpolationQuality::Fast,
+            self.auto_crop,
+        );
+        self.handle = Self::create_image_handle_from_image(&preview);
+        self.rotation_pending = true;
+        true
+    }
+
+    /// Prepare the full-resolution resampling of the current fine rotation.
+    ///
+    /// Returns `None` without a fine rotation.
+    #[must_use]
+    pub fn fine_rotation_job(&self) -> Option<FineRotationJob> {
+        Some(FineRotationJob {
+            source: Arc::clone(self.unrotated.as_ref()?),
+            angle: self.fine_rotation_angle,
+            quality: self.interpolation_quality,
+            auto_crop: self.auto_crop,
+        })
+    }
+
+    /// Take the result of a `FineRotationJob` into the document.
+    ///
+    /// Returns false (and leaves the document untouched) if the rotation or
+    /// its settings changed since the job was made.
+    pub fn apply_fine_rotation(&mut self, rotated: RotatedImage) -> bool {
+        let job = &rotated.job;
+        let current = self
+            .unrotated
+            .as_ref()
+            .is_some_and(|unrotated| Arc::ptr_eq(unrotated, &job.source))
+            && job.angle == self.fine_rotation_angle
+            && job.quality == self.interpolation_quality
+            && job.auto_crop == self.auto_crop;
+        if current {
+            self.document = rotated.image;
+            self.handle = Self::create_image_handle_from_image(&self.document);
+            self.rotation_pending = false;
+        }
+        current
+    }
+
+    /// Whether a previewed fine rotation still waits for its full-resolution
+    /// pixels.
+    #[must_use]
+    pub fn fine_rotation_pending(&self) -> bool {
+        self.rotation_pending
+    }
+
+    /// Resample a previewed fine rotation right away, so that the pixels
+    /// match the angle (before cropping or saving).
+    pub fn settle_fine_rotation(&mut self) {
+        if self.rotation_pending {
+            self.render_fine_rotation();
+            self.handle = Self::create_image_handle_from_image(&self.document);
+        }
+    }
+
+    /// Image pixels per displayed pixel of `handle`: below 1.0 while a fine
+    /// rotation is previewed on a downscaled copy.
+    #[must_use]
+    pub fn pixel_density(&self) -> f32 {
+        match (&self.preview_source, &self.unrotated) {
+            (Some(preview), Some(unrotated)) if self.rotation_pending => {
+                preview.width() as f32 / unrotated.width().max(1) as f32
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Normalize an angle to -180..180, so it reads like a straightening
+    /// offset; angles too small to see become 0.
+    fn normalize_fine_angle(angle_degrees: f32) -> f32 {
+        let angle = (angle_degrees + 180.0).rem_euclid(360.0) - 180.0;
+        if angle.is_finite() && angle.abs() >= 0.01 {
+            angle
+        } else {
+            0.0
+        }
+    }
+
+    /// Re-render an active fine rotation after its settings changed.
+    fn refresh_fine_rotation(&mut self) {
+        if self.unrotated.is_some() {
+            self.render_fine_rotation();
+            self.handle = Self::create_image_handle_from_image(&self.document);
+        }
+    }
+
     fn create_image_handle_from_image(img: &DynamicImage) -> ImageHandle {
         let (width, height) = img.dimensions();
         let pixels = img.to_rgba8().into_raw();
         ImageHandle::from_rgba(width, height, pixels)
     }
 
-    fn apply_rotation(img: DynamicImage, rotation: Rotation) -> DynamicImage {
-        use image::imageops::{rotate180, rotate270, rotate90};
-        match rotation {
-            Rotation::None => img,
-            Rotation::Cw90 => DynamicImage::ImageRgba8(rotate90(&img.to_rgba8())),
-            Rotation::Cw180 => DynamicImage::ImageRgba8(rotate180(&img.to_rgba8())),
-            Rotation::Cw270 => DynamicImage::ImageRgba8(rotate270(&img.to_rgba8())),
+    /// Apply a standard rotation followed by flips (see `TransformState`).
+    fn apply_transform(img: DynamicImage, state: TransformState) -> DynamicImage {
+        let mut img = match state.rotation {
+            RotationMode::Standard(rotation) => transform::apply_rotation(img, rotation),
+            RotationMode::Fine(_) => img,
+        };
+        if state.flip_h {
+            img = transform::apply_flip(img, FlipDirection::Horizontal);
         }
-    }
-
-    fn apply_flip(img: DynamicImage, direction: FlipDirection) -> DynamicImage {
-        use image::imageops::{flip_horizontal, flip_vertical};
-        match direction {
-            FlipDirection::Horizontal => DynamicImage::ImageRgba8(flip_horizontal(&img.to_rgba8())),
-            FlipDirection::Vertical => DynamicImage::ImageRgba8(flip_vertical(&img.to_rgba8())),
+        if state.flip_v {
+            img = transform::apply_flip(img, FlipDirection::Vertical);
         }
+        img
     }
 }
 
@@ -277,18 +845,24 @@ impl Renderable for RasterDocument {
 
 impl Transformable for RasterDocument {
     fn rotate(&mut self, rotation: Rotation) {
+        self.settle_frame();
+        // A 90° step snaps back to the image without fine rotation.
+        self.clear_fine_rotation();
+
         // Extract current rotation in degrees
         let current_deg = match self.transform.rotation {
             RotationMode::Standard(r) => r.to_degrees(),
-            RotationMode::Fine(_) => {
-                // If we have fine rotation, reset it and apply standard rotation
-                self.fine_rotation_angle = 0.0;
-                0
-            }
+            RotationMode::Fine(_) => 0,
         };
 
+        // Flips are applied after the rotation, so a mirrored image turns
+        // its pixels the opposite way.
         let new_deg = rotation.to_degrees();
-        let diff_deg = (new_deg - current_deg + 360) % 360;
+        let diff_deg = if self.transform.is_mirrored() {
+            (current_deg - new_deg + 360) % 360
+        } else {
+            (new_deg - current_deg + 360) % 360
+        };
 
         if diff_deg != 0 {
             let rotation_to_apply = match diff_deg {
@@ -297,7 +871,7 @@ impl Transformable for RasterDocument {
                 270 => Rotation::Cw270,
                 _ => unreachable!("Invalid rotation diff: {}", diff_deg),
             };
-            self.document = Self::apply_rotation(
+            self.document = transform::apply_rotation(
                 std::mem::replace(&mut self.document, DynamicImage::new_rgb8(1, 1)),
                 rotation_to_apply,
             );
@@ -309,7 +883,12 @@ impl Transformable for RasterDocument {
     }
 
     fn flip(&mut self, direction: FlipDirection) {
-        self.document = Self::apply_flip(
+        self.settle_frame();
+        // Mirroring turns a fine rotation the other way round.
+        let fine_angle = self.fine_rotation_angle;
+        self.clear_fine_rotation();
+
+        self.document = transform::apply_flip(
             std::mem::replace(&mut self.document, DynamicImage::new_rgb8(1, 1)),
             direction,
         );
@@ -317,37 +896,382 @@ impl Transformable for RasterDocument {
             FlipDirection::Horizontal => self.transform.flip_h = !self.transform.flip_h,
             FlipDirection::Vertical => self.transform.flip_v = !self.transform.flip_v,
         }
+
+        if fine_angle != 0.0 {
+            self.rotate_fine(-fine_angle);
+        }
         self.handle = Self::create_image_handle_from_image(&self.document);
     }
 
     fn transform_state(&self) -> TransformState {
-        self.transform
+        self.transform.with_fine_rotation(self.fine_rotation_angle)
     }
 
     fn rotate_fine(&mut self, angle_degrees: f32) {
-        // TODO: Re-enable when imageproc dependency is added to Cargo.toml
-        // For now, round to nearest 90-degree rotation
-        log::warn!("Fine rotation not yet implemented, rounding to nearest 90 degrees");
-
-        let rounded = ((angle_degrees / 90.0).round() as i16 * 90) % 360;
-        let rotation = match rounded {
-            0 => Rotation::None,
-            90 => Rotation::Cw90,
-            180 => Rotation::Cw180,
-            270 => Rotation::Cw270,
-            _ => Rotation::None,
-        };
+        self.settle_frame();
+        let angle = Self::normalize_fine_angle(angle_degrees);
+        if angle == 0.0 {
+            self.reset_fine_rotation();
+            return;
+        }
 
-        self.rotate(rotation);
-        self.transform.rotation = RotationMode::Standard(rotation);
+        if self.unrotated.is_none() {
+            self.unrotated = Some(Arc::new(self.document.clone()));
+        }
+        self.fine_rotation_angle = angle;
+        self.render_fine_rotation();
+        self.handle = Self::create_image_handle_from_image(&self.document);
     }
 
     fn reset_fine_rotation(&mut self) {
+        if self.unrotated.is_some() {
+            self.clear_fine_rotation();
+            self.handle = Self::create_image_handle_from_image(&self.document);
+        }
         self.fine_rotation_angle = 0.0;
-        self.transform.rotation = RotationMode::Standard(Rotation::None);
+    }
+
+    fn fine_rotation(&self) -> f32 {
+        self.fine_rotation_angle
     }
 
     fn set_interpolation_quality(&mut self, quality: InterpolationQuality) {
-        self.interpolation_quality = quality;
+        if self.interpolation_quality != quality {
+            self.interpolation_quality = quality;
+            self.refresh_fine_rotation();
+        }
+    }
+
+    fn set_auto_crop(&mut self, enabled: bool) {
+        if self.auto_crop != enabled {
+            self.auto_crop = enabled;
+            self.refresh_fine_rotation();
+        }
+    }
+}
+
+impl MultiPage for RasterDocument {
+    fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn current_page(&self) -> usize {
+        self.page_index
+    }
+
+    fn go_to_page(&mut self, page: usize) -> DocResult<()> {
+        if page >= self.page_count() {
+            return Err(anyhow::anyhow!(
+                "Page {} out of range (0-{})",
+                page,
+                self.page_count() - 1
+            ));
+        }
+        if matches!(self.pages, RasterPages::Single) {
+            return Ok(());
+        }
+
+        let page_image = self.page_image(&self.page_data()?, page)?;
+        (self.native_width, self.native_height) = page_image.dimensions();
+
+        // Pages and frames share the rotation and flips, edits start over.
+        self.unrotated = None;
+        self.preview_source = None;
+        self.rotation_pending = false;
+        self.fine_rotation_angle = 0.0;
+        self.source_pixels = true;
+        self.document = Self::apply_transform(page_image, self.transform);
+        self.handle = match self.cached_frame_handle(page) {
+            Some(handle) => handle,
+            None => Self::create_image_handle_from_image(&self.document),
+        };
+        if self.is_animated() {
+            if self.frame_handles.len() <= page {
+                self.frame_handles.resize(page + 1, None);
+            }
+            self.frame_handles[page] = Some(self.handle.clone());
+        }
+        self.frame_pending = false;
+        self.page_index = page;
+        Ok(())
+    }
+}
+
+impl MultiPageThumbnails for RasterDocument {
+    fn thumbnails_ready(&self) -> bool {
+        self.thumbnail_cache
+            .as_ref()
+            .is_some_and(|c| c.len() >= self.pages.len())
+    }
+
+    fn thumbnails_loaded(&self) -> bool {
+        RasterDocument::thumbnails_loaded(self) >= self.pages.len()
+    }
+
+    fn generate_thumbnail_page(&mut self, page: usize) -> DocResult<()> {
+        self.extend_thumbnails(page)
+    }
+
+    fn generate_all_thumbnails(&mut self) -> DocResult<()> {
+        self.extend_thumbnails(self.pages.len() - 1)
+    }
+
+    fn get_thumbnail(&mut self, page: usize) -> DocResult<Option<ImageHandle>> {
+        Ok(self.get_thumbnail_handle(page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::document::operations::container::{self, MetadataBlocks};
+    use crate::domain::document::operations::export::{self, ExportFormat};
+    use crate::test_support;
+    use image::RgbImage;
+    use image::metadata::Orientation;
+
+    #[test]
+    fn test_open_applies_exif_orientation() {
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(6, 4, |x, y| {
+            image::Rgb([(x * 40) as u8, (y * 60) as u8, 0])
+        }));
+
+        for orientation in 1..=8u16 {
+            // PNG keeps the pixel comparison exact.
+            let blocks = MetadataBlocks {
+                exif: Some(container::exif_with_orientation(orientation)),
+                ..MetadataBlocks::default()
+            };
+            let data = export::encode_image(&img, ExportFormat::Png, &Default::default(), &blocks)
+                .unwrap();
+            let path = dir.join(format!("orientation-{orientation}.png"));
+            std::fs::write(&path, data).unwrap();
+
+            let doc = RasterDocument::open(&path).unwrap();
+            assert_eq!(doc.transform_state().exif_orientation(), Some(orientation));
+            assert_eq!(doc.native_dimensions(), (6, 4));
+
+            let mut expected = img.clone();
+            expected.apply_orientation(Orientation::from_exif(orientation as u8).unwrap());
+            assert_eq!(
+                doc.image().to_rgba8(),
+                expected.to_rgba8(),
+                "orientation {orientation}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_open_raw_preview() {
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let path = dir.join("photo.nef");
+
+        let thumbnail = test_support::jpeg(4, 2);
+        let preview = test_support::jpeg(64, 32);
+        std::fs::write(&path, test_support::camera_raw(&thumbnail, &preview, 6)).unwrap();
+
+        // The larger preview, turned upright by the RAW's orientation.
+        let doc = RasterDocument::open_raw_preview(&path).unwrap();
+        assert_eq!(doc.native_dimensions(), (64, 32));
+        assert_eq!(doc.transform_state().exif_orientation(), Some(6));
+        assert_eq!(doc.image().dimensions(), (32, 64));
+
+        let exif = ExifMeta::from_bytes(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(exif.camera_make.as_deref(), Some("Noctua"));
+
+        std::fs::write(&path, test_support::gray_tiff(&[(4, 4, 0, false)])).unwrap();
+        assert!(RasterDocument::open_raw_preview(&path).is_err());
+    }
+
+    #[test]
+    fn test_fine_rotation() {
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let path = dir.join("horizon.png");
+
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(60, 40, |x, y| {
+            image::Rgb([(x * 4) as u8, (y * 6) as u8, 0])
+        }));
+        img.save(&path).unwrap();
+
+        let mut doc = RasterDocument::open(&path).unwrap();
+        doc.rotate_fine(3.0);
+        doc.rotate_fine(-2.5);
+        assert_eq!(doc.fine_rotation(), -2.5);
+        assert_eq!(doc.transform_state().rotation, RotationMode::Fine(357.5));
+        assert_eq!(
+            doc.dimensions(),
+            transform::dimensions_after_fine_rotation(60, 40, -2.5)
+        );
+        assert!(doc.source_transform().is_none());
+
+        // Nudging always resamples the original pixels.
+        let mut fresh = RasterDocument::open(&path).unwrap();
+        fresh.rotate_fine(-2.5);
+        assert_eq!(doc.image().to_rgba8(), fresh.image().to_rgba8());
+
+        doc.set_auto_crop(true);
+        assert_eq!(
+            doc.dimensions(),
+            transform::largest_inscribed_rect(60, 40, -2.5)
+        );
+
+        // Mirroring keeps the visual angle and turns the source the other way.
+        doc.flip(FlipDirection::Horizontal);
+        assert_eq!(doc.fine_rotation(), 2.5);
+        assert_eq!(doc.transform_state().rotation, RotationMode::Fine(357.5));
+
+        doc.reset_fine_rotation();
+        assert_eq!(doc.fine_rotation(), 0.0);
+        assert_eq!(doc.dimensions(), (60, 40));
+        assert_eq!(
+            doc.image().to_rgba8(),
+            image::imageops::flip_horizontal(&img.to_rgba8())
+        );
+
+        // A 90° step drops the fine rotation.
+        doc.rotate_fine(1.0);
+        doc.rotate(Rotation::Cw90);
+        assert_eq!(doc.fine_rotation(), 0.0);
+        assert_eq!(doc.dimensions(), (40, 60));
+    }
+
+    #[test]
+    fn test_fine_rotation_preview() {
+        let tmp = test_support::temp_dir();
+        let path = tmp.path().join("horizon.png");
+        DynamicImage::ImageRgb8(RgbImage::from_fn(1100, 40, |x, y| {
+            image::Rgb([x as u8, (y * 6) as u8, 0])
+        }))
+        .save(&path)
+        .unwrap();
+
+        // The preview rotates a downscaled copy; the pixels stay as they are.
+        let mut doc = RasterDocument::open(&path).unwrap();
+        assert!(doc.preview_fine_rotation(2.0));
+        assert!(doc.fine_rotation_pending());
+        assert_eq!(doc.fine_rotation(), 2.0);
+        assert_eq!(doc.dimensions(), (1100, 40));
+        assert_eq!(doc.pixel_density(), 1024.0 / 1100.0);
+
+        // Results for an older angle are dropped.
+        let stale = doc.fine_rotation_job().unwrap().run();
+        assert!(doc.preview_fine_rotation(3.0));
+        assert!(!doc.apply_fine_rotation(stale));
+        let rotated = doc.fine_rotation_job().unwrap().run();
+        assert!(doc.apply_fine_rotation(rotated));
+        assert!(!doc.fine_rotation_pending());
+        assert_eq!(doc.pixel_density(), 1.0);
+
+        let mut expected = RasterDocument::open(&path).unwrap();
+        expected.rotate_fine(3.0);
+        assert_eq!(doc.image().to_rgba8(), expected.image().to_rgba8());
+
+        // Cropping resamples a pending preview first.
+        assert!(doc.preview_fine_rotation(-1.5));
+        doc.crop(0, 0, 100, 30).unwrap();
+        assert!(!doc.fine_rotation_pending());
+        let mut expected = RasterDocument::open(&path).unwrap();
+        expected.rotate_fine(-1.5);
+        expected.crop(0, 0, 100, 30).unwrap();
+        assert_eq!(doc.image().to_rgba8(), expected.image().to_rgba8());
+
+        assert!(!doc.preview_fine_rotation(0.0));
+        assert_eq!(doc.fine_rotation(), 0.0);
+    }
+
+    #[test]
+    fn test_multi_page_tiff() {
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let path = dir.join("scan.tiff");
+
+        let tiff = test_support::gray_tiff(&[(4, 3, 10, false), (5, 6, 200, false)]);
+        std::fs::write(&path, tiff).unwrap();
+
+        let mut doc = RasterDocument::open(&path).unwrap();
+        assert_eq!(doc.page_count(), 2);
+        assert_eq!(doc.dimensions(), (4, 3));
+
+        // Rotations carry over to the next page.
+        doc.rotate(Rotation::Cw90);
+        doc.go_to_page(1).unwrap();
+        assert_eq!(doc.current_page(), 1);
+        assert_eq!(doc.dimensions(), (6, 5));
+        assert_eq!(doc.native_dimensions(), (5, 6));
+        assert_eq!(doc.image().to_luma8().get_pixel(0, 0).0, [200]);
+        assert!(doc.go_to_page(2).is_err());
+
+        doc.generate_all_thumbnails().unwrap();
+        assert!(MultiPageThumbnails::thumbnails_loaded(&doc));
+        assert!(doc.get_thumbnail_handle(1).is_some());
+    }
+
+    #[test]
+    fn test_animated_gif() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Frame};
+
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let path = dir.join("blink.gif");
+
+        let frame = |value: u8, delay_ms: u32| {
+            Frame::from_parts(
+                RgbaImage::from_pixel(4, 3, image::Rgba([value, 0, 0, 255])),
+                0,
+                0,
+                Delay::from_numer_denom_ms(delay_ms, 1),
+            )
+        };
+        let mut encoder = GifEncoder::new(std::fs::File::create(&path).unwrap());
+        encoder
+            .encode_frames([frame(255, 200), frame(0, 0)])
+            .unwrap();
+        drop(encoder);
+
+        let mut doc = RasterDocument::open(&path).unwrap();
+        assert!(doc.is_animated());
+        assert_eq!(doc.page_count(), 2);
+        assert_eq!(doc.frame_delay(), Some(Duration::from_millis(200)));
+        assert_eq!(doc.image().to_rgba8().get_pixel(0, 0).0, [255, 0, 0, 255]);
+
+        // Frames without a delay play at the default speed.
+        doc.go_to_page(1).unwrap();
+        assert_eq!(doc.frame_delay(), Some(DEFAULT_FRAME_DELAY));
+        assert_eq!(doc.image().to_rgba8().get_pixel(0, 0).0, [0, 0, 0, 255]);
+
+        // Playback swaps the handle built for each frame; the pixels follow
+        // once it stops.
+        doc.show_frame(0).unwrap();
+        let first = doc.handle();
+        doc.show_frame(1).unwrap();
+        assert!(doc.frame_pending());
+        assert_eq!(doc.image().to_rgba8().get_pixel(0, 0).0, [255, 0, 0, 255]);
+        doc.show_frame(0).unwrap();
+        assert_eq!(doc.handle().id(), first.id());
+        doc.show_frame(1).unwrap();
+        doc.settle_frame();
+        assert!(!doc.frame_pending());
+        assert_eq!(doc.image().to_rgba8().get_pixel(0, 0).0, [0, 0, 0, 255]);
+
+        // Turning builds the handles again.
+        doc.rotate(Rotation::Cw90);
+        doc.show_frame(0).unwrap();
+        assert!(!doc.frame_pending());
+        assert_ne!(doc.handle().id(), first.id());
+        assert_eq!(doc.dimensions(), (3, 4));
+
+        // Frames beyond the memory limit are not decoded.
+        let frame_size = 4 * 3 * 4;
+        let frames = RasterDocument::decode_animation(&path, ImageFormat::Gif, frame_size).unwrap();
+        assert!(frames.is_none());
+        let frames =
+            RasterDocument::decode_animation(&path, ImageFormat::Gif, 2 * frame_size).unwrap();
+        assert_eq!(frames.map(|frames| frames.len()), Some(2));
     }
 }
@@ -4,13 +4,14 @@
 // Vector documents (SVG, etc.).
 
 use std::path::Path;
+use std::sync::{Arc, OnceLock};
 
 /// Minimum pixmap size for SVG rendering (prevents zero-size pixmaps).
 const MIN_PIXMAP_SIZE: u32 = 1;
 
 use image::{DynamicImage, GenericImageView, RgbaImage};
-use resvg::tiny_skia::{self, Pixmap};
-use resvg::usvg::{Options, Tree};
+use resvg::tiny_skia::{self, Pixmap, Point};
+use resvg::usvg::{fontdb, Options, Tree};
 
 use cosmic::widget::image::Handle as ImageHandle;
 
@@ -18,6 +19,45 @@ use crate::domain::document::core::document::{
     DocResult, DocumentInfo, FlipDirection, Renderable, RenderOutput, Rotation, RotationMode,
     TransformState, Transformable,
 };
+use crate::domain::document::operations::render::FontFamilies;
+
+/// Font families chosen at startup.
+static FONT_FAMILIES: OnceLock<FontFamilies> = OnceLock::new();
+
+/// System fonts, loaded when the first SVG is opened and shared by all
+/// documents afterwards.
+static FONT_DATABASE: OnceLock<Arc<fontdb::Database>> = OnceLock::new();
+
+/// Set the font families for SVG text.
+///
+/// Must be called before the first SVG document is opened; the font
+/// database is only built once, so later changes need a restart.
+pub fn configure_fonts(families: FontFamilies) {
+    if FONT_FAMILIES.set(families).is_err() {
+        log::warn!("SVG font families were already set, ignoring new ones");
+    }
+}
+
+/// Configured font families (defaults if `configure_fonts` was not called).
+fn font_families() -> &'static FontFamilies {
+    FONT_FAMILIES.get_or_init(FontFamilies::default)
+}
+
+/// Shared font database with the system fonts.
+fn font_database() -> Arc<fontdb::Database> {
+    let database = FONT_DATABASE.get_or_init(|| {
+        let families = font_families();
+        let mut database = fontdb::Database::new();
+        database.load_system_fonts();
+        database.set_sans_serif_family(families.sans_serif.as_str());
+        database.set_serif_family(families.serif.as_str());
+        database.set_monospace_family(families.monospace.as_str());
+
+        log::info!("Loaded {} system fonts for SVG text", database.len());
+        Arc::new(database)
+    });
+    Arc::clone(database)
+}
 
 /// Represents a vector document such as SVG.
 pub struct VectorDocument {
@@ -31,6 +71,8 @@ pub struct VectorDocument {
     current_scale: f64,
     /// Accumulated transformations.
     transform: TransformState,
+    /// Fine rotation angle in degrees, applied on top of `transform`.
+    fine_rotation_angle: f32,
     /// Rasterized image at the current scale.
     pub rendered: DynamicImage,
     /// Image handle for display.
@@ -43,12 +85,11 @@ pub struct VectorDocument {
 
 impl VectorDocument {
     /// Load a vector document from disk.
+    ///
+    /// Gzip-compressed files (SVGZ) are decompressed transparently.
     pub fn open(path: &Path) -> anyhow::Result<Self> {
-        let raw_data = std::fs::read_to_string(path)?;
-
-        // Parse SVG with default options.
-        let options = Options::default();
-        let document = Tree::from_str(&raw_data, &options)?;
+        let raw_data = std::fs::read(path)?;
+        let document = Tree::from_data(&raw_data, &Self::parse_options(path))?;
 
         // Get native size from the parsed document.
         let size = document.size();
@@ -68,6 +109,7 @@ impl VectorDocument {
             native_height,
             current_scale: 1.0,
             transform,
+            fine_rotation_angle: 0.0,
             rendered,
             handle,
             width,
@@ -93,6 +135,20 @@ impl VectorDocument {
         (self.native_width, self.native_height)
     }
 
+    /// Parser options for the SVG file at `path`.
+    ///
+    /// Relative references (e.g. `<image href="photo.png">`) resolve against
+    /// the folder of the file, not the working directory. Text is set in the
+    /// shared system fonts.
+    fn parse_options(path: &Path) -> Options<'static> {
+        Options {
+            resources_dir: path.parent().map(Path::to_path_buf),
+            font_family: font_families().default.clone(),
+            fontdb: font_database(),
+            ..Options::default()
+        }
+    }
+
     /// Extract metadata for this vector document.
     pub fn extract_meta(
         &self,
@@ -117,9 +173,15 @@ impl VectorDocument {
             height: self.native_height,
             file_size,
             color_type: "Vector".to_string(),
+            format_mismatch: None,
         };
 
-        DocumentMeta { basic, exif: None }
+        DocumentMeta {
+            basic,
+            exif: None,
+            pdf: None,
+            tags: Vec::new(),
+        }
     }
 
     /// Crop the document to the specified rectangle.
@@ -168,7 +230,7 @@ impl VectorDocument {
             self.native_width,
             self.native_height,
             scale,
-            self.transform,
+            self.transform_state(),
         ) {
             Ok((rendered, width, height)) => {
                 self.current_scale = scale;
@@ -192,7 +254,7 @@ impl VectorDocument {
             self.native_width,
             self.native_height,
             self.current_scale,
-            self.transform,
+            self.transform_state(),
         ) {
             self.rendered = rendered;
             self.width = width;
@@ -234,6 +296,8 @@ impl Renderable for VectorDocument {
 
 impl Transformable for VectorDocument {
     fn rotate(&mut self, rotation: Rotation) {
+        // A 90° step snaps back to the image without fine rotation.
+        self.fine_rotation_angle = 0.0;
         self.transform.rotation = RotationMode::Standard(rotation);
         self.rerender();
     }
@@ -243,15 +307,44 @@ impl Transformable for VectorDocument {
             FlipDirection::Horizontal => self.transform.flip_h = !self.transform.flip_h,
             FlipDirection::Vertical => self.transform.flip_v = !self.transform.flip_v,
         }
+        // Mirroring turns a fine rotation the other way round.
+        self.fine_rotation_angle = -self.fine_rotation_angle;
         self.rerender();
     }
 
     fn transform_state(&self) -> TransformState {
-        self.transform
+        self.transform.with_fine_rotation(self.fine_rotation_angle)
+    }
+
+    fn rotate_fine(&mut self, angle_degrees: f32) {
+        // Normalize to -180..180 so the angle reads like a straightening offset.
+        let angle = (angle_degrees + 180.0).rem_euclid(360.0) - 180.0;
+        self.fine_rotation_angle = if angle.is_finite() && angle.abs() >= 0.01 {
+            angle
+        } else {
+            0.0
+        };
+        self.rerender();
+    }
+
+    fn reset_fine_rotation(&mut self) {
+        if self.fine_rotation_angle != 0.0 {
+            self.fine_rotation_angle = 0.0;
+            self.rerender();
+        }
+    }
+
+    fn fine_rotation(&self) -> f32 {
+        self.fine_rotation_angle
     }
 }
 
 /// Render the SVG document at a given scale with transformations.
+///
+/// Scale, rotation and flips are combined into a single `tiny_skia`
+/// transform, so every orientation (including fine rotations) is rendered
+/// directly from the vector data. The pixmap grows to the bounding box of
+/// the transformed document.
 fn render_document(
     document: &Tree,
     native_width: u32,
@@ -259,52 +352,70 @@ fn render_document(
     scale: f64,
     transform: TransformState,
 ) -> anyhow::Result<(DynamicImage, u32, u32)> {
+    #[allow(clippy::cast_possible_truncation)]
+    let scale_f32 = scale as f32;
+
+    // Rotate first, then flip (see `TransformState`).
+    let flip_x = if transform.flip_h { -1.0 } else { 1.0 };
+    let flip_y = if transform.flip_v { -1.0 } else { 1.0 };
+    let ts = tiny_skia::Transform::from_scale(scale_f32, scale_f32)
+        .post_concat(rotation_transform(transform.rotation))
+        .post_scale(flip_x, flip_y);
+
+    // Move the bounding box of the transformed document to the origin.
+    let (w, h) = (native_width as f32, native_height as f32);
+    let mut corners = [
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(w, 0.0),
+        Point::from_xy(0.0, h),
+        Point::from_xy(w, h),
+    ];
+    ts.map_points(&mut corners);
+
+    let min_x = corners.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+    let max_x = corners
+        .iter()
+        .map(|p| p.x)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let min_y = corners.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+    let max_y = corners
+        .iter()
+        .map(|p| p.y)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let ts = ts.post_translate(-min_x, -min_y);
+
+    // Shave off float noise so right angles do not grow by a pixel.
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    let width = ((f64::from(native_width) * scale).ceil() as u32).max(MIN_PIXMAP_SIZE);
+    let width = ((max_x - min_x - 1e-3).ceil() as u32).max(MIN_PIXMAP_SIZE);
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    let height = ((f64::from(native_height) * scale).ceil() as u32).max(MIN_PIXMAP_SIZE);
+    let height = ((max_y - min_y - 1e-3).ceil() as u32).max(MIN_PIXMAP_SIZE);
 
     let mut pixmap =
         Pixmap::new(width, height).ok_or_else(|| anyhow::anyhow!("Failed to create pixmap"))?;
-
-    #[allow(clippy::cast_possible_truncation)]
-    let scale_f32 = scale as f32;
-    let ts = tiny_skia::Transform::from_scale(scale_f32, scale_f32);
     resvg::render(document, ts, &mut pixmap.as_mut());
 
-    let mut image = pixmap_to_dynamic_image(&pixmap);
+    let image = pixmap_to_dynamic_image(&pixmap);
 
-    // Apply flip transformations using shared utilities
-    if transform.flip_h {
-        image = crate::domain::document::operations::transform::apply_flip(
-            image,
-            FlipDirection::Horizontal,
-        );
-    }
-    if transform.flip_v {
-        image = crate::domain::document::operations::transform::apply_flip(
-            image,
-            FlipDirection::Vertical,
-        );
-    }
+    Ok((image, width, height))
+}
 
-    // Apply rotation using shared utilities
-    image = match transform.rotation {
-        RotationMode::Standard(rotation) => {
-            crate::domain::document::operations::transform::apply_rotation(image, rotation)
+/// Build the clockwise rotation for a rotation mode.
+///
+/// Standard rotations use exact matrices so that 90° steps stay pixel-aligned.
+fn rotation_transform(rotation: RotationMode) -> tiny_skia::Transform {
+    match rotation {
+        RotationMode::Standard(Rotation::None) => tiny_skia::Transform::identity(),
+        RotationMode::Standard(Rotation::Cw90) => {
+            tiny_skia::Transform::from_row(0.0, 1.0, -1.0, 0.0, 0.0, 0.0)
         }
-        RotationMode::Fine(_) => {
-            // For vector documents, fine rotation is handled differently
-            // For now, we just render without rotation
-            // TODO: Implement fine rotation support for vector documents
-            image
+        RotationMode::Standard(Rotation::Cw180) => {
+            tiny_skia::Transform::from_row(-1.0, 0.0, 0.0, -1.0, 0.0, 0.0)
         }
-    };
-
-    let final_width = image.width();
-    let final_height = image.height();
-
-    Ok((image, final_width, final_height))
+        RotationMode::Standard(Rotation::Cw270) => {
+            tiny_skia::Transform::from_row(0.0, -1.0, 1.0, 0.0, 0.0, 0.0)
+        }
+        RotationMode::Fine(degrees) => tiny_skia::Transform::from_rotate(degrees),
+    }
 }
 
 /// Convert a `tiny_skia` Pixmap to a `DynamicImage`.
@@ -332,3 +443,119 @@ fn pixmap_to_dynamic_image(pixmap: &Pixmap) -> DynamicImage {
 
     DynamicImage::ImageRgba8(rgba_image)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::document::operations::transform;
+    use crate::test_support;
+    use tempfile::TempDir;
+
+    const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20">
+        <rect x="0" y="0" width="10" height="20" fill="#ff0000"/>
+        <rect x="10" y="0" width="30" height="10" fill="#0000ff"/>
+    </svg>"##;
+
+    /// Path of a file in the SVG test fixtures.
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/svg")
+            .join(name)
+    }
+
+    fn open_test_document() -> (VectorDocument, TempDir) {
+        let dir = test_support::temp_dir();
+        let path = dir.path().join("test.svg");
+        std::fs::write(&path, SVG).unwrap();
+        (VectorDocument::open(&path).unwrap(), dir)
+    }
+
+    #[test]
+    fn test_standard_transforms_match_pixel_ops() {
+        let (mut doc, _dir) = open_test_document();
+        let upright = doc.rendered.clone();
+
+        for rotation in [Rotation::Cw90, Rotation::Cw180, Rotation::Cw270] {
+            doc.rotate(rotation);
+            let expected = transform::apply_rotation(upright.clone(), rotation);
+            assert_eq!(doc.rendered.to_rgba8(), expected.to_rgba8(), "{rotation:?}");
+        }
+
+        // Flips are applied after the rotation.
+        doc.rotate(Rotation::Cw90);
+        doc.flip(FlipDirection::Horizontal);
+        doc.flip(FlipDirection::Vertical);
+        let expected = transform::apply_rotation(upright.clone(), Rotation::Cw90);
+        let expected = transform::apply_flip(expected, FlipDirection::Horizontal);
+        let expected = transform::apply_flip(expected, FlipDirection::Vertical);
+        assert_eq!(doc.rendered.to_rgba8(), expected.to_rgba8());
+    }
+
+    #[test]
+    fn test_fine_rotation_grows_pixmap() {
+        let (mut doc, _dir) = open_test_document();
+
+        doc.rotate_fine(30.0);
+        assert_eq!(doc.fine_rotation(), 30.0);
+        assert_eq!(doc.transform_state().rotation, RotationMode::Fine(30.0));
+        assert_eq!(
+            doc.dimensions(),
+            transform::dimensions_after_fine_rotation(40, 20, 30.0)
+        );
+
+        // The rotated corners are left empty.
+        assert_eq!(doc.rendered.to_rgba8().get_pixel(0, 0)[3], 0);
+
+        // Re-rendering at another scale keeps the rotation.
+        doc.render_at_scale(2.0);
+        assert_eq!(
+            doc.dimensions(),
+            transform::dimensions_after_fine_rotation(80, 40, 30.0)
+        );
+
+        doc.reset_fine_rotation();
+        assert_eq!(doc.dimensions(), (80, 40));
+    }
+
+    #[test]
+    fn test_open_svgz() {
+        let doc = VectorDocument::open(&fixture("square.svgz")).unwrap();
+        assert_eq!(doc.dimensions(), (32, 16));
+
+        let rendered = doc.rendered.to_rgba8();
+        assert_eq!(rendered.get_pixel(8, 8).0, [255, 0, 0, 255]);
+        assert_eq!(rendered.get_pixel(24, 8).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_relative_image_href() {
+        // The linked PNG is next to the SVG, not in the working directory.
+        let doc = VectorDocument::open(&fixture("linked-image.svg")).unwrap();
+        assert_eq!(doc.rendered.to_rgba8().get_pixel(4, 4).0, [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_font_database_is_shared() {
+        assert!(Arc::ptr_eq(&font_database(), &font_database()));
+
+        // Without system fonts there is nothing to render text with.
+        if font_database().is_empty() {
+            return;
+        }
+
+        let tmp = test_support::temp_dir();
+        let dir = tmp.path();
+        let path = dir.join("text.svg");
+        std::fs::write(
+            &path,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="120" height="40">
+                <text x="4" y="30" font-size="28" font-family="sans-serif">Text</text>
+            </svg>"#,
+        )
+        .unwrap();
+
+        let doc = VectorDocument::open(&path).unwrap();
+        let rendered = doc.rendered.to_rgba8();
+        assert!(rendered.pixels().any(|pixel| pixel.0[3] > 0));
+    }
+}
@@ -15,13 +15,15 @@ use crate::domain::document::types::raster::RasterDocument;
 use crate::domain::document::types::vector::VectorDocument;
 #[cfg(feature = "portable")]
 use crate::domain::document::types::portable::PortableDocument;
+#[cfg(feature = "comic")]
+use crate::domain::document::types::comic::ComicDocument;
 
 /// Open a document from a file path and dispatch to the correct type.
 ///
 /// Raster formats are delegated to the `image` crate, which decides
 /// based on enabled codecs (e.g. default-formats).
 pub fn open_document(path: &Path) -> anyhow::Result<DocumentContent> {
-    let kind = DocumentKind::from_path(path)
+    let kind = DocumentKind::detect(path)
         .ok_or_else(|| anyhow!("Unsupported document type: {}", path.display()))?;
 
     let content = match kind {
@@ -36,10 +38,15 @@ pub fn open_document(path: &Path) -> anyhow::Result<DocumentContent> {
         }
         #[cfg(feature = "portable")]
         DocumentKind::Portable => {
-            let portable = PortableDocument::open(path)?;
+            let portable = PortableDocument::open(path, None)?;
             DocumentContent::Portable(portable)
         }
-        #[cfg(not(any(feature = "vector", feature = "portable")))]
+        #[cfg(feature = "comic")]
+        DocumentKind::Comic => {
+            let comic = ComicDocument::open(path)?;
+            DocumentContent::Comic(comic)
+        }
+        #[cfg(not(all(feature = "vector", feature = "portable", feature = "comic")))]
         _ => return Err(anyhow!("No document features enabled")),
     };
 
@@ -58,7 +65,12 @@ pub fn collect_supported_files(dir: &Path) -> Vec<PathBuf> {
             let path = entry.path();
 
             // Only keep regular files that are recognized as supported documents.
-            if path.is_file() && DocumentKind::from_path(&path).is_some() {
+            // The extension is checked first to keep scans of large folders
+            // cheap; the content only for files without a known extension.
+            if path.is_file()
+                && (DocumentKind::from_path(&path).is_some()
+                    || DocumentKind::from_content(&path).is_some())
+            {
                 entries.push(path);
             }
         }
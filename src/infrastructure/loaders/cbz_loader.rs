@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/infrastructure/loaders/cbz_loader.rs
+//
+// Loader for comic book archives (CBZ/ZIP).
+
+use std::path::Path;
+
+use crate::domain::document::core::content::DocumentContent;
+use crate::domain::document::core::document::DocResult;
+use crate::domain::document::types::comic::ComicDocument;
+use crate::infrastructure::loaders::document_loader::DocumentLoader;
+
+/// Loader for comic book archives.
+pub struct CbzLoader;
+
+impl DocumentLoader for CbzLoader {
+    fn load(&self, path: &Path) -> DocResult<DocumentContent> {
+        let document = ComicDocument::open(path)
+            .map_err(|e| anyhow::anyhow!("Failed to load comic book archive: {e}"))?;
+
+        Ok(DocumentContent::Comic(document))
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("cbz") || ext.eq_ignore_ascii_case("zip"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports() {
+        let loader = CbzLoader;
+
+        assert!(loader.supports(Path::new("issue-01.cbz")));
+        assert!(loader.supports(Path::new("volume.ZIP")));
+        assert!(!loader.supports(Path::new("test.png")));
+        assert!(!loader.supports(Path::new("test.pdf")));
+    }
+}
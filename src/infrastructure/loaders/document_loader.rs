@@ -5,14 +5,24 @@
 
 use std::path::Path;
 
+use image::ImageFormat;
+
 use crate::domain::document::core::content::{DocumentContent, DocumentKind};
 use crate::domain::document::core::document::DocResult;
+use crate::domain::document::core::format::{self, DetectedFormat};
 
 use super::raster_loader::RasterLoader;
+use super::raw_loader::RawLoader;
+#[cfg(feature = "heif")]
+use super::heif_loader::HeifLoader;
+#[cfg(feature = "jxl")]
+use super::jxl_loader::JxlLoader;
 #[cfg(feature = "vector")]
 use super::svg_loader::SvgLoader;
 #[cfg(feature = "portable")]
 use super::pdf_loader::PdfLoader;
+#[cfg(feature = "comic")]
+use super::cbz_loader::CbzLoader;
 
 /// Trait for loading documents from files.
 ///
@@ -27,7 +37,8 @@ pub trait DocumentLoader {
 
 /// Document loader factory.
 ///
-/// Detects the document format and delegates to the appropriate loader.
+/// Detects the document format from the file content (the extension is only
+/// a fallback) and delegates to the appropriate loader.
 pub struct DocumentLoaderFactory;
 
 impl DocumentLoaderFactory {
@@ -46,7 +57,23 @@ impl DocumentLoaderFactory {
     /// - The file cannot be read
     /// - The document is malformed
     pub fn load(&self, path: &Path) -> DocResult<DocumentContent> {
-        let kind = DocumentKind::from_path(path).ok_or_else(|| {
+        self.load_with_password(path, None)
+    }
+
+    /// Load a document, unlocking encrypted documents with `password`.
+    ///
+    /// The password is ignored by formats without encryption support.
+    ///
+    /// # Errors
+    ///
+    /// Same as `load`, plus a `PasswordError` if the document is encrypted
+    /// and the password is missing or wrong.
+    pub fn load_with_password(
+        &self,
+        path: &Path,
+        password: Option<&str>,
+    ) -> DocResult<DocumentContent> {
+        let kind = DocumentKind::detect(path).ok_or_else(|| {
             anyhow::anyhow!(
                 "Unsupported file format: {}",
                 path.extension()
@@ -55,11 +82,11 @@ impl DocumentLoaderFactory {
             )
         })?;
 
+        #[cfg(not(feature = "portable"))]
+        let _ = password;
+
         match kind {
-            DocumentKind::Raster => {
-                let loader = RasterLoader;
-                loader.load(path)
-            }
+            DocumentKind::Raster => Self::load_raster(path),
             #[cfg(feature = "vector")]
             DocumentKind::Vector => {
                 let loader = SvgLoader;
@@ -67,26 +94,67 @@ impl DocumentLoaderFactory {
             }
             #[cfg(feature = "portable")]
             DocumentKind::Portable => {
-                let loader = PdfLoader;
+                let loader = PdfLoader::with_password(password);
+                loader.load(path)
+            }
+            #[cfg(feature = "comic")]
+            DocumentKind::Comic => {
+                let loader = CbzLoader;
                 loader.load(path)
             }
-            #[cfg(not(any(feature = "vector", feature = "portable")))]
+            #[cfg(not(all(feature = "vector", feature = "portable", feature = "comic")))]
             _ => Err(anyhow::anyhow!(
                 "No document loaders available (check feature flags)"
             )),
         }
     }
 
-    /// Detect the document kind from a file path.
+    /// Load a raster image with the decoder for its format.
+    ///
+    /// Formats image-rs cannot decode (HEIF, JPEG XL) have their own loaders
+    /// when the matching cargo feature is enabled. Camera RAW files are TIFF
+    /// inside and told apart by their extension.
+    fn load_raster(path: &Path) -> DocResult<DocumentContent> {
+        match DetectedFormat::from_file(path) {
+            #[cfg(feature = "heif")]
+            Some(DetectedFormat::Heif) => HeifLoader.load(path),
+            #[cfg(feature = "jxl")]
+            Some(DetectedFormat::Jxl) => JxlLoader.load(path),
+            Some(DetectedFormat::Raster(ImageFormat::Tiff)) | None
+                if format::is_camera_raw(path) =>
+            {
+                RawLoader.load(path)
+            }
+            _ => RasterLoader.load(path),
+        }
+    }
+
+    /// Formats supported through optional cargo features in this build.
+    #[must_use]
+    pub fn optional_formats() -> Vec<&'static str> {
+        vec![
+            #[cfg(feature = "heif")]
+            "HEIF/HEIC",
+            #[cfg(feature = "avif")]
+            "AVIF",
+            #[cfg(feature = "jxl")]
+            "JPEG XL",
+            #[cfg(feature = "raw")]
+            "Camera RAW",
+        ]
+    }
+
+    /// Detect the document kind from the file content, or from the
+    /// extension if the content is not recognized.
     #[must_use]
     pub fn detect_kind(&self, path: &Path) -> Option<DocumentKind> {
-        DocumentKind::from_path(path)
+        DocumentKind::detect(path)
     }
 
     /// Check if a file is supported by any loader.
     #[must_use]
     pub fn is_supported(&self, path: &Path) -> bool {
-        DocumentKind::from_path(path).is_some()
+        DocumentKind::detect(path).is_some()
     }
 }
 
@@ -135,6 +203,11 @@ mod tests {
             );
         }
 
+        assert_eq!(
+            factory.detect_kind(Path::new("test.dng")),
+            Some(DocumentKind::Raster)
+        );
+
         assert_eq!(factory.detect_kind(Path::new("test.txt")), None);
     }
 
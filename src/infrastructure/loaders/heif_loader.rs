@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/infrastructure/loaders/heif_loader.rs
+//
+// Loader for HEIF/HEIC images (libheif).
+
+use std::path::Path;
+
+use image::{DynamicImage, RgbImage, RgbaImage};
+use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+use crate::domain::document::core::content::DocumentContent;
+use crate::domain::document::core::document::DocResult;
+use crate::domain::document::types::raster::RasterDocument;
+use crate::infrastructure::loaders::document_loader::DocumentLoader;
+
+/// Loader for HEIF/HEIC images.
+pub struct HeifLoader;
+
+impl HeifLoader {
+    /// Decode the primary image of a HEIF file to 8-bit RGB(A).
+    ///
+    /// libheif applies the rotation and mirroring stored in the file.
+    fn decode(path: &Path) -> DocResult<DynamicImage> {
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Path is not valid UTF-8"))?;
+        let context = HeifContext::read_from_file(path_str)?;
+        let handle = context.primary_image_handle()?;
+
+        let has_alpha = handle.has_alpha_channel();
+        let chroma = if has_alpha {
+            RgbChroma::Rgba
+        } else {
+            RgbChroma::Rgb
+        };
+        let image = LibHeif::new().decode(&handle, ColorSpace::Rgb(chroma), None)?;
+        let plane = image
+            .planes()
+            .interleaved
+            .ok_or_else(|| anyhow::anyhow!("Decoded HEIF image has no RGB plane"))?;
+
+        // Rows are padded to `stride` bytes.
+        let channels = if has_alpha { 4 } else { 3 };
+        let row_len = plane.width as usize * channels;
+        let pixels: Vec<u8> = plane
+            .data
+            .chunks(plane.stride)
+            .flat_map(|row| &row[..row_len])
+            .copied()
+            .collect();
+
+        let image = if has_alpha {
+            RgbaImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgba8)
+        } else {
+            RgbImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgb8)
+        };
+        image.ok_or_else(|| anyhow::anyhow!("Decoded HEIF image is truncated"))
+    }
+}
+
+impl DocumentLoader for HeifLoader {
+    fn load(&self, path: &Path) -> DocResult<DocumentContent> {
+        let image =
+            Self::decode(path).map_err(|e| anyhow::anyhow!("Failed to load HEIF image: {e}"))?;
+
+        Ok(DocumentContent::Raster(RasterDocument::from_decoded(
+            path, image,
+        )))
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ["heic", "heif", "hif"]
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports() {
+        let loader = HeifLoader;
+
+        assert!(loader.supports(Path::new("IMG_0001.HEIC")));
+        assert!(loader.supports(Path::new("test.heif")));
+        assert!(!loader.supports(Path::new("test.avif")));
+        assert!(!loader.supports(Path::new("test.jpg")));
+    }
+}
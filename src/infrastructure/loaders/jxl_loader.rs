@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/infrastructure/loaders/jxl_loader.rs
+//
+// Loader for JPEG XL images (jxl-oxide).
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use image::DynamicImage;
+use jxl_oxide::integration::JxlDecoder;
+
+use crate::domain::document::core::content::DocumentContent;
+use crate::domain::document::core::document::DocResult;
+use crate::domain::document::types::raster::RasterDocument;
+use crate::infrastructure::loaders::document_loader::DocumentLoader;
+
+/// Loader for JPEG XL images.
+pub struct JxlLoader;
+
+impl JxlLoader {
+    /// Decode the image, oriented as stored in the codestream header.
+    fn decode(path: &Path) -> DocResult<DynamicImage> {
+        let decoder = JxlDecoder::new(BufReader::new(File::open(path)?))?;
+        Ok(DynamicImage::from_decoder(decoder)?)
+    }
+}
+
+impl DocumentLoader for JxlLoader {
+    fn load(&self, path: &Path) -> DocResult<DocumentContent> {
+        let image =
+            Self::decode(path).map_err(|e| anyhow::anyhow!("Failed to load JPEG XL image: {e}"))?;
+
+        Ok(DocumentContent::Raster(RasterDocument::from_decoded(
+            path, image,
+        )))
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jxl"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports() {
+        let loader = JxlLoader;
+
+        assert!(loader.supports(Path::new("test.jxl")));
+        assert!(loader.supports(Path::new("test.JXL")));
+        assert!(!loader.supports(Path::new("test.jpg")));
+    }
+}
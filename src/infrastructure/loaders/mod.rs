@@ -6,10 +6,17 @@
 pub mod document_loader;
 
 pub mod raster_loader;
+pub mod raw_loader;
+#[cfg(feature = "heif")]
+pub mod heif_loader;
+#[cfg(feature = "jxl")]
+pub mod jxl_loader;
 #[cfg(feature = "vector")]
 pub mod svg_loader;
 #[cfg(feature = "portable")]
 pub mod pdf_loader;
+#[cfg(feature = "comic")]
+pub mod cbz_loader;
 
 // Re-export main types
 pub use document_loader::DocumentLoaderFactory;
@@ -5,18 +5,35 @@
 
 use std::path::Path;
 
+use anyhow::Context;
+
 use crate::domain::document::core::content::DocumentContent;
 use crate::domain::document::core::document::DocResult;
 use crate::domain::document::types::portable::PortableDocument;
 use crate::infrastructure::loaders::document_loader::DocumentLoader;
 
 /// Loader for PDF portable documents.
-pub struct PdfLoader;
+#[derive(Default)]
+pub struct PdfLoader {
+    /// Password for encrypted documents.
+    password: Option<String>,
+}
+
+impl PdfLoader {
+    /// Create a loader that unlocks encrypted documents with `password`.
+    #[must_use]
+    pub fn with_password(password: Option<&str>) -> Self {
+        Self {
+            password: password.map(str::to_string),
+        }
+    }
+}
 
 impl DocumentLoader for PdfLoader {
     fn load(&self, path: &Path) -> DocResult<DocumentContent> {
-        let document = PortableDocument::open(path)
-            .map_err(|e| anyhow::anyhow!("Failed to load PDF document: {e}"))?;
+        // Keep the error as context so a `PasswordError` can be downcast.
+        let document = PortableDocument::open(path, self.password.as_deref())
+            .context("Failed to load PDF document")?;
 
         Ok(DocumentContent::Portable(document))
     }
@@ -37,7 +54,7 @@ mod tests {
 
     #[test]
     fn test_supports() {
-        let loader = PdfLoader;
+        let loader = PdfLoader::default();
 
         assert!(loader.supports(Path::new("test.pdf")));
         assert!(loader.supports(Path::new("test.PDF")));
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/infrastructure/loaders/raw_loader.rs
+//
+// Loader for TIFF-based camera RAW files (DNG, CR2, NEF, ARW, ...).
+
+use std::path::Path;
+
+use crate::domain::document::core::content::DocumentContent;
+use crate::domain::document::core::document::DocResult;
+use crate::domain::document::core::format;
+use crate::domain::document::types::raster::RasterDocument;
+use crate::infrastructure::loaders::document_loader::DocumentLoader;
+
+/// Loader for camera RAW files.
+///
+/// Shows the largest JPEG preview embedded by the camera. With the `raw`
+/// feature, the sensor data is developed instead; the preview remains the
+/// fallback for cameras the decoder does not know.
+pub struct RawLoader;
+
+#[cfg(feature = "raw")]
+impl RawLoader {
+    /// Demosaic the sensor data into an sRGB image, turned upright by the
+    /// decoder.
+    fn develop(path: &Path) -> DocResult<image::DynamicImage> {
+        let decoded = imagepipe::simple_decode_8bit(path, 0, 0).map_err(|e| anyhow::anyhow!(e))?;
+        let image = image::RgbImage::from_raw(
+            u32::try_from(decoded.width)?,
+            u32::try_from(decoded.height)?,
+            decoded.data,
+        )
+        .ok_or_else(|| anyhow::anyhow!("Decoded image has an unexpected size"))?;
+        Ok(image::DynamicImage::ImageRgb8(image))
+    }
+}
+
+impl DocumentLoader for RawLoader {
+    fn load(&self, path: &Path) -> DocResult<DocumentContent> {
+        #[cfg(feature = "raw")]
+        match Self::develop(path) {
+            Ok(image) => {
+                return Ok(DocumentContent::Raster(RasterDocument::from_decoded(
+                    path, image,
+                )));
+            }
+            Err(e) => log::warn!("Failed to develop RAW file, showing its preview: {e}"),
+        }
+
+        let document = RasterDocument::open_raw_preview(path)
+            .map_err(|e| anyhow::anyhow!("Failed to load camera RAW file: {e}"))?;
+        Ok(DocumentContent::Raster(document))
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        format::is_camera_raw(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports() {
+        let loader = RawLoader;
+
+        assert!(loader.supports(Path::new("test.dng")));
+        assert!(loader.supports(Path::new("test.NEF")));
+        assert!(loader.supports(Path::new("test.arw")));
+        assert!(!loader.supports(Path::new("test.tiff")));
+    }
+}
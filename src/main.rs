@@ -11,13 +11,29 @@ mod infrastructure;
 mod config;
 mod i18n;
 
+#[cfg(test)]
+mod test_support;
+
 use anyhow::Result;
 use clap::Parser;
 use cosmic::app::Settings;
+use crate::infrastructure::loaders::DocumentLoaderFactory;
 use crate::ui::NoctuaApp;
+use std::sync::LazyLock;
+
+/// Version text for `--version`, listing the optional formats of this build.
+static LONG_VERSION: LazyLock<String> = LazyLock::new(|| {
+    let formats = DocumentLoaderFactory::optional_formats();
+    let formats = if formats.is_empty() {
+        "none".to_string()
+    } else {
+        formats.join(", ")
+    };
+    format!("{}\nOptional formats: {formats}", env!("CARGO_PKG_VERSION"))
+});
 
 #[derive(Parser, Debug, Clone)]
-#[command(version, about)]
+#[command(version, long_version = LONG_VERSION.as_str(), about)]
 pub struct Args {
     /// File to open on startup
     #[arg(value_name = "FILE")]
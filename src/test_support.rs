@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/test_support.rs
+//
+// Shared helpers for unit tests: temporary directories and image fixtures.
+
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, RgbImage};
+use tempfile::TempDir;
+
+/// Create a uniquely named temporary directory.
+///
+/// The directory and its contents are removed when the returned guard is
+/// dropped, even if the test panics.
+pub fn temp_dir() -> TempDir {
+    tempfile::Builder::new()
+        .prefix("noctua-")
+        .tempdir()
+        .expect("failed to create temporary directory")
+}
+
+/// Encode a solid-color RGB JPEG.
+pub fn jpeg(width: u32, height: u32) -> Vec<u8> {
+    let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(
+        width,
+        height,
+        image::Rgb([200, 40, 40]),
+    ));
+    let mut data = Vec::new();
+    img.write_to(&mut Cursor::new(&mut data), ImageFormat::Jpeg)
+        .unwrap();
+    data
+}
+
+/// Build a JPEG segment from its marker and body.
+pub fn jpeg_segment(marker: u8, body: &[u8]) -> Vec<u8> {
+    let mut segment = vec![0xFF, marker];
+    segment.extend_from_slice(&(body.len() as u16 + 2).to_be_bytes());
+    segment.extend_from_slice(body);
+    segment
+}
+
+/// Build a multi-picture (MPO) file as cameras write it.
+///
+/// The first image is `jpeg(width, height)` with a comment, EXIF, an
+/// extended XMP segment and the MPF index (APP2) after its JFIF header. A
+/// second image, whose EXIF holds a GPS marker, follows its end.
+pub fn mpo(width: u32, height: u32) -> Vec<u8> {
+    let mut data = jpeg(width, height);
+    let jfif_end = 4 + usize::from(u16::from_be_bytes([data[4], data[5]]));
+    // Extended XMP: GUID of the full packet, its length and the chunk offset.
+    let mut extended_xmp = b"http://ns.adobe.com/xmp/extension/\0".to_vec();
+    extended_xmp.extend_from_slice(&[b'0'; 32]);
+    extended_xmp.extend_from_slice(&11u32.to_be_bytes());
+    extended_xmp.extend_from_slice(&0u32.to_be_bytes());
+    extended_xmp.extend_from_slice(b"GPSLatitude");
+    let segments = [
+        jpeg_segment(0xFE, b"taken at home"),
+        jpeg_segment(0xE1, b"Exif\0\0MM\0*\0\0\0\x08\0\0"),
+        jpeg_segment(0xE1, &extended_xmp),
+        jpeg_segment(0xE2, b"MPF\0MM\0*"),
+    ]
+    .concat();
+    data.splice(jfif_end..jfif_end, segments);
+
+    let mut second = jpeg(width / 2, height / 2);
+    second.splice(2..2, jpeg_segment(0xE1, b"Exif\0\0GPSLatitude"));
+    data.extend_from_slice(&second);
+    data
+}
+
+/// Build an uncompressed little-endian grayscale TIFF.
+///
+/// Each page is `(width, height, gray value, reduced resolution)`.
+pub fn gray_tiff(pages: &[(u32, u32, u8, bool)]) -> Vec<u8> {
+    const SHORT: u16 = 3;
+    const LONG: u16 = 4;
+
+    let mut out = b"II\x2a\0\x08\0\0\0".to_vec();
+    let mut next_pointer = 4;
+    for &(width, height, value, reduced) in pages {
+        if out.len() % 2 == 1 {
+            out.push(0);
+        }
+        let ifd = out.len() as u32;
+        out[next_pointer..next_pointer + 4].copy_from_slice(&ifd.to_le_bytes());
+
+        let data = ifd + 2 + 10 * 12 + 4;
+        let entries = [
+            (0x00FE_u16, LONG, u32::from(reduced)), // NewSubfileType
+            (0x0100, LONG, width),
+            (0x0101, LONG, height),
+            (0x0102, SHORT, 8),
+            (0x0103, SHORT, 1),
+            (0x0106, SHORT, 1),
+            (0x0111, LONG, data),
+            (0x0115, SHORT, 1),
+            (0x0116, LONG, height),
+            (0x0117, LONG, width * height),
+        ];
+        out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (tag, kind, value) in entries {
+            out.extend_from_slice(&tag.to_le_bytes());
+            out.extend_from_slice(&kind.to_le_bytes());
+            out.extend_from_slice(&1u32.to_le_bytes());
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        next_pointer = out.len();
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.resize(out.len() + (width * height) as usize, value);
+    }
+    out
+}
+
+/// Build a little-endian TIFF-based camera RAW file.
+///
+/// IFD0 holds the Make ("Noctua") and Orientation tags and references
+/// `thumbnail`; a SubIFD references the larger `preview`.
+pub fn camera_raw(thumbnail: &[u8], preview: &[u8], orientation: u16) -> Vec<u8> {
+    const ASCII: u16 = 2;
+    const SHORT: u16 = 3;
+    const LONG: u16 = 4;
+    const COMPRESSION_OLD_JPEG: u32 = 6;
+
+    let sub_ifd = 8 + 2 + 5 * 12 + 4;
+    let make = sub_ifd + 2 + 4 * 12 + 4;
+    let thumbnail_at = make + 8;
+    let preview_at = thumbnail_at + thumbnail.len() as u32;
+
+    let mut out = b"II\x2a\0\x08\0\0\0".to_vec();
+    let mut push_ifd = |entries: &[(u16, u16, u32, u32)]| {
+        out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for &(tag, kind, count, value) in entries {
+            out.extend_from_slice(&tag.to_le_bytes());
+            out.extend_from_slice(&kind.to_le_bytes());
+            out.extend_from_slice(&count.to_le_bytes());
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        out.extend_from_slice(&0u32.to_le_bytes());
+    };
+    push_ifd(&[
+        (0x010F, ASCII, 7, make),                   // Make
+        (0x0112, SHORT, 1, u32::from(orientation)), // Orientation
+        (0x014A, LONG, 1, sub_ifd),                 // SubIFDs
+        (0x0201, LONG, 1, thumbnail_at),            // JPEGInterchangeFormat
+        (0x0202, LONG, 1, thumbnail.len() as u32),  // JPEGInterchangeFormatLength
+    ]);
+    push_ifd(&[
+        (0x00FE, LONG, 1, 1),                     // NewSubfileType
+        (0x0103, SHORT, 1, COMPRESSION_OLD_JPEG), // Compression
+        (0x0201, LONG, 1, preview_at),            // JPEGInterchangeFormat
+        (0x0202, LONG, 1, preview.len() as u32),  // JPEGInterchangeFormatLength
+    ]);
+    out.extend_from_slice(b"Noctua\0\0");
+    out.extend_from_slice(thumbnail);
+    out.extend_from_slice(preview);
+    out
+}
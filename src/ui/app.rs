@@ -4,7 +4,7 @@
 // COSMIC application wiring and main app struct.
 
 use super::message::AppMessage;
-use super::model::{AppModel, ViewMode};
+use super::model::{AppMode, AppModel, ViewMode};
 use super::update;
 use crate::ui::views;
 
@@ -21,6 +21,12 @@ use cosmic::{Action, Element, Task};
 
 use crate::application::DocumentManager;
 use crate::config::AppConfig;
+use crate::domain::document::core::document::PasswordError;
+use crate::domain::document::core::page::PageLayout;
+#[cfg(feature = "vector")]
+use crate::domain::document::operations::render::FontFamilies;
+#[cfg(feature = "vector")]
+use crate::domain::document::types::vector;
 use crate::Args;
 
 /// Flags passed from `main` into the application.
@@ -34,6 +40,7 @@ pub enum Flags {
 pub enum ContextPage {
     #[default]
     Properties,
+    Histogram,
 }
 
 /// Main application type.
@@ -85,8 +92,24 @@ impl cosmic::Application for NoctuaApp {
                 .cloned()
         });
 
+        // SVG text uses the configured fonts; they are loaded with the first SVG.
+        #[cfg(feature = "vector")]
+        vector::configure_fonts(FontFamilies {
+            default: config.svg_font_family.clone(),
+            sans_serif: config.svg_sans_serif_family.clone(),
+            serif: config.svg_serif_family.clone(),
+            monospace: config.svg_monospace_family.clone(),
+        });
+
         // Initialize document manager
         let mut document_manager = DocumentManager::new();
+        // No document is open yet, so there is nothing to re-render.
+        let _ = document_manager.set_page_layout(PageLayout {
+            two_page_spread: config.two_page_spread,
+            cover_alone: config.cover_alone,
+            right_to_left: config.right_to_left,
+            continuous: config.continuous_scroll,
+        });
 
         // Initialize model
         let mut model = AppModel::new(config.clone());
@@ -95,6 +118,9 @@ impl cosmic::Application for NoctuaApp {
         if let Some(path) = initial_path {
             if let Err(e) = document_manager.open_document(&path) {
                 log::error!("Failed to open initial path {}: {}", path.display(), e);
+                if let Some(error) = e.downcast_ref::<PasswordError>() {
+                    model.prompt_password(path.clone(), *error, false);
+                }
             } else {
                 // Set initial view mode to Fit
                 model.viewport.fit_mode = ViewMode::Fit;
@@ -136,21 +162,24 @@ impl cosmic::Application for NoctuaApp {
             core.nav_bar_set_toggled(config.nav_bar_visible);
         }
 
+        let mut app = Self {
+            core,
+            model,
+            nav,
+            context_page: ContextPage::default(),
+            config,
+            config_handler,
+            document_manager,
+        };
+
         // Start thumbnail generation for initial document if applicable.
-        let init_task = start_thumbnail_generation(&model);
-
-        (
-            Self {
-                core,
-                model,
-                nav,
-                context_page: ContextPage::default(),
-                config,
-                config_handler,
-                document_manager,
-            },
-            init_task,
-        )
+        // The continuous page view does not wait for a canvas size, its
+        // first pages are rendered right away.
+        let render_task = cosmic::task::future(async { AppMessage::RenderPage(0) });
+        let thumbnail_task = app.thumbnail_task().unwrap_or_else(Task::none);
+        let init_task = Task::batch([thumbnail_task, render_task]);
+
+        (app, init_task)
     }
 
     fn on_close_requested(&self, _id: window::Id) -> Option<Self::Message> {
@@ -178,7 +207,18 @@ impl cosmic::Application for NoctuaApp {
                     // Closing nav bar - hide left panel
                     self.model.panels.left = None;
                 }
-                return Task::none();
+                return self.thumbnail_task().unwrap_or_else(Task::none);
+            }
+
+            AppMessage::ToggleSearch
+                if !self
+                    .document_manager
+                    .current_document()
+                    .is_some_and(|doc| doc.supports_text_search()) =>
+            {
+                // Ctrl+F keeps opening the format panel for documents
+                // without a text layer.
+                return self.update(AppMessage::OpenFormatPanel);
             }
 
             AppMessage::OpenFormatPanel => {
@@ -201,25 +241,58 @@ impl cosmic::Application for NoctuaApp {
                 }
                 self.config.context_drawer_visible = self.core.window.show_context;
                 self.save_config();
-                return Task::none();
+                return self.histogram_task().unwrap_or_else(Task::none);
+            }
+
+            AppMessage::ToggleTwoPageSpread
+            | AppMessage::ToggleCoverAlone
+            | AppMessage::ToggleRightToLeft
+            | AppMessage::ToggleContinuousScroll
+            | AppMessage::SetStripMetadata(_)
+            | AppMessage::SetStripGps(_) => {
+                // The update stores the new setting in the config.
+                let result = update::update(self, &message);
+                self.save_config();
+                return match result {
+                    update::UpdateResult::None => Task::none(),
+                    update::UpdateResult::Task(task) => task,
+                };
             }
 
             AppMessage::OpenPath(_) | AppMessage::NextDocument | AppMessage::PrevDocument => {
                 let result = update::update(self, &message);
-                let thumb_task = start_thumbnail_generation_task(&self.model);
+                let thumb_task = self.thumbnail_task().unwrap_or_else(Task::none);
+                let histogram_task = self.histogram_task().unwrap_or_else(Task::none);
                 return match result {
-                    update::UpdateResult::None => thumb_task,
-                    update::UpdateResult::Task(task) => Task::batch([task, thumb_task]),
+                    update::UpdateResult::None => Task::batch([thumb_task, histogram_task]),
+                    update::UpdateResult::Task(task) => {
+                        Task::batch([task, thumb_task, histogram_task])
+                    }
                 };
             }
 
             _ => {}
         }
 
-        match update::update(self, &message) {
+        let task = match update::update(self, &message) {
             update::UpdateResult::None => Task::none(),
             update::UpdateResult::Task(task) => task,
+        };
+
+        // Frames shown during playback get their pixels once it stops.
+        if !animation_playing(self)
+            && let Some(doc) = self.document_manager.current_document_mut()
+        {
+            doc.settle_frame();
         }
+
+        // Keep the histogram in sync with crops, transforms and page changes,
+        // and the pages panel with scrolling.
+        let tasks: Vec<_> = [Some(task), self.histogram_task(), self.thumbnail_task()]
+            .into_iter()
+            .flatten()
+            .collect();
+        Task::batch(tasks)
     }
 
     fn header_start(&self) -> Vec<Element<'_, Self::Message>> {
@@ -238,9 +311,13 @@ impl cosmic::Application for NoctuaApp {
         if !self.core.window.show_context {
             return None;
         }
+        let content = match self.context_page {
+            ContextPage::Properties => views::panels::view(&self.model, &self.document_manager),
+            ContextPage::Histogram => views::histogram_panel::view(&self.model),
+        };
         Some(context_drawer::context_drawer(
-            views::panels::view(&self.model, &self.document_manager),
-            AppMessage::ToggleContextPage(ContextPage::Properties),
+            content,
+            AppMessage::ToggleContextPage(self.context_page),
         ))
     }
 
@@ -255,6 +332,10 @@ impl cosmic::Application for NoctuaApp {
         views::nav_bar(&self.model, &self.document_manager)
     }
 
+    fn dialog(&self) -> Option<Element<'_, Self::Message>> {
+        views::dialogs::view(&self.model)
+    }
+
     fn footer(&self) -> Option<Element<'_, Self::Message>> {
         Some(views::footer::view(&self.model, &self.document_manager))
     }
@@ -263,6 +344,7 @@ impl cosmic::Application for NoctuaApp {
         Subscription::batch([
             keyboard::on_key_press(handle_key_press),
             thumbnail_refresh_subscription(self),
+            animation_subscription(self),
         ])
     }
 }
@@ -275,17 +357,100 @@ impl NoctuaApp {
         }
     }
 
+    /// Start a histogram computation if the shown image changed and the
+    /// histogram panel or the clipping overlay needs it.
+    fn histogram_task(&mut self) -> Option<Task<Action<AppMessage>>> {
+        let panel_open =
+            self.core.window.show_context && self.context_page == ContextPage::Histogram;
+        // Every frame of a playing animation is a new image; the histogram
+        // catches up once playback is paused, or once a fine rotation is
+        // resampled at full resolution.
+        if animation_playing(self)
+            || self
+                .document_manager
+                .current_document()
+                .is_some_and(|doc| doc.fine_rotation_pending())
+        {
+            return None;
+        }
+        let state = &mut self.model.histogram;
+        if !panel_open && !state.clipping_overlay {
+            return None;
+        }
+
+        let Some(source) = self
+            .model
+            .viewport
+            .cached_image_handle
+            .as_ref()
+            .map(cosmic::widget::image::Handle::id)
+        else {
+            state.source = None;
+            state.result = None;
+            return None;
+        };
+        if state.source == Some(source) {
+            return None;
+        }
+
+        let image = self
+            .document_manager
+            .current_document()?
+            .rendered_image()
+            .clone();
+        state.source = Some(source);
+        state.generation += 1;
+        state.computing = true;
+        Some(update::histogram_task(
+            image,
+            state.generation,
+            state.clipping_overlay,
+        ))
+    }
+
+    /// Start making the missing thumbnails of the pages in view of the pages
+    /// panel, for documents that make them on demand (comic books).
+    fn thumbnail_task(&mut self) -> Option<Task<Action<AppMessage>>> {
+        use crate::ui::model::LeftPanel;
+
+        let panels = &mut self.model.panels;
+        // One job at a time; when it finishes, the next one picks up the
+        // pages scrolled into view meanwhile.
+        if !self.core.nav_bar_active()
+            || panels.left != Some(LeftPanel::Thumbnails)
+            || panels.thumbnails_pending
+        {
+            return None;
+        }
+
+        let doc = self.document_manager.current_document()?;
+        let pages =
+            views::pages_panel::pages_in_view(panels.thumbnails_view.as_ref(), doc.page_count());
+        let job = doc.thumbnail_job(pages)?;
+        panels.thumbnails_pending = true;
+        Some(update::thumbnail_task(job, panels.thumbnail_generation))
+    }
+
     /// Update nav bar visibility based on current document type.
     pub fn update_nav_bar_for_document(&mut self) {
         use crate::ui::model::LeftPanel;
 
+        // Expanded outline entries and pending thumbnails belong to the
+        // previous document.
+        self.model.panels.reset_for_document();
+
         if let Some(doc) = self.document_manager.current_document() {
-            if doc.is_multi_page() {
-                // Multi-page document: open nav bar and show thumbnails
+            if doc.is_multi_page() && !doc.is_animated() {
+                // Multi-page document: open nav bar, keep the outline if the
+                // new document has one, otherwise show thumbnails
                 self.core.nav_bar_set_toggled(true);
-                self.model.panels.left = Some(LeftPanel::Thumbnails);
+                let keep_outline = self.model.panels.left == Some(LeftPanel::Outline)
+                    && !doc.outline().is_empty();
+                if !keep_outline {
+                    self.model.panels.left = Some(LeftPanel::Thumbnails);
+                }
             } else {
-                // Single-page document: close nav bar
+                // Single-page document or animation: close nav bar
                 self.core.nav_bar_set_toggled(false);
                 self.model.panels.left = None;
             }
@@ -296,11 +461,21 @@ impl NoctuaApp {
 /// Map raw key presses + modifiers into high-level application messages.
 fn handle_key_press(key: Key, modifiers: Modifiers) -> Option<AppMessage> {
     use AppMessage::{
-        PanLeft, PanRight, PanUp, PanDown, OpenFormatPanel, NextDocument, PrevDocument,
-        FlipHorizontal, FlipVertical, RotateCCW, RotateCW, ZoomIn, ZoomOut, ZoomReset, ZoomFit,
-        ToggleCropMode, ToggleScaleMode, PanReset, ToggleContextPage, ToggleNavBar, SetAsWallpaper,
+        PanLeft, PanRight, PanUp, PanDown, NextDocument, PrevDocument, FlipHorizontal,
+        FlipVertical, RotateCCW, RotateCW, ZoomIn, ZoomOut, ZoomReset, ZoomFit, ToggleCropMode,
+        ToggleScaleMode, PanReset, ToggleContextPage, ToggleNavBar, SetAsWallpaper, Save, SaveAs,
+        NudgeFineRotation, ToggleSearch, SearchNext, SearchPrev, ToggleAnimation, NextFrame,
+        PrevFrame, NextPage, PrevPage, HistoryBack, HistoryForward, ToggleInspectMode,
     };
 
+    // Handle Ctrl + Shift shortcuts.
+    if modifiers.control() && modifiers.shift() && !modifiers.alt() && !modifiers.logo() {
+        return match key.as_ref() {
+            Key::Character(ch) if ch.eq_ignore_ascii_case("s") => Some(SaveAs),
+            _ => None,
+        };
+    }
+
     // Handle Ctrl + arrow keys for panning.
     if modifiers.control() && !modifiers.shift() && !modifiers.alt() && !modifiers.logo() {
         return match key.as_ref() {
@@ -308,7 +483,18 @@ fn handle_key_press(key: Key, modifiers: Modifiers) -> Option<AppMessage> {
             Key::Named(Named::ArrowRight) => Some(PanRight),
             Key::Named(Named::ArrowUp) => Some(PanUp),
             Key::Named(Named::ArrowDown) => Some(PanDown),
-            Key::Character(ch) if ch.eq_ignore_ascii_case("f") => Some(OpenFormatPanel),
+            // Search, or the format panel for documents without text.
+            Key::Character(ch) if ch.eq_ignore_ascii_case("f") => Some(ToggleSearch),
+            Key::Character(ch) if ch.eq_ignore_ascii_case("s") => Some(Save),
+            _ => None,
+        };
+    }
+
+    // Handle Alt + arrow keys for going back and forward after following links.
+    if modifiers.alt() && !modifiers.control() && !modifiers.shift() && !modifiers.logo() {
+        return match key.as_ref() {
+            Key::Named(Named::ArrowLeft) => Some(HistoryBack),
+            Key::Named(Named::ArrowRight) => Some(HistoryForward),
             _ => None,
         };
     }
@@ -318,6 +504,8 @@ fn handle_key_press(key: Key, modifiers: Modifiers) -> Option<AppMessage> {
         return None;
     }
 
+    let fine_step = if modifiers.shift() { 0.1 } else { 1.0 };
+
     match key.as_ref() {
         // Navigation with arrow keys (no modifiers).
         Key::Named(Named::ArrowRight) => Some(NextDocument),
@@ -334,6 +522,28 @@ fn handle_key_press(key: Key, modifiers: Modifiers) -> Option<AppMessage> {
             }
         }
 
+        // Fine rotation: 1° steps, 0.1° steps with Shift.
+        Key::Character("[" | "{") => Some(NudgeFineRotation(-fine_step)),
+        Key::Character("]" | "}") => Some(NudgeFineRotation(fine_step)),
+
+        // Text search: next hit, previous with Shift.
+        Key::Named(Named::F3) => {
+            if modifiers.shift() {
+                Some(SearchPrev)
+            } else {
+                Some(SearchNext)
+            }
+        }
+
+        // Pages of multi-page documents (a spread at a time in spread layouts).
+        Key::Named(Named::PageDown) => Some(NextPage),
+        Key::Named(Named::PageUp) => Some(PrevPage),
+
+        // Animation playback and frame stepping.
+        Key::Named(Named::Space) => Some(ToggleAnimation),
+        Key::Character(",") => Some(PrevFrame),
+        Key::Character(".") => Some(NextFrame),
+
         // Zoom.
         Key::Character("+" | "=") => Some(ZoomIn),
         Key::Character("-") => Some(ZoomOut),
@@ -343,6 +553,7 @@ fn handle_key_press(key: Key, modifiers: Modifiers) -> Option<AppMessage> {
         // Tool modes.
         Key::Character(ch) if ch.eq_ignore_ascii_case("c") => Some(ToggleCropMode),
         Key::Character(ch) if ch.eq_ignore_ascii_case("s") => Some(ToggleScaleMode),
+        Key::Character(ch) if ch.eq_ignore_ascii_case("p") => Some(ToggleInspectMode),
 
         // Crop mode actions (Enter/Escape handled via key press, validated in update).
         Key::Named(Named::Enter) => Some(AppMessage::ApplyCrop),
@@ -368,24 +579,6 @@ fn handle_key_press(key: Key, modifiers: Modifiers) -> Option<AppMessage> {
 // Thumbnail Helpers
 // =============================================================================
 
-fn start_thumbnail_generation(model: &AppModel) -> Task<Action<AppMessage>> {
-    start_thumbnail_generation_task(model)
-}
-
-fn start_thumbnail_generation_task(_model: &AppModel) -> Task<Action<AppMessage>> {
-    // TODO: Re-enable when document is synced from DocumentManager
-    // if let Some(doc) = &model.document {
-    //     let page_count = doc.page_count();
-    //     if page_count > 0 && !doc.thumbnails_ready() {
-    //         return Task::batch([
-    //             Task::done(Action::App(AppMessage::GenerateThumbnailPage(0))),
-    //             Task::done(Action::App(AppMessage::RefreshView)),
-    //         ]);
-    //     }
-    // }
-    Task::none()
-}
-
 fn thumbnail_refresh_subscription(_app: &NoctuaApp) -> Subscription<AppMessage> {
     // TODO: Re-enable when document is synced from DocumentManager
     let needs_refresh = false;
@@ -401,3 +594,29 @@ fn thumbnail_refresh_subscription(_app: &NoctuaApp) -> Subscription<AppMessage>
         Subscription::none()
     }
 }
+
+/// Advance the frames of an animated image while it plays.
+fn animation_subscription(app: &NoctuaApp) -> Subscription<AppMessage> {
+    let delay = app
+        .document_manager
+        .current_document()
+        .and_then(|doc| doc.frame_delay());
+
+    match delay {
+        Some(delay) if animation_playing(app) => {
+            time::every(delay).map(|_| AppMessage::AnimationTick)
+        }
+        _ => Subscription::none(),
+    }
+}
+
+/// Is an animated image advancing its frames?
+fn animation_playing(app: &NoctuaApp) -> bool {
+    // Cropping or transforming works on a still frame.
+    !app.model.animation_paused
+        && matches!(app.model.mode, AppMode::View | AppMode::Fullscreen)
+        && app
+            .document_manager
+            .current_document()
+            .is_some_and(|doc| doc.is_animated())
+}
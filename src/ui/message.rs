@@ -15,13 +15,43 @@ pub enum AppMessage {
     NextDocument,
     PrevDocument,
     GotoPage(usize),
+    NextPage,
+    PrevPage,
     GenerateThumbnailPage(usize),
+    ThumbnailsScrolled(cosmic::iced::widget::scrollable::Viewport),
+    ThumbnailsGenerated(u64, Vec<(usize, cosmic::widget::image::Handle)>),
+
+    // Document links.
+    FollowLink(crate::domain::document::core::page::LinkTarget),
+    OpenLink,
+    HistoryBack,
+    HistoryForward,
+
+    // Panels.
+    SetLeftPanel(crate::ui::model::LeftPanel),
+    ToggleOutlineEntry(Vec<usize>),
+
+    // Page layout (multi-page documents).
+    ToggleTwoPageSpread,
+    ToggleCoverAlone,
+    ToggleRightToLeft,
+    ToggleContinuousScroll,
 
     // Transformations.
     RotateCW,
     RotateCCW,
     FlipHorizontal,
     FlipVertical,
+    SetFineRotation(f32),
+    NudgeFineRotation(f32),
+    ResetFineRotation,
+    RenderFineRotation(u64),
+    FineRotationRendered(
+        u64,
+        Box<crate::domain::document::operations::transform::RotatedImage>,
+    ),
+    SetInterpolationQuality(crate::domain::document::core::document::InterpolationQuality),
+    SetRotationAutoCrop(bool),
 
     // View / zoom.
     ZoomIn,
@@ -35,6 +65,32 @@ pub enum AppMessage {
         canvas_size: cosmic::iced::Size,
         image_size: cosmic::iced::Size,
     },
+    RenderPage(u64),
+    PageRendered(
+        u64,
+        Box<crate::domain::document::operations::render::RenderedPage>,
+    ),
+    PagesScrolled(cosmic::iced::widget::scrollable::Viewport),
+
+    // Text search.
+    ToggleSearch,
+    SearchQueryChanged(String),
+    SearchSubmit,
+    SearchFinished(
+        u64,
+        String,
+        Vec<crate::domain::document::operations::search::SearchHit>,
+    ),
+    SearchNext,
+    SearchPrev,
+    SelectSearchHit(usize),
+
+    // Animation.
+    ToggleAnimation,
+    NextFrame,
+    PrevFrame,
+    AnimationTick,
+    ExportFrame,
 
     // Pan control.
     PanLeft,
@@ -46,6 +102,11 @@ pub enum AppMessage {
     // Tool modes.
     ToggleCropMode,
     ToggleScaleMode,
+    ToggleInspectMode,
+
+    // Pixel inspector.
+    InspectPixel(Option<(u32, u32)>),
+    PickPixel(u32, u32),
 
     // Crop operations.
     StartCrop,
@@ -80,9 +141,34 @@ pub enum AppMessage {
     // Metadata.
     #[allow(dead_code)]
     RefreshMetadata,
+    ToggleAllMetadata,
+    MetadataQueryChanged(String),
+    CopyToClipboard(String),
+    EditMetadata,
+    CancelMetadataEdit,
+    MetadataFieldChanged(
+        crate::domain::document::operations::metadata_edit::MetadataField,
+        String,
+    ),
+    SetStripMetadata(bool),
+    SetStripGps(bool),
+    PreviewMetadataChanges,
+
+    // Histogram.
+    HistogramComputed(u64, Box<super::model::ComputedHistogram>),
+    SetClippingOverlay(bool),
 
     // Save operations.
+    Save,
     SaveAs,
+    SaveAsSelected(PathBuf),
+    ConfirmOverwrite,
+
+    // Dialogs.
+    CloseDialog,
+    PasswordChanged(String),
+    PasswordRememberToggled(bool),
+    SubmitPassword,
 
     // Wallpaper.
     SetAsWallpaper,
@@ -6,15 +6,27 @@
 // AppModel contains ONLY UI-specific state.
 // Document state lives in DocumentManager (application layer).
 
-use cosmic::iced::Size;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use cosmic::iced::{Rectangle, Size};
 
 use crate::ui::widgets::CropSelection;
 use crate::config::AppConfig;
+use crate::domain::document::core::document::{InterpolationQuality, PasswordError};
+use crate::domain::document::core::page::PageHistory;
+use crate::domain::document::operations::color::PixelColor;
+use crate::domain::document::operations::histogram::Histogram;
+use crate::domain::document::operations::metadata_edit::{MetadataChange, MetadataEdit, MetadataField};
+use crate::domain::document::operations::search::SearchResults;
 
 // =============================================================================
 // View Mode
 // =============================================================================
 
+/// Space between pages in the continuous view (logical pixels).
+pub const PAGE_SPACING: f32 = 8.0;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ViewMode {
     #[default]
@@ -101,6 +113,9 @@ pub enum AppMode {
         orientation: Orientation,
     },
 
+    /// Pixel inspector (color picker) with the pixel under the cursor
+    Inspect { pixel: Option<InspectedPixel> },
+
     /// Fullscreen mode (all panels hidden)
     Fullscreen,
 }
@@ -118,16 +133,33 @@ impl AppMode {
             Self::View => Some(RightPanel::Properties),
             Self::Crop { .. } => Some(RightPanel::CropTools),
             Self::Transform { .. } => Some(RightPanel::TransformTools),
+            Self::Inspect { .. } => Some(RightPanel::Properties),
             Self::Fullscreen => None,
         }
     }
 
     /// Check if mode is an active tool (not View/Fullscreen)
     pub fn is_tool_active(&self) -> bool {
-        matches!(self, Self::Crop { .. } | Self::Transform { .. })
+        matches!(
+            self,
+            Self::Crop { .. } | Self::Transform { .. } | Self::Inspect { .. }
+        )
     }
 }
 
+/// A pixel under the cursor in inspect mode.
+#[derive(Debug, Clone)]
+pub struct InspectedPixel {
+    /// Position in the rendered image
+    pub x: u32,
+    pub y: u32,
+
+    pub color: PixelColor,
+
+    /// Magnified surrounding pixels
+    pub loupe: cosmic::widget::image::Handle,
+}
+
 // =============================================================================
 // Viewport (zoom, pan, canvas)
 // =============================================================================
@@ -158,6 +190,15 @@ pub struct Viewport {
 
     /// Cached image handle for rendering (updated when document or scale changes)
     pub cached_image_handle: Option<cosmic::widget::image::Handle>,
+
+    /// Image pixels per logical pixel of the cached handle
+    pub pixel_density: f32,
+
+    /// Counter for zoom-dependent re-renders; older results are dropped
+    pub render_generation: u64,
+
+    /// Visible part of the continuous page view (scroll offset and size)
+    pub pages_view: Rectangle,
 }
 
 impl Default for Viewport {
@@ -171,6 +212,9 @@ impl Default for Viewport {
             fit_mode: ViewMode::Fit,
             scroll_id: cosmic::widget::Id::new("canvas-scroll"),
             cached_image_handle: None,
+            pixel_density: 1.0,
+            render_generation: 0,
+            pages_view: Rectangle::new(cosmic::iced::Point::ORIGIN, Size::ZERO),
         }
     }
 }
@@ -181,6 +225,28 @@ impl Viewport {
         self.pan_x = 0.0;
         self.pan_y = 0.0;
     }
+
+    /// On-screen size of each page in the continuous view.
+    ///
+    /// Fitted pages fill the width of the view, otherwise they follow the
+    /// zoom. `page_sizes` are the page sizes at 100%.
+    #[must_use]
+    pub fn continuous_page_sizes(&self, page_sizes: &[(f64, f64)], view_width: f32) -> Vec<Size> {
+        page_sizes
+            .iter()
+            .map(|&(width, height)| {
+                let (width, height) = (width as f32, height as f32);
+                if width <= 0.0 {
+                    return Size::ZERO;
+                }
+                let shown_width = match self.fit_mode {
+                    ViewMode::Fit => view_width,
+                    ViewMode::ActualSize | ViewMode::Custom => width * self.scale,
+                };
+                Size::new(shown_width, height * shown_width / width)
+            })
+            .collect()
+    }
 }
 
 // =============================================================================
@@ -195,6 +261,49 @@ pub struct PanelState {
 
     /// Right panel (context-dependent tools/properties)
     pub right: Option<RightPanel>,
+
+    /// Outline entries (by child index path) whose expanded state the user
+    /// toggled away from the document default
+    pub outline_toggled: HashSet<Vec<usize>>,
+
+    /// Is the "All metadata" section of the properties panel expanded?
+    pub all_metadata_expanded: bool,
+
+    /// Filter for the "All metadata" section
+    pub metadata_query: String,
+
+    /// Scroll position of the pages panel (None = not scrolled yet)
+    pub thumbnails_view: Option<cosmic::iced::widget::scrollable::Viewport>,
+
+    /// Increased for every document; thumbnails of an earlier one are dropped
+    pub thumbnail_generation: u64,
+
+    /// Whether thumbnails are being made in the background
+    pub thumbnails_pending: bool,
+}
+
+impl PanelState {
+    /// Whether the outline entry at `path` is expanded.
+    #[must_use]
+    pub fn is_outline_expanded(&self, path: &[usize], open_by_default: bool) -> bool {
+        open_by_default != self.outline_toggled.contains(path)
+    }
+
+    /// Expand or collapse the outline entry at `path`.
+    pub fn toggle_outline_entry(&mut self, path: &[usize]) {
+        if !self.outline_toggled.remove(path) {
+            self.outline_toggled.insert(path.to_vec());
+        }
+    }
+
+    /// Forget the outline and thumbnail state of the previous document.
+    ///
+    /// The scroll position stays: the pages panel keeps it across documents.
+    pub fn reset_for_document(&mut self) {
+        self.outline_toggled.clear();
+        self.thumbnail_generation += 1;
+        self.thumbnails_pending = false;
+    }
 }
 
 /// Left panel types
@@ -202,6 +311,9 @@ pub struct PanelState {
 pub enum LeftPanel {
     /// Thumbnail navigation for multi-page documents
     Thumbnails,
+
+    /// Table of contents of documents with an outline
+    Outline,
 }
 
 /// Right panel types
@@ -218,6 +330,168 @@ pub enum RightPanel {
     TransformTools,
 }
 
+// =============================================================================
+// Text Search
+// =============================================================================
+
+/// Text search bar state (documents with a text layer).
+#[derive(Debug, Clone)]
+pub struct SearchState {
+    /// Whether the search bar is shown
+    pub visible: bool,
+
+    /// Current text of the search field
+    pub query: String,
+
+    /// Hits of the last submitted query
+    pub results: Option<SearchResults>,
+
+    /// Whether a background search is running
+    pub searching: bool,
+
+    /// Increased with every search; stale results are dropped
+    pub generation: u64,
+
+    /// Search field ID (for focusing)
+    pub input_id: cosmic::widget::Id,
+}
+
+impl Default for SearchState {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            query: String::new(),
+            results: None,
+            searching: false,
+            generation: 0,
+            input_id: cosmic::widget::Id::new("search-input"),
+        }
+    }
+}
+
+// =============================================================================
+// Histogram
+// =============================================================================
+
+/// Result of a background histogram computation.
+#[derive(Debug, Clone)]
+pub struct ComputedHistogram {
+    pub histogram: Histogram,
+
+    /// Drawn histogram
+    pub plot: cosmic::widget::image::Handle,
+
+    /// Image with clipped pixels tinted (if the overlay is enabled)
+    pub overlay: Option<cosmic::widget::image::Handle>,
+}
+
+/// Histogram panel and clipping overlay state.
+#[derive(Debug, Clone, Default)]
+pub struct HistogramState {
+    /// Rendered image the histogram belongs to
+    pub source: Option<cosmic::iced::advanced::image::Id>,
+
+    /// Increased with every computation; stale results are dropped
+    pub generation: u64,
+
+    /// Last finished computation
+    pub result: Option<ComputedHistogram>,
+
+    /// Whether a computation is running
+    pub computing: bool,
+
+    /// Tint clipped pixels on the canvas
+    pub clipping_overlay: bool,
+}
+
+impl HistogramState {
+    /// The tinted image to show instead of `handle`, if the overlay is on
+    /// and up to date.
+    #[must_use]
+    pub fn overlay_for(
+        &self,
+        handle: &cosmic::widget::image::Handle,
+    ) -> Option<&cosmic::widget::image::Handle> {
+        if !self.clipping_overlay || self.source != Some(handle.id()) {
+            return None;
+        }
+        self.result.as_ref()?.overlay.as_ref()
+    }
+}
+
+// =============================================================================
+// Metadata Editor
+// =============================================================================
+
+/// Metadata fields being edited in the properties panel.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataEditor {
+    /// Values as read from the file
+    pub original: BTreeMap<MetadataField, String>,
+
+    /// Current text of the input fields
+    pub values: BTreeMap<MetadataField, String>,
+
+    /// Changes found by the last dry run (cleared on every edit)
+    pub preview: Option<Vec<MetadataChange>>,
+}
+
+impl MetadataEditor {
+    pub fn new(original: BTreeMap<MetadataField, String>) -> Self {
+        Self {
+            values: original.clone(),
+            original,
+            preview: None,
+        }
+    }
+
+    /// Current text of a field.
+    #[must_use]
+    pub fn value(&self, field: MetadataField) -> &str {
+        self.values.get(&field).map_or("", String::as_str)
+    }
+
+    /// The fields whose text differs from the file.
+    #[must_use]
+    pub fn edit(&self) -> MetadataEdit {
+        let fields = MetadataField::ALL
+            .into_iter()
+            .filter(|field| {
+                self.value(*field).trim() != self.original.get(field).map_or("", |v| v.trim())
+            })
+            .map(|field| (field, self.value(field).to_string()))
+            .collect();
+        MetadataEdit {
+            fields,
+            strip_gps: false,
+        }
+    }
+}
+
+// =============================================================================
+// Dialogs
+// =============================================================================
+
+/// Modal dialog currently shown on top of the window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppDialog {
+    /// Ask before replacing an existing file on save.
+    ConfirmOverwrite { path: PathBuf },
+
+    /// Ask for the password of an encrypted document.
+    Password {
+        path: PathBuf,
+        password: String,
+        /// Remember the password for this session
+        remember: bool,
+        /// The previous attempt used a wrong password
+        incorrect: bool,
+    },
+
+    /// Ask before opening an external link of a document.
+    OpenLink { uri: String },
+}
+
 // =============================================================================
 // AppModel (UI State Only)
 // =============================================================================
@@ -241,24 +515,68 @@ pub struct AppModel {
     /// Panel visibility
     pub panels: PanelState,
 
+    /// Text search state
+    pub search: SearchState,
+
+    /// Pages visited before and after following document links
+    pub page_history: PageHistory,
+
+    /// Is playback of an animated image paused?
+    pub animation_paused: bool,
+
     /// Error message to display
     pub error: Option<String>,
 
     /// Is main menu open?
     pub menu_open: bool,
 
+    /// Active modal dialog (if any)
+    pub dialog: Option<AppDialog>,
+
+    /// Sampling used for fine rotation
+    pub interpolation_quality: InterpolationQuality,
+
+    /// Crop fine rotations to the largest rectangle without empty corners
+    pub rotation_auto_crop: bool,
+
+    /// Counter for full-resolution fine rotations; older results are dropped
+    pub fine_rotation_generation: u64,
+
+    /// Histogram panel state
+    pub histogram: HistogramState,
+
+    /// Metadata editor of the properties panel (None = not editing)
+    pub metadata_editor: Option<MetadataEditor>,
+
+    /// Remove all metadata when saving
+    pub strip_metadata: bool,
+
+    /// Remove the GPS location when saving
+    pub strip_gps: bool,
+
     /// Tick counter for animations
     pub tick: u64,
 }
 
 impl AppModel {
-    pub fn new(_config: AppConfig) -> Self {
+    pub fn new(config: AppConfig) -> Self {
         Self {
             mode: AppMode::default(),
             viewport: Viewport::default(),
             panels: PanelState::default(),
+            search: SearchState::default(),
+            page_history: PageHistory::default(),
+            animation_paused: false,
             error: None,
             menu_open: false,
+            dialog: None,
+            interpolation_quality: InterpolationQuality::default(),
+            rotation_auto_crop: config.rotation_auto_crop,
+            fine_rotation_generation: 0,
+            histogram: HistogramState::default(),
+            metadata_editor: None,
+            strip_metadata: !config.export_preserve_metadata,
+            strip_gps: config.export_strip_gps,
             tick: 0,
         }
     }
@@ -277,4 +595,23 @@ impl AppModel {
     pub fn reset_pan(&mut self) {
         self.viewport.reset_pan();
     }
+
+    /// Show the password dialog after `path` failed to unlock
+    pub fn prompt_password(&mut self, path: PathBuf, error: PasswordError, remember: bool) {
+        self.dialog = Some(AppDialog::Password {
+            path,
+            password: String::new(),
+            remember,
+            incorrect: error == PasswordError::Incorrect,
+        });
+    }
+
+    /// Hide the search bar and drop its results
+    pub fn close_search(&mut self) {
+        self.search.visible = false;
+        self.search.results = None;
+        // A search still running belongs to the closed bar.
+        self.search.searching = false;
+        self.search.generation += 1;
+    }
 }
@@ -3,14 +3,39 @@
 //
 // Application update loop: applies messages to the global model state.
 
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+use cosmic::dialog::file_chooser;
+use cosmic::iced::widget::scrollable::{self, AbsoluteOffset};
+use cosmic::iced::{Rectangle, Size};
+use cosmic::widget::image::Handle as ImageHandle;
 use cosmic::{Action, Task};
+use image::DynamicImage;
 
 use super::NoctuaApp;
 use super::message::AppMessage;
-use super::model::{AppMode, ViewMode};
+use super::model::{
+    AppDialog, AppMode, ComputedHistogram, InspectedPixel, MetadataEditor, PAGE_SPACING, ViewMode,
+};
+use super::views::histogram_panel::{PLOT_HEIGHT, PLOT_WIDTH};
+use super::views::inspector::{LOUPE_RADIUS, LOUPE_ZOOM};
 use crate::application::commands::transform_document::{TransformDocumentCommand, TransformOperation};
 use crate::application::commands::crop_document::CropDocumentCommand;
-use crate::domain::document::core::document::Renderable;
+use crate::application::commands::save_document::SaveDocumentCommand;
+use crate::domain::document::core::content::DocumentContent;
+use crate::domain::document::core::document::{PasswordError, Renderable, Transformable};
+use crate::domain::document::core::page::{LinkTarget, PageHistory, PageLayout, PageStrip};
+use crate::domain::document::operations::color::{self, PixelColor};
+use crate::domain::document::operations::container::MetadataBlocks;
+use crate::domain::document::operations::export::{ExportFormat, ImageExportOptions};
+use crate::domain::document::operations::histogram::{self, Histogram};
+use crate::domain::document::operations::metadata_edit;
+use crate::domain::document::operations::render::{PageRenderJob, ThumbnailJob};
+use crate::domain::document::operations::search::SearchResults;
+use crate::domain::document::operations::transform::FineRotationJob;
+use crate::fl;
 use crate::ui::widgets::{CropSelection, DragHandle};
 
 // =============================================================================
@@ -30,68 +55,143 @@ pub enum UpdateResult {
 pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
     match msg {
         // ---- File / navigation ----------------------------------------------------
-        AppMessage::OpenPath(path) => {
-            if let Err(e) = app.document_manager.open_document(path) {
-                app.model.set_error(format!("Failed to open document: {e}"));
-            } else {
-                app.model.reset_pan();
-                app.model.viewport.fit_mode = ViewMode::Fit;
-                app.model.viewport.scale = 1.0;
-                cache_render(&mut app.model, &mut app.document_manager);
+        AppMessage::OpenPath(path) => match app.document_manager.open_document(path) {
+            Ok(()) => return document_opened(app),
+            Err(e) => open_failed(app, path, &e, false),
+        },
 
-                // Auto-toggle nav bar for multi-page documents
-                app.update_nav_bar_for_document();
+        AppMessage::NextDocument | AppMessage::PrevDocument => {
+            // Ignore navigation in Crop mode
+            if !matches!(app.model.mode, AppMode::Crop { .. }) {
+                let moved = if matches!(msg, AppMessage::NextDocument) {
+                    app.document_manager.next_document()
+                } else {
+                    app.document_manager.previous_document()
+                };
+                match moved {
+                    Some((_, Ok(()))) => return document_opened(app),
+                    Some((path, Err(e))) => {
+                        // The previous document is closed; clear its view too.
+                        let result = document_opened(app);
+                        open_failed(app, &path, &e, false);
+                        return result;
+                    }
+                    None => {}
+                }
             }
         }
 
-        AppMessage::NextDocument => {
-            // Ignore navigation in Crop mode
-            if !matches!(app.model.mode, AppMode::Crop { .. })
-                && let Some(_path) = app.document_manager.next_document()
-            {
-                // Reset zoom when navigating to new document
-                app.model.viewport.scale = 1.0;
-                app.model.viewport.fit_mode = ViewMode::Fit;
-                app.model.reset_pan();
-                cache_render(&mut app.model, &mut app.document_manager);
+        AppMessage::GotoPage(page) => return go_to_page(app, *page),
 
-                // Auto-toggle nav bar for multi-page documents
-                app.update_nav_bar_for_document();
+        AppMessage::NextPage | AppMessage::PrevPage => {
+            let forward = matches!(msg, AppMessage::NextPage);
+            let target = app.document_manager.current_document().and_then(|doc| {
+                if forward {
+                    doc.next_page()
+                } else {
+                    doc.prev_page()
+                }
+            });
+            if let Some(page) = target {
+                return go_to_page(app, page);
             }
         }
 
-        AppMessage::PrevDocument => {
-            // Ignore navigation in Crop mode
-            if !matches!(app.model.mode, AppMode::Crop { .. })
-                && let Some(_path) = app.document_manager.previous_document()
+        // ---- Document links ----------------------------------------------------------
+        AppMessage::FollowLink(target) => match target {
+            LinkTarget::Page(page) => {
+                let current = current_page(app);
+                if *page != current {
+                    app.model.page_history.push(current);
+                    return go_to_page(app, *page);
+                }
+            }
+            LinkTarget::Uri(uri) => {
+                app.model.dialog = Some(AppDialog::OpenLink { uri: uri.clone() });
+            }
+        },
+
+        AppMessage::OpenLink => {
+            if let Some(AppDialog::OpenLink { uri }) = app.model.dialog.take()
+                && let Err(e) = open::that_detached(&uri)
             {
-                // Reset zoom when navigating to new document
-                app.model.viewport.scale = 1.0;
-                app.model.viewport.fit_mode = ViewMode::Fit;
-                app.model.reset_pan();
-                cache_render(&mut app.model, &mut app.document_manager);
+                app.model
+                    .set_error(format!("Failed to open link {uri}: {e}"));
+            }
+        }
 
-                // Auto-toggle nav bar for multi-page documents
-                app.update_nav_bar_for_document();
+        AppMessage::HistoryBack => {
+            let current = current_page(app);
+            if let Some(page) = app.model.page_history.back(current) {
+                return go_to_page(app, page);
             }
         }
 
-        AppMessage::GotoPage(page) => {
-            if let Some(doc) = app.document_manager.current_document_mut() {
-                if let Err(e) = doc.go_to_page(*page) {
-                    log::error!("Failed to navigate to page {page}: {e}");
-                } else {
-                    cache_render(&mut app.model, &mut app.document_manager);
-                }
+        AppMessage::HistoryForward => {
+            let current = current_page(app);
+            if let Some(page) = app.model.page_history.forward(current) {
+                return go_to_page(app, page);
             }
         }
 
+        // ---- Page layout -------------------------------------------------------------
+        AppMessage::ToggleTwoPageSpread => {
+            let mut layout = app.document_manager.page_layout();
+            layout.two_page_spread = !layout.two_page_spread;
+            app.config.two_page_spread = layout.two_page_spread;
+            return set_page_layout(app, layout);
+        }
+
+        AppMessage::ToggleCoverAlone => {
+            let mut layout = app.document_manager.page_layout();
+            layout.cover_alone = !layout.cover_alone;
+            app.config.cover_alone = layout.cover_alone;
+            return set_page_layout(app, layout);
+        }
+
+        AppMessage::ToggleRightToLeft => {
+            let mut layout = app.document_manager.page_layout();
+            layout.right_to_left = !layout.right_to_left;
+            app.config.right_to_left = layout.right_to_left;
+            return set_page_layout(app, layout);
+        }
+
+        AppMessage::ToggleContinuousScroll => {
+            let mut layout = app.document_manager.page_layout();
+            layout.continuous = !layout.continuous;
+            app.config.continuous_scroll = layout.continuous;
+            return set_page_layout(app, layout);
+        }
+
+        // ---- Panels --------------------------------------------------------------
+        AppMessage::SetLeftPanel(panel) => {
+            app.model.panels.left = Some(*panel);
+        }
+
+        AppMessage::ToggleOutlineEntry(path) => {
+            app.model.panels.toggle_outline_entry(path);
+        }
+
         // ---- Thumbnail generation -------------------------------------------------
         AppMessage::GenerateThumbnailPage(_page) => {
             // TODO: Thumbnail generation via DocumentManager
             // Currently handled by DocumentManager.open_document()
         }
 
+        AppMessage::ThumbnailsScrolled(viewport) => {
+            app.model.panels.thumbnails_view = Some(*viewport);
+        }
+
+        AppMessage::ThumbnailsGenerated(generation, thumbnails) => {
+            let panels = &mut app.model.panels;
+            if *generation == panels.thumbnail_generation {
+                panels.thumbnails_pending = false;
+                if let Some(doc) = app.document_manager.current_document_mut() {
+                    doc.set_thumbnails(thumbnails.clone());
+                }
+            }
+        }
+
         AppMessage::RefreshView => {
             app.model.tick += 1;
         }
@@ -100,22 +200,26 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
         AppMessage::ZoomIn => {
             app.model.viewport.scale = (app.model.viewport.scale * 1.2).min(10.0);
             app.model.viewport.fit_mode = ViewMode::Custom;
+            return schedule_page_render(&mut app.model);
         }
 
         AppMessage::ZoomOut => {
             app.model.viewport.scale = (app.model.viewport.scale / 1.2).max(0.1);
             app.model.viewport.fit_mode = ViewMode::Custom;
+            return schedule_page_render(&mut app.model);
         }
 
         AppMessage::ZoomReset => {
             app.model.viewport.scale = 1.0;
             app.model.viewport.fit_mode = ViewMode::ActualSize;
             app.model.reset_pan();
+            return schedule_page_render(&mut app.model);
         }
 
         AppMessage::ZoomFit => {
             app.model.viewport.fit_mode = ViewMode::Fit;
             app.model.reset_pan();
+            return schedule_page_render(&mut app.model);
         }
 
         AppMessage::ViewerStateChanged {
@@ -127,6 +231,7 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
         } => {
             // Detect scale changes (zoom vs just pan)
             let old_scale = app.model.viewport.scale;
+            let old_canvas_size = app.model.viewport.canvas_size;
 
             // Update model from viewer state
             app.model.viewport.scale = *scale;
@@ -140,6 +245,141 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
             if (old_scale - *scale).abs() > 0.001 {
                 app.model.viewport.fit_mode = ViewMode::Custom;
                 cache_render(&mut app.model, &mut app.document_manager);
+                return schedule_page_render(&mut app.model);
+            }
+
+            // A resized canvas changes the zoom of a fitted page.
+            if app.model.viewport.fit_mode == ViewMode::Fit && old_canvas_size != *canvas_size {
+                return schedule_page_render(&mut app.model);
+            }
+        }
+
+        AppMessage::RenderPage(generation) => {
+            if *generation == app.model.viewport.render_generation
+                && let Some(task) = start_page_render(app, *generation)
+            {
+                return UpdateResult::Task(task);
+            }
+        }
+
+        AppMessage::PageRendered(generation, page) => {
+            if *generation == app.model.viewport.render_generation
+                && let Some(doc) = app.document_manager.current_document_mut()
+                && doc.apply_rendered_page(page.as_ref().clone())
+            {
+                cache_render(&mut app.model, &mut app.document_manager);
+            }
+        }
+
+        AppMessage::PagesScrolled(viewport) => {
+            let offset = viewport.absolute_offset();
+            let bounds = viewport.bounds();
+            app.model.viewport.pages_view =
+                Rectangle::new(cosmic::iced::Point::new(offset.x, offset.y), bounds.size());
+            return pages_scrolled(app);
+        }
+
+        // ---- Text search ---------------------------------------------------------
+        AppMessage::ToggleSearch => {
+            if app.model.search.visible {
+                app.model.close_search();
+            } else if app
+                .document_manager
+                .current_document()
+                .is_some_and(|doc| doc.supports_text_search())
+            {
+                app.model.search.visible = true;
+                let input_id = app.model.search.input_id.clone();
+                return UpdateResult::Task(cosmic::widget::text_input::focus(input_id));
+            }
+        }
+
+        AppMessage::SearchQueryChanged(query) => {
+            app.model.search.query.clone_from(query);
+        }
+
+        AppMessage::SearchSubmit => {
+            let query = app.model.search.query.trim().to_string();
+            let is_repeat = app
+                .model
+                .search
+                .results
+                .as_ref()
+                .is_some_and(|results| results.query() == query);
+
+            // Enter on an unchanged query moves on to the next hit.
+            if is_repeat {
+                if let Some(results) = app.model.search.results.as_mut() {
+                    results.next();
+                }
+                return show_search_hit(app);
+            }
+            return start_search(app, &query);
+        }
+
+        AppMessage::SearchFinished(generation, query, hits) => {
+            if *generation != app.model.search.generation {
+                return UpdateResult::None;
+            }
+            app.model.search.searching = false;
+            if let Some(doc) = app.document_manager.current_document() {
+                log::info!("Found {} matches for \"{query}\"", hits.len());
+                app.model.search.results = Some(SearchResults::new(
+                    query.clone(),
+                    hits.clone(),
+                    doc.current_page(),
+                ));
+                return show_search_hit(app);
+            }
+        }
+
+        AppMessage::SearchNext => {
+            if let Some(results) = app.model.search.results.as_mut() {
+                results.next();
+                return show_search_hit(app);
+            }
+        }
+
+        AppMessage::SearchPrev => {
+            if let Some(results) = app.model.search.results.as_mut() {
+                results.prev();
+                return show_search_hit(app);
+            }
+        }
+
+        AppMessage::SelectSearchHit(index) => {
+            if let Some(results) = app.model.search.results.as_mut() {
+                results.select(*index);
+                return show_search_hit(app);
+            }
+        }
+
+        // ---- Animation -----------------------------------------------------------
+        AppMessage::ToggleAnimation => {
+            if is_animated(app) {
+                app.model.animation_paused = !app.model.animation_paused;
+            }
+        }
+
+        AppMessage::NextFrame => {
+            app.model.animation_paused = true;
+            step_frame(app, true);
+        }
+
+        AppMessage::PrevFrame => {
+            app.model.animation_paused = true;
+            step_frame(app, false);
+        }
+
+        AppMessage::AnimationTick => {
+            if !app.model.animation_paused {
+                step_frame(app, true);
+            }
+        }
+
+        AppMessage::ExportFrame => {
+            if let Some(task) = export_frame(&app.document_manager) {
+                return UpdateResult::Task(task);
             }
         }
 
@@ -181,6 +421,33 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
             };
         }
 
+        AppMessage::ToggleInspectMode => {
+            app.model.mode = match &app.model.mode {
+                AppMode::Inspect { .. } => AppMode::View,
+                // The continuous view has no single image to inspect.
+                _ if is_continuous(app) => return UpdateResult::None,
+                _ => AppMode::Inspect { pixel: None },
+            };
+        }
+
+        // ---- Pixel inspector -----------------------------------------------------
+        AppMessage::InspectPixel(position) => {
+            let inspected = position.and_then(|(x, y)| inspect_pixel(&app.document_manager, x, y));
+            if let AppMode::Inspect { pixel } = &mut app.model.mode {
+                *pixel = inspected;
+            }
+        }
+
+        AppMessage::PickPixel(x, y) => {
+            if let Some(color) = app
+                .document_manager
+                .current_document()
+                .and_then(|doc| PixelColor::at(doc.rendered_image(), *x, *y))
+            {
+                return UpdateResult::Task(cosmic::iced::clipboard::write(color.hex()));
+            }
+        }
+
         // ---- Crop operations -----------------------------------------------------
         AppMessage::StartCrop => {
             if app.document_manager.current_document().is_some() {
@@ -261,8 +528,88 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
         }
 
         // ---- Save operations -----------------------------------------------------
+        AppMessage::Save => {
+            if let Some(path) = app.document_manager.current_path() {
+                // Formats we cannot write back (PDF, SVG) go through Save As,
+                // and so do animations, which would be saved as a single frame.
+                if is_writable_format(path) && !is_animated(app) {
+                    app.model.dialog = Some(AppDialog::ConfirmOverwrite {
+                        path: path.to_path_buf(),
+                    });
+                } else if let Some(task) = save_as(&app.document_manager) {
+                    return UpdateResult::Task(task);
+                }
+            }
+        }
+
         AppMessage::SaveAs => {
-            save_as(&mut app.model);
+            if let Some(task) = save_as(&app.document_manager) {
+                return UpdateResult::Task(task);
+            }
+        }
+
+        AppMessage::SaveAsSelected(path) => {
+            // Default to PNG when the chosen name has no known extension.
+            let path = if ExportFormat::from_path(path).is_some() {
+                path.clone()
+            } else {
+                path.with_extension("png")
+            };
+
+            // A single frame would replace the animation in its own file.
+            if is_animated(app) && app.document_manager.current_path() == Some(path.as_path()) {
+                app.model.set_error(
+                    "Saving over the animation would keep only the current frame; \
+                     choose another file name"
+                        .to_string(),
+                );
+            } else if path.exists() {
+                app.model.dialog = Some(AppDialog::ConfirmOverwrite { path });
+            } else {
+                save_document(app, &path);
+            }
+        }
+
+        AppMessage::ConfirmOverwrite => {
+            if let Some(AppDialog::ConfirmOverwrite { path }) = app.model.dialog.take() {
+                save_document(app, &path);
+            }
+        }
+
+        // ---- Dialogs -------------------------------------------------------------
+        AppMessage::CloseDialog => {
+            app.model.dialog = None;
+        }
+
+        AppMessage::PasswordChanged(value) => {
+            if let Some(AppDialog::Password { password, .. }) = &mut app.model.dialog {
+                password.clone_from(value);
+            }
+        }
+
+        AppMessage::PasswordRememberToggled(value) => {
+            if let Some(AppDialog::Password { remember, .. }) = &mut app.model.dialog {
+                *remember = *value;
+            }
+        }
+
+        AppMessage::SubmitPassword => {
+            if let Some(AppDialog::Password {
+                path,
+                password,
+                remember,
+                ..
+            }) = app.model.dialog.take()
+            {
+                match app.document_manager.open_document_with_password(
+                    &path,
+                    Some(&password),
+                    remember,
+                ) {
+                    Ok(()) => return document_opened(app),
+                    Err(e) => open_failed(app, &path, &e, remember),
+                }
+            }
         }
 
         // ---- Document transformations --------------------------------------------
@@ -274,6 +621,7 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
                     app.model.set_error(format!("Flip horizontal failed: {e}"));
                 } else {
                     cache_render(&mut app.model, &mut app.document_manager);
+                    return schedule_page_render(&mut app.model);
                 }
             }
         }
@@ -286,6 +634,7 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
                     app.model.set_error(format!("Flip vertical failed: {e}"));
                 } else {
                     cache_render(&mut app.model, &mut app.document_manager);
+                    return schedule_page_render(&mut app.model);
                 }
             }
         }
@@ -298,6 +647,7 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
                     app.model.set_error(format!("Rotate clockwise failed: {e}"));
                 } else {
                     cache_render(&mut app.model, &mut app.document_manager);
+                    return schedule_page_render(&mut app.model);
                 }
             }
         }
@@ -310,14 +660,166 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
                     app.model.set_error(format!("Rotate CCW failed: {e}"));
                 } else {
                     cache_render(&mut app.model, &mut app.document_manager);
+                    return schedule_page_render(&mut app.model);
                 }
             }
         }
 
+        AppMessage::SetFineRotation(angle) => {
+            if !matches!(app.model.mode, AppMode::Crop { .. }) {
+                return rotate_fine(app, *angle);
+            }
+        }
+
+        AppMessage::NudgeFineRotation(delta) => {
+            if !matches!(app.model.mode, AppMode::Crop { .. })
+                && let Some(doc) = app.document_manager.current_document()
+            {
+                // Round away float drift so repeated nudges land on 0.1° steps.
+                let angle = ((doc.fine_rotation() + delta) * 10.0).round() / 10.0;
+                return rotate_fine(app, angle);
+            }
+        }
+
+        AppMessage::RenderFineRotation(generation) => {
+            if *generation == app.model.fine_rotation_generation
+                && let Some(job) = app
+                    .document_manager
+                    .current_document()
+                    .and_then(DocumentContent::fine_rotation_job)
+            {
+                return UpdateResult::Task(fine_rotation_task(job, *generation));
+            }
+        }
+
+        AppMessage::FineRotationRendered(generation, rotated) => {
+            if *generation == app.model.fine_rotation_generation
+                && let Some(doc) = app.document_manager.current_document_mut()
+                && doc.apply_fine_rotation(rotated.as_ref().clone())
+            {
+                cache_render(&mut app.model, &mut app.document_manager);
+            }
+        }
+
+        AppMessage::ResetFineRotation => {
+            let cmd = TransformDocumentCommand::new(TransformOperation::ResetFineRotation);
+            if let Err(e) = cmd.execute(&mut app.document_manager) {
+                app.model.set_error(format!("Reset rotation failed: {e}"));
+            } else {
+                cache_render(&mut app.model, &mut app.document_manager);
+            }
+        }
+
+        AppMessage::SetInterpolationQuality(quality) => {
+            app.model.interpolation_quality = *quality;
+            let cmd = TransformDocumentCommand::new(TransformOperation::SetInterpolation(*quality));
+            if cmd.execute(&mut app.document_manager).is_ok() {
+                cache_render(&mut app.model, &mut app.document_manager);
+            }
+        }
+
+        AppMessage::SetRotationAutoCrop(enabled) => {
+            app.model.rotation_auto_crop = *enabled;
+            let cmd = TransformDocumentCommand::new(TransformOperation::SetAutoCrop(*enabled));
+            if cmd.execute(&mut app.document_manager).is_ok() {
+                cache_render(&mut app.model, &mut app.document_manager);
+            }
+        }
+
         // ---- Metadata ------------------------------------------------------------
         AppMessage::RefreshMetadata => {
-            // Metadata is managed by DocumentManager
-            // Nothing to do here - views access it directly
+            app.document_manager.refresh_metadata();
+        }
+
+        AppMessage::ToggleAllMetadata => {
+            app.model.panels.all_metadata_expanded = !app.model.panels.all_metadata_expanded;
+        }
+
+        AppMessage::MetadataQueryChanged(query) => {
+            app.model.panels.metadata_query.clone_from(query);
+        }
+
+        AppMessage::CopyToClipboard(text) => {
+            return UpdateResult::Task(cosmic::iced::clipboard::write(text.clone()));
+        }
+
+        // ---- Metadata editing ----------------------------------------------------
+        AppMessage::EditMetadata => {
+            if let Some(path) = app.document_manager.current_path() {
+                match MetadataBlocks::from_file(path) {
+                    Ok(blocks) => {
+                        let fields = metadata_edit::read_fields(&blocks);
+                        app.model.metadata_editor = Some(MetadataEditor::new(fields));
+                    }
+                    Err(e) => app.model.set_error(format!("Could not read metadata: {e}")),
+                }
+            }
+        }
+
+        AppMessage::CancelMetadataEdit => {
+            app.model.metadata_editor = None;
+        }
+
+        AppMessage::MetadataFieldChanged(field, value) => {
+            if let Some(editor) = &mut app.model.metadata_editor {
+                editor.values.insert(*field, value.clone());
+                editor.preview = None;
+            }
+        }
+
+        AppMessage::SetStripMetadata(enabled) => {
+            app.model.strip_metadata = *enabled;
+            app.config.export_preserve_metadata = !*enabled;
+            if let Some(editor) = &mut app.model.metadata_editor {
+                editor.preview = None;
+            }
+        }
+
+        AppMessage::SetStripGps(enabled) => {
+            app.model.strip_gps = *enabled;
+            app.config.export_strip_gps = *enabled;
+            if let Some(editor) = &mut app.model.metadata_editor {
+                editor.preview = None;
+            }
+        }
+
+        AppMessage::PreviewMetadataChanges => {
+            // Preview a save over the current file, or the PNG copy Save As
+            // suggests for formats that cannot be written back.
+            let Some(source) = app.document_manager.current_path() else {
+                return UpdateResult::None;
+            };
+            let target = if is_writable_format(source) {
+                source.to_path_buf()
+            } else {
+                source.with_extension("png")
+            };
+            match save_command(app).preview_metadata(&app.document_manager, &target) {
+                Ok(changes) => {
+                    if let Some(editor) = &mut app.model.metadata_editor {
+                        editor.preview = Some(changes);
+                    }
+                }
+                Err(e) => app.model.set_error(format!("Metadata preview failed: {e}")),
+            }
+        }
+
+        // ---- Histogram -----------------------------------------------------------
+        AppMessage::HistogramComputed(generation, result) => {
+            let state = &mut app.model.histogram;
+            if *generation == state.generation {
+                state.result = Some(result.as_ref().clone());
+                state.computing = false;
+            }
+        }
+
+        AppMessage::SetClippingOverlay(enabled) => {
+            let state = &mut app.model.histogram;
+            state.clipping_overlay = *enabled;
+            // Recompute so that the overlay image is created.
+            if *enabled {
+                state.source = None;
+            }
         }
 
         // ---- Format operations ---------------------------------------------------
@@ -377,6 +879,211 @@ pub fn update(app: &mut NoctuaApp, msg: &AppMessage) -> UpdateResult {
 // Helper Functions
 // =============================================================================
 
+/// Reset the view for a freshly opened document.
+fn document_opened(app: &mut NoctuaApp) -> UpdateResult {
+    app.model.reset_pan();
+    app.model.viewport.fit_mode = ViewMode::Fit;
+    app.model.viewport.scale = 1.0;
+    app.model.close_search();
+    app.model.page_history = PageHistory::default();
+    app.model.metadata_editor = None;
+    app.model.animation_paused = false;
+    leave_inspect_if_continuous(app);
+    cache_render(&mut app.model, &mut app.document_manager);
+
+    // Auto-toggle nav bar for multi-page documents
+    app.update_nav_bar_for_document();
+    scroll_to_start(app)
+}
+
+/// Show a newly opened document from the top of the continuous view.
+fn scroll_to_start(app: &mut NoctuaApp) -> UpdateResult {
+    if !is_continuous(app) {
+        return UpdateResult::None;
+    }
+    app.model.viewport.pages_view.x = 0.0;
+    scroll_to_page(app, 0)
+}
+
+/// Show a page of the current document.
+fn go_to_page(app: &mut NoctuaApp, page: usize) -> UpdateResult {
+    // Picking a frame from the pages panel stops playback on it.
+    app.model.animation_paused |= is_animated(app);
+    if let Some(doc) = app.document_manager.current_document_mut() {
+        if let Err(e) = doc.go_to_page(page) {
+            log::error!("Failed to navigate to page {page}: {e}");
+        } else if is_continuous(app) {
+            return scroll_to_page(app, page);
+        } else {
+            cache_render(&mut app.model, &mut app.document_manager);
+            return schedule_page_render(&mut app.model);
+        }
+    }
+    UpdateResult::None
+}
+
+/// Index of the page shown, or the first one of a spread.
+fn current_page(app: &NoctuaApp) -> usize {
+    app.document_manager
+        .current_document()
+        .map_or(0, DocumentContent::current_page)
+}
+
+/// Show the current and later documents with a new page arrangement.
+fn set_page_layout(app: &mut NoctuaApp, layout: PageLayout) -> UpdateResult {
+    if let Err(e) = app.document_manager.set_page_layout(layout) {
+        app.model.set_error(format!("Failed to show pages: {e}"));
+    }
+    leave_inspect_if_continuous(app);
+    cache_render(&mut app.model, &mut app.document_manager);
+
+    // The continuous view opens at the current page.
+    let page = app
+        .document_manager
+        .current_document()
+        .map(|doc| doc.current_page());
+    match page {
+        Some(page) if is_continuous(app) => scroll_to_page(app, page),
+        _ => schedule_page_render(&mut app.model),
+    }
+}
+
+/// Leave inspect mode if the current document is shown in the continuous
+/// view, which has no single image to inspect.
+fn leave_inspect_if_continuous(app: &mut NoctuaApp) {
+    if is_continuous(app) && matches!(app.model.mode, AppMode::Inspect { .. }) {
+        app.model.mode = AppMode::View;
+    }
+}
+
+/// Is the current document shown in the continuous view?
+fn is_continuous(app: &NoctuaApp) -> bool {
+    app.document_manager
+        .current_document()
+        .and_then(DocumentContent::page_layout)
+        .is_some_and(|layout| layout.continuous)
+}
+
+/// Positions of the pages in the continuous view and the pages to render
+/// around the visible ones.
+fn page_strip(app: &NoctuaApp) -> Option<(Vec<Size>, PageStrip, Range<usize>)> {
+    let doc = app.document_manager.current_document()?;
+    let view = app.model.viewport.pages_view;
+
+    // Before the first scroll event the canvas size is the best guess.
+    let view_size = if view.width > 0.0 {
+        view.size()
+    } else {
+        app.model.viewport.canvas_size
+    };
+    let sizes = app.model.viewport.continuous_page_sizes(
+        &doc.page_sizes(),
+        (view_size.width - 2.0 * PAGE_SPACING).max(0.0),
+    );
+    let strip = PageStrip::new(sizes.iter().map(|size| size.height), PAGE_SPACING);
+
+    let visible = if view_size.height > 0.0 {
+        strip.pages_in_view(view.y, view_size.height)
+    } else {
+        let page = doc.current_page();
+        page..page + 1
+    };
+    // One page of margin, so that pages are ready before they scroll in.
+    let pages = visible.start.saturating_sub(1)..(visible.end + 1).min(sizes.len());
+
+    Some((sizes, strip, pages))
+}
+
+/// Scroll the continuous view to the top of `page`.
+fn scroll_to_page(app: &mut NoctuaApp, page: usize) -> UpdateResult {
+    let Some((_, strip, _)) = page_strip(app) else {
+        return UpdateResult::None;
+    };
+
+    let view = &mut app.model.viewport.pages_view;
+    view.y = strip.top(page);
+    let offset = AbsoluteOffset {
+        x: view.x,
+        y: view.y,
+    };
+    let scroll = scrollable::scroll_to(app.model.viewport.scroll_id.clone(), offset);
+
+    match schedule_page_render(&mut app.model) {
+        UpdateResult::Task(render) => UpdateResult::Task(Task::batch([scroll, render])),
+        UpdateResult::None => UpdateResult::Task(scroll),
+    }
+}
+
+/// Follow the continuous view: the page in view becomes the current one
+/// and pages that scrolled in get rendered.
+fn pages_scrolled(app: &mut NoctuaApp) -> UpdateResult {
+    let Some((_, strip, pages)) = page_strip(app) else {
+        return UpdateResult::None;
+    };
+    let view = app.model.viewport.pages_view;
+    let page = strip.current_page(view.y, view.height);
+
+    let Some(doc) = app.document_manager.current_document_mut() else {
+        return UpdateResult::None;
+    };
+    // Keep memory bounded on long documents.
+    doc.retain_page_rasters(pages);
+    if page != doc.current_page()
+        && let Err(e) = doc.go_to_page(page)
+    {
+        log::error!("Failed to track page {page}: {e}");
+    }
+
+    schedule_page_render(&mut app.model)
+}
+
+/// Is the current document an animated image?
+fn is_animated(app: &NoctuaApp) -> bool {
+    app.document_manager
+        .current_document()
+        .is_some_and(|doc| doc.is_animated())
+}
+
+/// Show the next or previous animation frame, wrapping around at the ends.
+fn step_frame(app: &mut NoctuaApp, forward: bool) {
+    let Some(doc) = app.document_manager.current_document_mut() else {
+        return;
+    };
+    if !doc.is_animated() {
+        return;
+    }
+
+    let count = doc.page_count();
+    let frame = if forward {
+        (doc.current_page() + 1) % count
+    } else {
+        (doc.current_page() + count - 1) % count
+    };
+    // During playback only the display handle changes; the pixels follow
+    // once it stops.
+    let result = if app.model.animation_paused {
+        doc.go_to_page(frame)
+    } else {
+        doc.show_frame(frame)
+    };
+    if let Err(e) = result {
+        log::error!("Failed to show frame {frame}: {e}");
+        return;
+    }
+    cache_render(&mut app.model, &mut app.document_manager);
+}
+
+/// Ask for a password for encrypted documents, report other errors.
+fn open_failed(app: &mut NoctuaApp, path: &Path, error: &anyhow::Error, remember: bool) {
+    if let Some(password_error) = error.downcast_ref::<PasswordError>() {
+        app.model
+            .prompt_password(path.to_path_buf(), *password_error, remember);
+    } else {
+        app.model
+            .set_error(format!("Failed to open document: {error:#}"));
+    }
+}
+
 /// Cache rendered image handle in viewport for view performance.
 fn cache_render(
     model: &mut super::model::AppModel,
@@ -386,6 +1093,15 @@ fn cache_render(
         match doc.render(model.viewport.scale as f64) {
             Ok(output) => {
                 model.viewport.cached_image_handle = Some(output.handle);
+                model.viewport.pixel_density = doc.pixel_density();
+
+                // The inspected pixel may have changed color or position.
+                if let AppMode::Inspect { pixel: Some(pixel) } = &model.mode {
+                    let (x, y) = (pixel.x, pixel.y);
+                    model.mode = AppMode::Inspect {
+                        pixel: inspect_pixel(manager, x, y),
+                    };
+                }
             }
             Err(e) => {
                 log::error!("Failed to cache render: {e}");
@@ -397,8 +1113,400 @@ fn cache_render(
     }
 }
 
-fn save_as(model: &mut super::model::AppModel) {
-    // TODO: Implement file dialog for save path
-    // For now, show error that this needs UI integration
-    model.set_error("Save As: File dialog not yet implemented".to_string());
+/// Sample the pixel at `(x, y)` of the rendered image for the inspector.
+fn inspect_pixel(
+    manager: &crate::application::DocumentManager,
+    x: u32,
+    y: u32,
+) -> Option<InspectedPixel> {
+    let image = manager.current_document()?.rendered_image();
+    let color = PixelColor::at(image, x, y)?;
+    let loupe = color::loupe(image, x, y, LOUPE_RADIUS, LOUPE_ZOOM);
+    Some(InspectedPixel {
+        x,
+        y,
+        color,
+        loupe: ImageHandle::from_rgba(loupe.width(), loupe.height(), loupe.into_raw()),
+    })
+}
+
+/// Debounce delay before a page is re-rendered for a new zoom level.
+const RENDER_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Schedule a re-render of the current page once zooming has settled.
+///
+/// Every call starts a new generation, so only the last request in a burst
+/// of zoom steps actually renders.
+fn schedule_page_render(model: &mut super::model::AppModel) -> UpdateResult {
+    model.viewport.render_generation += 1;
+    let generation = model.viewport.render_generation;
+
+    UpdateResult::Task(cosmic::task::future(async move {
+        tokio::time::sleep(RENDER_DEBOUNCE).await;
+        AppMessage::RenderPage(generation)
+    }))
+}
+
+/// Render the current page at the on-screen resolution on a worker thread.
+///
+/// The current raster stays visible until the result arrives.
+fn start_page_render(app: &NoctuaApp, generation: u64) -> Option<Task<Action<AppMessage>>> {
+    if is_continuous(app) {
+        return start_continuous_render(app, generation);
+    }
+
+    let doc = app.document_manager.current_document()?;
+    let viewport = &app.model.viewport;
+
+    let mut zoom = f64::from(viewport.scale);
+    if viewport.fit_mode == ViewMode::Fit {
+        let (width, height) = doc.dimensions();
+        if width == 0 || height == 0 || viewport.canvas_size.width <= 0.0 {
+            return None;
+        }
+        let fit = (viewport.canvas_size.width / width as f32)
+            .min(viewport.canvas_size.height / height as f32);
+        zoom *= f64::from(fit);
+    }
+    zoom *= f64::from(cosmic::Application::core(app).scale_factor());
+
+    let job = doc.render_job(zoom)?;
+    Some(render_task(job, generation))
+}
+
+/// Render the pages in and around the continuous view on worker threads.
+///
+/// Pages show their thumbnail until the render arrives.
+fn start_continuous_render(app: &NoctuaApp, generation: u64) -> Option<Task<Action<AppMessage>>> {
+    let doc = app.document_manager.current_document()?;
+    let (sizes, _, pages) = page_strip(app)?;
+    let page_sizes = doc.page_sizes();
+    let scale_factor = f64::from(cosmic::Application::core(app).scale_factor());
+
+    let tasks: Vec<_> = pages
+        .filter_map(|page| {
+            // Without a known view width, render at 100%.
+            let width = page_sizes.get(page)?.0;
+            let shown = f64::from(sizes[page].width);
+            let zoom = if shown > 0.0 { shown / width } else { 1.0 } * scale_factor;
+            Some(doc.page_render_jobs(page..page + 1, zoom))
+        })
+        .flatten()
+        .map(|job| render_task(job, generation))
+        .collect();
+
+    (!tasks.is_empty()).then(|| Task::batch(tasks))
+}
+
+/// Run a `PageRenderJob` on a worker thread.
+fn render_task(job: PageRenderJob, generation: u64) -> Task<Action<AppMessage>> {
+    cosmic::task::future(async move {
+        let result =
+            tokio::task::spawn_blocking(move || DocumentContent::run_render_job(job)).await;
+        match result {
+            Ok(Ok(page)) => AppMessage::PageRendered(generation, Box::new(page)),
+            Ok(Err(e)) => {
+                log::error!("Failed to render page: {e}");
+                AppMessage::NoOp
+            }
+            Err(e) => {
+                log::error!("Page render task failed: {e}");
+                AppMessage::NoOp
+            }
+        }
+    })
+}
+
+/// Make page thumbnails on a worker thread.
+///
+/// On failure the job stays pending, so it is not retried until the next
+/// document.
+pub fn thumbnail_task(job: ThumbnailJob, generation: u64) -> Task<Action<AppMessage>> {
+    cosmic::task::future(async move {
+        let result =
+            tokio::task::spawn_blocking(move || DocumentContent::run_thumbnail_job(&job)).await;
+        match result {
+            Ok(Ok(thumbnails)) => AppMessage::ThumbnailsGenerated(generation, thumbnails),
+            Ok(Err(e)) => {
+                log::error!("Failed to generate thumbnails: {e}");
+                AppMessage::NoOp
+            }
+            Err(e) => {
+                log::error!("Thumbnail task failed: {e}");
+                AppMessage::NoOp
+            }
+        }
+    })
+}
+
+/// Compute the histogram (and the clipping overlay) of an image on a worker
+/// thread.
+pub fn histogram_task(
+    image: DynamicImage,
+    generation: u64,
+    with_overlay: bool,
+) -> Task<Action<AppMessage>> {
+    cosmic::task::future(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            let histogram = Histogram::from_image(&image);
+            let plot = histogram.plot(PLOT_WIDTH, PLOT_HEIGHT);
+            let plot = ImageHandle::from_rgba(plot.width(), plot.height(), plot.into_raw());
+            let overlay = with_overlay.then(|| {
+                let overlay = histogram::clipping_overlay(&image);
+                ImageHandle::from_rgba(overlay.width(), overlay.height(), overlay.into_raw())
+            });
+            ComputedHistogram {
+                histogram,
+                plot,
+                overlay,
+            }
+        })
+        .await;
+        match result {
+            Ok(result) => AppMessage::HistogramComputed(generation, Box::new(result)),
+            Err(e) => {
+                log::error!("Histogram task failed: {e}");
+                AppMessage::NoOp
+            }
+        }
+    })
+}
+
+/// Search the current document for `query` on a worker thread.
+///
+/// The hits arrive as `SearchFinished`; the first hit on or after the
+/// current page then becomes the current one.
+fn start_search(app: &mut NoctuaApp, query: &str) -> UpdateResult {
+    let search = &mut app.model.search;
+    search.generation += 1;
+    search.results = None;
+    search.searching = false;
+
+    let Some(job) = app
+        .document_manager
+        .current_document()
+        .and_then(|doc| doc.search_job(query))
+    else {
+        return UpdateResult::None;
+    };
+    if query.is_empty() {
+        return UpdateResult::None;
+    }
+
+    search.searching = true;
+    let generation = search.generation;
+    UpdateResult::Task(cosmic::task::future(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            DocumentContent::run_search_job(&job).map(|hits| (job.query, hits))
+        })
+        .await;
+        match result {
+            Ok(Ok((query, hits))) => AppMessage::SearchFinished(generation, query, hits),
+            Ok(Err(e)) => AppMessage::ShowError(format!("Search failed: {e}")),
+            Err(e) => {
+                log::error!("Search task failed: {e}");
+                AppMessage::NoOp
+            }
+        }
+    }))
+}
+
+/// Go to the page of the current search hit and pan it into view.
+fn show_search_hit(app: &mut NoctuaApp) -> UpdateResult {
+    let Some(hit) = app
+        .model
+        .search
+        .results
+        .as_ref()
+        .and_then(SearchResults::current)
+        .cloned()
+    else {
+        return UpdateResult::None;
+    };
+
+    let mut result = UpdateResult::None;
+    if app
+        .document_manager
+        .current_document()
+        .is_some_and(|doc| !doc.visible_pages().contains(&hit.page))
+    {
+        result = go_to_page(app, hit.page);
+    }
+
+    // A fitted page is fully visible, otherwise center the hit.
+    let viewport = &mut app.model.viewport;
+    if viewport.fit_mode != ViewMode::Fit
+        && let Some(doc) = app.document_manager.current_document()
+        && let Some(bounds) = doc.search_hit_bounds(&hit)
+    {
+        let (width, height) = doc.dimensions();
+        let scale = viewport.scale;
+        let max_x = ((width as f32 * scale - viewport.canvas_size.width) / 2.0).max(0.0);
+        let max_y = ((height as f32 * scale - viewport.canvas_size.height) / 2.0).max(0.0);
+
+        let center_x = (bounds.x + bounds.width / 2.0) as f32;
+        let center_y = (bounds.y + bounds.height / 2.0) as f32;
+        viewport.pan_x = ((center_x - width as f32 / 2.0) * scale).clamp(-max_x, max_x);
+        viewport.pan_y = ((center_y - height as f32 / 2.0) * scale).clamp(-max_y, max_y);
+    }
+
+    result
+}
+
+/// Straighten the current document with the sampling settings from the model.
+///
+/// Raster images show a preview right away and are resampled at full
+/// resolution on a worker thread once the angle has settled.
+fn rotate_fine(app: &mut NoctuaApp, angle: f32) -> UpdateResult {
+    let operations = [
+        TransformOperation::SetInterpolation(app.model.interpolation_quality),
+        TransformOperation::SetAutoCrop(app.model.rotation_auto_crop),
+        TransformOperation::PreviewFineRotation(angle.clamp(-180.0, 180.0)),
+    ];
+
+    for operation in operations {
+        if let Err(e) = TransformDocumentCommand::new(operation).execute(&mut app.document_manager)
+        {
+            app.model.set_error(format!("Fine rotation failed: {e}"));
+            return UpdateResult::None;
+        }
+    }
+
+    cache_render(&mut app.model, &mut app.document_manager);
+    schedule_fine_rotation(app)
+}
+
+/// Schedule the full-resolution resampling of a previewed fine rotation
+/// once the angle has settled.
+///
+/// Every call starts a new generation, so only the last angle of a slider
+/// drag or a burst of nudges is resampled.
+fn schedule_fine_rotation(app: &mut NoctuaApp) -> UpdateResult {
+    app.model.fine_rotation_generation += 1;
+    let generation = app.model.fine_rotation_generation;
+    let pending = app
+        .document_manager
+        .current_document()
+        .is_some_and(DocumentContent::fine_rotation_pending);
+    if !pending {
+        return UpdateResult::None;
+    }
+
+    UpdateResult::Task(cosmic::task::future(async move {
+        tokio::time::sleep(RENDER_DEBOUNCE).await;
+        AppMessage::RenderFineRotation(generation)
+    }))
+}
+
+/// Resample a fine rotation at full resolution on a worker thread.
+fn fine_rotation_task(job: FineRotationJob, generation: u64) -> Task<Action<AppMessage>> {
+    cosmic::task::future(async move {
+        match tokio::task::spawn_blocking(move || job.run()).await {
+            Ok(rotated) => AppMessage::FineRotationRendered(generation, Box::new(rotated)),
+            Err(e) => {
+                log::error!("Fine rotation task failed: {e}");
+                AppMessage::NoOp
+            }
+        }
+    })
+}
+
+/// Whether the document at `path` can be written back in its own format.
+fn is_writable_format(path: &Path) -> bool {
+    matches!(
+        ExportFormat::from_path(path),
+        Some(ExportFormat::Png | ExportFormat::Jpeg | ExportFormat::WebP)
+    )
+}
+
+/// Suggest a file name for Save As based on the current document.
+///
+/// Documents in formats we cannot write (PDF, SVG) get a `.png` name.
+fn suggested_file_name(path: &Path) -> String {
+    let path = if is_writable_format(path) {
+        path.to_path_buf()
+    } else {
+        path.with_extension("png")
+    };
+
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Open the Save As file chooser for the current document.
+fn save_as(manager: &crate::application::DocumentManager) -> Option<Task<Action<AppMessage>>> {
+    let current = manager.current_path()?;
+    Some(save_file_dialog(suggested_file_name(current)))
+}
+
+/// Open the Save As file chooser for the current animation frame.
+fn export_frame(manager: &crate::application::DocumentManager) -> Option<Task<Action<AppMessage>>> {
+    let stem = manager.current_path()?.file_stem()?.to_string_lossy();
+    let frame = manager.current_document()?.current_page() + 1;
+    Some(save_file_dialog(format!("{stem}-frame-{frame}.png")))
+}
+
+/// Ask for a target file and save the current document to it.
+fn save_file_dialog(file_name: String) -> Task<Action<AppMessage>> {
+    let dialog = file_chooser::save::Dialog::new()
+        .title(fl!("dialog-save-as-title"))
+        .file_name(file_name);
+
+    cosmic::task::future(async move {
+        match dialog.save_file().await {
+            Ok(response) => response
+                .url()
+                .and_then(|url| url.to_file_path().ok())
+                .map_or(AppMessage::NoOp, AppMessage::SaveAsSelected),
+            Err(file_chooser::Error::Cancelled) => AppMessage::NoOp,
+            Err(e) => AppMessage::ShowError(format!("Save As failed: {e}")),
+        }
+    })
+}
+
+/// Save command with the export settings and pending metadata changes.
+fn save_command(app: &NoctuaApp) -> SaveDocumentCommand {
+    let options = ImageExportOptions {
+        quality: app.config.export_quality,
+        lossless: app.config.export_webp_lossless,
+        preserve_metadata: app.config.export_preserve_metadata,
+    };
+
+    let mut edit = app
+        .model
+        .metadata_editor
+        .as_ref()
+        .map(MetadataEditor::edit)
+        .unwrap_or_default();
+    edit.strip_gps = app.config.export_strip_gps;
+
+    SaveDocumentCommand::new()
+        .with_options(options)
+        .with_metadata_edit(edit)
+}
+
+/// Write the current document to `path`.
+fn save_document(app: &mut NoctuaApp, path: &Path) {
+    // Write the full-resolution pixels of a fine rotation still previewed,
+    // and of the animation frame on screen.
+    if let Some(doc) = app.document_manager.current_document_mut() {
+        doc.settle_frame();
+        if doc.fine_rotation_pending() {
+            doc.settle_fine_rotation();
+            cache_render(&mut app.model, &mut app.document_manager);
+        }
+    }
+
+    let cmd = save_command(app);
+    if let Err(e) = cmd.execute(&app.document_manager, path) {
+        app.model.set_error(format!("Save failed: {e}"));
+        return;
+    }
+
+    // Overwriting the open file changes its size and metadata.
+    if app.document_manager.current_path() == Some(path) {
+        app.model.metadata_editor = None;
+        app.document_manager.refresh_metadata();
+    }
 }
@@ -4,24 +4,56 @@
 // Render the center canvas area with the current document.
 
 use cosmic::iced::widget::image::FilterMethod;
-use cosmic::iced::{ContentFit, Length};
-use cosmic::iced_widget::stack;
-use cosmic::widget::{container, text};
+use cosmic::iced::widget::scrollable::{Direction, Scrollbar};
+use cosmic::iced::{Alignment, ContentFit, Length, Rectangle};
+use cosmic::iced_widget::{responsive, stack};
+use cosmic::widget::{column, container, horizontal_space, image, row, scrollable, text};
 use cosmic::Element;
 
+use super::{inspector, search_bar};
 use crate::ui::widgets::{crop_overlay, Viewer};
-use crate::ui::model::{AppMode, ViewMode};
+use crate::ui::model::{AppMode, PAGE_SPACING, ViewMode};
 use crate::ui::{AppMessage, AppModel};
 use crate::application::DocumentManager;
 use crate::config::AppConfig;
+use crate::domain::document::core::content::DocumentContent;
+use crate::domain::document::core::page::LinkTarget;
 use crate::fl;
 
 /// Render the center canvas area with the current document.
 pub fn view<'a>(
     model: &'a AppModel,
-    _manager: &'a DocumentManager,
+    manager: &'a DocumentManager,
     config: &'a AppConfig,
 ) -> Element<'a, AppMessage> {
+    let canvas = document_view(model, manager, config);
+    if !model.search.visible {
+        return canvas;
+    }
+
+    column()
+        .push(search_bar::view(model))
+        .push(
+            row()
+                .push(canvas)
+                .push_maybe(search_bar::hit_list(model))
+                .height(Length::Fill),
+        )
+        .into()
+}
+
+/// Render the current document, or a placeholder if none is loaded.
+fn document_view<'a>(
+    model: &'a AppModel,
+    manager: &'a DocumentManager,
+    config: &'a AppConfig,
+) -> Element<'a, AppMessage> {
+    if let Some(doc) = manager.current_document()
+        && doc.page_layout().is_some_and(|layout| layout.continuous)
+    {
+        return continuous_view(model, doc);
+    }
+
     // Use cached image handle from viewport
     if let Some(handle) = &model.viewport.cached_image_handle {
         // Determine content fit mode
@@ -33,8 +65,21 @@ pub fn view<'a>(
         // Check if we're in crop mode (to disable pan)
         let disable_pan = matches!(model.mode, AppMode::Crop { .. });
 
+        // Clicks pick pixels in inspect mode instead of following links
+        let inspecting = matches!(model.mode, AppMode::Inspect { .. });
+
+        let (highlights, active_highlight) = search_highlights(model, manager);
+        let (links, targets) = if inspecting {
+            (Vec::new(), Vec::new())
+        } else {
+            document_links(manager)
+        };
+
+        // Show the clipping overlay in place of the image when enabled
+        let handle = model.histogram.overlay_for(handle).unwrap_or(handle);
+
         // Create image viewer
-        let img_viewer = Viewer::new(handle.clone())
+        let mut img_viewer = Viewer::new(handle.clone())
             .with_state(
                 model.viewport.scale,
                 model.viewport.pan_x,
@@ -53,15 +98,27 @@ pub fn view<'a>(
             .height(Length::Fill)
             .content_fit(content_fit)
             .filter_method(FilterMethod::Nearest)
+            .pixel_density(model.viewport.pixel_density)
+            .highlights(highlights, active_highlight)
+            .links(links, move |index| {
+                AppMessage::FollowLink(targets[index].clone())
+            })
             .min_scale(config.min_scale)
             .max_scale(config.max_scale)
             .scale_step(config.scale_step - 1.0)
             .disable_pan(disable_pan);
+        if inspecting {
+            img_viewer = img_viewer
+                .on_pixel_hover(AppMessage::InspectPixel)
+                .on_pixel_click(AppMessage::PickPixel);
+        }
 
-        // Overlay crop UI when in crop mode
+        // Overlay crop UI when in crop mode, the pixel readout when inspecting
         if let AppMode::Crop { selection } = &model.mode {
             let overlay = crop_overlay(selection, config.crop_show_grid);
             stack![img_viewer, overlay].into()
+        } else if let AppMode::Inspect { pixel } = &model.mode {
+            stack![img_viewer, inspector::view(pixel.as_ref())].into()
         } else {
             container(img_viewer)
                 .width(Length::Fill)
@@ -77,3 +134,110 @@ pub fn view<'a>(
             .into()
     }
 }
+
+/// Render all pages below each other in a scrollable strip.
+///
+/// Pages show their thumbnail until they are rendered at the current zoom.
+fn continuous_view<'a>(model: &'a AppModel, doc: &'a DocumentContent) -> Element<'a, AppMessage> {
+    responsive(move |size| {
+        let view_width = (size.width - 2.0 * PAGE_SPACING).max(0.0);
+        let sizes = model
+            .viewport
+            .continuous_page_sizes(&doc.page_sizes(), view_width);
+        let strip_width = sizes
+            .iter()
+            .map(|size| size.width)
+            .fold(view_width, f32::max);
+
+        let mut pages = column::with_capacity(sizes.len())
+            .spacing(PAGE_SPACING)
+            .padding([0.0, PAGE_SPACING])
+            .align_x(Alignment::Center)
+            .width(Length::Fixed(strip_width + 2.0 * PAGE_SPACING));
+
+        for (page, size) in sizes.into_iter().enumerate() {
+            let content: Element<'a, AppMessage> = match doc
+                .page_raster(page)
+                .or_else(|| doc.get_thumbnail_handle(page))
+            {
+                Some(handle) => image(handle)
+                    .content_fit(ContentFit::Contain)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .into(),
+                None => horizontal_space().into(),
+            };
+            pages = pages.push(
+                container(content)
+                    .width(Length::Fixed(size.width))
+                    .height(Length::Fixed(size.height))
+                    .class(cosmic::theme::Container::Card),
+            );
+        }
+
+        scrollable(pages)
+            .id(model.viewport.scroll_id.clone())
+            .direction(Direction::Both {
+                vertical: Scrollbar::default(),
+                horizontal: Scrollbar::default(),
+            })
+            .on_scroll(AppMessage::PagesScrolled)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    })
+    .into()
+}
+
+/// Collect the search hits on the current page as viewer highlights.
+///
+/// Returns the rectangles and the index of the current hit among them.
+fn search_highlights(
+    model: &AppModel,
+    manager: &DocumentManager,
+) -> (Vec<Rectangle>, Option<usize>) {
+    let (Some(results), Some(doc)) = (&model.search.results, manager.current_document()) else {
+        return (Vec::new(), None);
+    };
+
+    let mut highlights = Vec::new();
+    let mut active = None;
+    for (index, hit) in results.hits().iter().enumerate() {
+        let Some(bounds) = doc.search_hit_bounds(hit) else {
+            continue;
+        };
+        if results.current_index() == Some(index) {
+            active = Some(highlights.len());
+        }
+        highlights.push(Rectangle {
+            x: bounds.x as f32,
+            y: bounds.y as f32,
+            width: bounds.width as f32,
+            height: bounds.height as f32,
+        });
+    }
+
+    (highlights, active)
+}
+
+/// Collect the links on the displayed pages as viewer link areas.
+///
+/// Returns the rectangles and their targets in the same order.
+fn document_links(manager: &DocumentManager) -> (Vec<Rectangle>, Vec<LinkTarget>) {
+    let Some(doc) = manager.current_document() else {
+        return (Vec::new(), Vec::new());
+    };
+
+    doc.links()
+        .into_iter()
+        .map(|(bounds, target)| {
+            let rect = Rectangle {
+                x: bounds.x as f32,
+                y: bounds.y as f32,
+                width: bounds.width as f32,
+                height: bounds.height as f32,
+            };
+            (rect, target)
+        })
+        .unzip()
+}
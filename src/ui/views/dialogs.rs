@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/ui/views/dialogs.rs
+//
+// Modal dialogs (overwrite confirmation, document password, external links).
+
+use cosmic::widget::{self, button, checkbox, column, text};
+use cosmic::Element;
+
+use crate::ui::model::{AppDialog, AppModel};
+use crate::ui::AppMessage;
+use crate::fl;
+
+/// Build the active modal dialog, if any.
+pub fn view(model: &AppModel) -> Option<Element<'_, AppMessage>> {
+    let dialog = model.dialog.as_ref()?;
+
+    let element = match dialog {
+        AppDialog::ConfirmOverwrite { path } => {
+            let filename = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            widget::dialog()
+                .title(fl!("dialog-overwrite-title"))
+                .body(fl!("dialog-overwrite-body", filename: filename))
+                .primary_action(
+                    button::destructive(fl!("dialog-replace"))
+                        .on_press(AppMessage::ConfirmOverwrite),
+                )
+                .secondary_action(
+                    button::standard(fl!("dialog-cancel")).on_press(AppMessage::CloseDialog),
+                )
+                .into()
+        }
+
+        AppDialog::Password {
+            path,
+            password,
+            remember,
+            incorrect,
+        } => {
+            let filename = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let placeholder = fl!("dialog-password-placeholder");
+            let input = widget::secure_input(placeholder, password, None, true)
+                .on_input(AppMessage::PasswordChanged)
+                .on_submit(|_| AppMessage::SubmitPassword);
+
+            let mut controls = column::with_capacity(3).spacing(12).push(input);
+            if *incorrect {
+                controls = controls.push(text::body(fl!("dialog-password-incorrect")));
+            }
+            controls = controls.push(
+                checkbox(fl!("dialog-password-remember"), *remember)
+                    .on_toggle(AppMessage::PasswordRememberToggled),
+            );
+
+            let submit = (!password.is_empty()).then_some(AppMessage::SubmitPassword);
+
+            widget::dialog()
+                .title(fl!("dialog-password-title"))
+                .body(fl!("dialog-password-body", filename: filename))
+                .control(controls)
+                .primary_action(button::suggested(fl!("dialog-unlock")).on_press_maybe(submit))
+                .secondary_action(
+                    button::standard(fl!("dialog-cancel")).on_press(AppMessage::CloseDialog),
+                )
+                .into()
+        }
+
+        AppDialog::OpenLink { uri } => widget::dialog()
+            .title(fl!("dialog-open-link-title"))
+            .body(fl!("dialog-open-link-body", uri: uri.as_str()))
+            .primary_action(button::suggested(fl!("dialog-open")).on_press(AppMessage::OpenLink))
+            .secondary_action(
+                button::standard(fl!("dialog-cancel")).on_press(AppMessage::CloseDialog),
+            )
+            .into(),
+    };
+
+    Some(element)
+}
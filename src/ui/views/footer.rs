@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // src/ui/views/footer.rs
 //
-// Footer bar with zoom controls and document info.
+// Footer bar with zoom controls, animation playback, page layout and document info.
 
 use cosmic::iced::Alignment;
 use cosmic::widget::{button, icon, row, text};
@@ -10,6 +10,7 @@ use cosmic::Element;
 use crate::ui::model::{AppModel, ViewMode};
 use crate::ui::AppMessage;
 use crate::application::DocumentManager;
+use crate::domain::document::core::content::DocumentContent;
 use crate::domain::document::core::document::Renderable;
 use crate::fl;
 
@@ -41,6 +42,20 @@ pub fn view<'a>(model: &'a AppModel, manager: &'a DocumentManager) -> Element<'a
         fl!("status-nav-position", current: current, total: total)
     };
 
+    // Playback controls for animated images
+    let animation = manager
+        .current_document()
+        .filter(|doc| doc.is_animated())
+        .map(|doc| {
+            animation_controls(model.animation_paused, doc.current_page(), doc.page_count())
+        });
+
+    // Page controls for documents with a page layout (PDF, comic books)
+    let pages = manager
+        .current_document()
+        .filter(|doc| doc.page_layout().is_some())
+        .map(page_controls);
+
     row()
         .spacing(8)
         .align_y(Alignment::Center)
@@ -71,6 +86,10 @@ pub fn view<'a>(model: &'a AppModel, manager: &'a DocumentManager) -> Element<'a
                 .on_press(AppMessage::ZoomFit)
                 .padding(4),
         )
+        // Animation controls
+        .push_maybe(animation)
+        // Page layout controls
+        .push_maybe(pages)
         // Document dimensions
         .push_maybe(if !doc_info.is_empty() {
             Some(text(doc_info))
@@ -85,3 +104,99 @@ pub fn view<'a>(model: &'a AppModel, manager: &'a DocumentManager) -> Element<'a
         })
         .into()
 }
+
+/// Build play/pause, frame stepping and export controls with the frame position.
+fn animation_controls<'a>(paused: bool, frame: usize, frames: usize) -> Element<'a, AppMessage> {
+    let position = fl!("status-frame-position", current: frame + 1, total: frames);
+    let play_icon = if paused {
+        "media-playback-start-symbolic"
+    } else {
+        "media-playback-pause-symbolic"
+    };
+
+    row()
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .push(
+            button::icon(icon::from_name("media-skip-backward-symbolic"))
+                .on_press(AppMessage::PrevFrame)
+                .padding(4),
+        )
+        .push(
+            button::icon(icon::from_name(play_icon))
+                .on_press(AppMessage::ToggleAnimation)
+                .padding(4),
+        )
+        .push(
+            button::icon(icon::from_name("media-skip-forward-symbolic"))
+                .on_press(AppMessage::NextFrame)
+                .padding(4),
+        )
+        .push(text(position))
+        .push(
+            button::icon(icon::from_name("camera-photo-symbolic"))
+                .on_press(AppMessage::ExportFrame)
+                .padding(4),
+        )
+        .into()
+}
+
+/// Build page stepping and layout toggles with the page position.
+fn page_controls<'a>(doc: &DocumentContent) -> Element<'a, AppMessage> {
+    let layout = doc.page_layout().unwrap_or_default();
+    let visible = doc.visible_pages();
+    let pages = if visible.len() > 1 {
+        format!("{}–{}", visible.start + 1, visible.end)
+    } else {
+        (visible.start + 1).to_string()
+    };
+    let position = fl!("status-page-position", pages: pages, total: doc.page_count());
+
+    // In right-to-left spreads the left button advances.
+    let spread = layout.two_page_spread && !layout.continuous;
+    let (left, right) = if spread && layout.right_to_left {
+        (AppMessage::NextPage, AppMessage::PrevPage)
+    } else {
+        (AppMessage::PrevPage, AppMessage::NextPage)
+    };
+
+    row()
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .push(
+            button::icon(icon::from_name("go-previous-symbolic"))
+                .on_press(left)
+                .padding(4),
+        )
+        .push(text(position))
+        .push(
+            button::icon(icon::from_name("go-next-symbolic"))
+                .on_press(right)
+                .padding(4),
+        )
+        .push_maybe(doc.supports_continuous_view().then(|| {
+            button::icon(icon::from_name("view-continuous-symbolic"))
+                .selected(layout.continuous)
+                .on_press(AppMessage::ToggleContinuousScroll)
+                .padding(4)
+        }))
+        .push(
+            button::icon(icon::from_name("view-dual-symbolic"))
+                .selected(layout.two_page_spread)
+                .on_press_maybe((!layout.continuous).then_some(AppMessage::ToggleTwoPageSpread))
+                .padding(4),
+        )
+        .push(
+            button::icon(icon::from_name("x-office-document-symbolic"))
+                .selected(layout.cover_alone)
+                .on_press_maybe(spread.then_some(AppMessage::ToggleCoverAlone))
+                .padding(4),
+        )
+        .push(
+            button::icon(icon::from_name("format-text-direction-rtl-symbolic"))
+                .selected(layout.right_to_left)
+                .on_press_maybe(spread.then_some(AppMessage::ToggleRightToLeft))
+                .padding(4),
+        )
+        .into()
+}
@@ -1,17 +1,20 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // src/app/view/format_panel.rs
 //
-// Format panel for paper format and orientation selection.
+// Format panel for fine rotation, paper format and orientation selection.
 
-use cosmic::widget::{column, radio, text};
+use cosmic::iced::Alignment;
+use cosmic::widget::{button, column, radio, row, slider, text, toggler};
 use cosmic::Element;
 
+use crate::application::DocumentManager;
+use crate::domain::document::core::document::{InterpolationQuality, Transformable};
 use crate::ui::model::{AppMode, AppModel, Orientation, PaperFormat};
 use crate::ui::AppMessage;
 use crate::fl;
 
 /// Build the format panel view for the navigation bar.
-pub fn view(model: &AppModel) -> Element<'static, AppMessage> {
+pub fn view(model: &AppModel, manager: &DocumentManager) -> Element<'static, AppMessage> {
     // Extract values from Transform mode
     let (paper_format, orientation) = match &model.mode {
         AppMode::Transform {
@@ -21,7 +24,58 @@ pub fn view(model: &AppModel) -> Element<'static, AppMessage> {
         _ => (None, Orientation::default()),
     };
 
-    let mut content = column::with_capacity(20).spacing(12).padding(16);
+    let mut content = column::with_capacity(30).spacing(12).padding(16);
+
+    // --- Straighten Section ---
+    let angle = manager
+        .current_document()
+        .map_or(0.0, |doc| doc.fine_rotation());
+
+    content = content
+        .push(text::heading(fl!("straighten-section-title")))
+        .push(text::caption(fl!("straighten-section-subtitle")))
+        .push(
+            row()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(slider(-45.0..=45.0, angle, AppMessage::SetFineRotation).step(0.1))
+                .push(text::body(fl!(
+                    "straighten-angle",
+                    angle: format!("{angle:.1}")
+                ))),
+        )
+        .push(
+            toggler(model.rotation_auto_crop)
+                .label(fl!("straighten-auto-crop"))
+                .on_toggle(AppMessage::SetRotationAutoCrop),
+        )
+        .push(text::body(fl!("straighten-quality")));
+
+    for (label, quality) in [
+        (fl!("straighten-quality-fast"), InterpolationQuality::Fast),
+        (
+            fl!("straighten-quality-balanced"),
+            InterpolationQuality::Balanced,
+        ),
+        (fl!("straighten-quality-best"), InterpolationQuality::Best),
+    ] {
+        content = content.push(
+            radio(
+                label,
+                quality,
+                Some(model.interpolation_quality),
+                AppMessage::SetInterpolationQuality,
+            )
+            .size(16),
+        );
+    }
+
+    content = content
+        .push(
+            button::standard(fl!("straighten-reset"))
+                .on_press_maybe((angle != 0.0).then_some(AppMessage::ResetFineRotation)),
+        )
+        .push(cosmic::widget::vertical_space().height(16));
 
     // --- Format Section ---
     content = content
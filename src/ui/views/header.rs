@@ -77,11 +77,20 @@ pub fn start<'a>(
 }
 
 /// Build the end (right) side of the header bar.
-pub fn end<'a>(
-    _model: &'a AppModel,
-    _manager: &'a DocumentManager,
-) -> Vec<Element<'a, AppMessage>> {
+pub fn end<'a>(_model: &'a AppModel, manager: &'a DocumentManager) -> Vec<Element<'a, AppMessage>> {
+    let has_doc = manager.current_document().is_some();
+
     vec![
+        // Save As
+        button::icon(icon::from_name("document-save-as-symbolic"))
+            .on_press_maybe(has_doc.then_some(AppMessage::SaveAs))
+            //.tooltip(fl!("tooltip-save-as"))
+            .into(),
+        // Histogram panel toggle
+        button::icon(icon::from_name("view-statistics-symbolic"))
+            .on_press(AppMessage::ToggleContextPage(ContextPage::Histogram))
+            //.tooltip(fl!("tooltip-histogram-panel"))
+            .into(),
         // Info panel toggle
         button::icon(icon::from_name("dialog-information-symbolic"))
             .on_press(AppMessage::ToggleContextPage(ContextPage::Properties))
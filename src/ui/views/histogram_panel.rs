@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/ui/views/histogram_panel.rs
+//
+// Histogram panel: luminance and RGB histograms with per-channel statistics.
+
+use cosmic::iced::{Alignment, ContentFit, Length};
+use cosmic::widget::{column, divider, horizontal_space, image, row, text, toggler};
+use cosmic::Element;
+
+use crate::domain::document::operations::histogram::Channel;
+use crate::ui::{AppMessage, AppModel};
+use crate::fl;
+
+/// Size of the drawn histogram in pixels (one column per value).
+pub const PLOT_WIDTH: u32 = 256;
+pub const PLOT_HEIGHT: u32 = 120;
+
+/// Build the histogram panel view.
+pub fn view(model: &AppModel) -> Element<'static, AppMessage> {
+    let state = &model.histogram;
+    let mut content = column::with_capacity(12).spacing(8).padding(12).push(
+        row::with_capacity(3)
+            .spacing(4)
+            .align_y(Alignment::Center)
+            .padding([0, 0, 8, 0])
+            .push(text::title4(fl!("panel-histogram")))
+            .push(horizontal_space().width(Length::Fill))
+            .push_maybe(
+                state
+                    .computing
+                    .then(|| text::caption(fl!("histogram-computing"))),
+            ),
+    );
+
+    let Some(ref result) = state.result else {
+        let placeholder = if state.computing {
+            String::new()
+        } else {
+            fl!("no-document")
+        };
+        return content.push(text::body(placeholder)).into();
+    };
+
+    content = content.push(
+        image(result.plot.clone())
+            .width(Length::Fill)
+            .height(Length::Fixed(PLOT_HEIGHT as f32))
+            .content_fit(ContentFit::Fill),
+    );
+
+    let histogram = &result.histogram;
+    for channel in Channel::ALL {
+        let stats = histogram.stats(channel);
+        let name = match channel {
+            Channel::Luma => fl!("histogram-luma"),
+            Channel::Red => fl!("histogram-red"),
+            Channel::Green => fl!("histogram-green"),
+            Channel::Blue => fl!("histogram-blue"),
+        };
+        content = content.push(
+            column::with_capacity(3)
+                .spacing(2)
+                .push(text::heading(name))
+                .push(text::body(fl!(
+                    "histogram-mean-median",
+                    mean: format!("{:.1}", stats.mean),
+                    median: stats.median
+                )))
+                .push(text::caption(fl!(
+                    "histogram-clipped",
+                    shadows: percent(stats.shadows_clipped),
+                    highlights: percent(stats.highlights_clipped)
+                ))),
+        );
+    }
+
+    content
+        .push(text::body(fl!(
+            "histogram-unique-colors",
+            count: histogram.unique_colors()
+        )))
+        .push(divider::horizontal::light())
+        .push(
+            toggler(state.clipping_overlay)
+                .label(fl!("histogram-clipping-overlay"))
+                .on_toggle(AppMessage::SetClippingOverlay),
+        )
+        .into()
+}
+
+/// Format a share (0.0-1.0) as a percentage.
+fn percent(share: f64) -> String {
+    format!("{:.2}", share * 100.0)
+}
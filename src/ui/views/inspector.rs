@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/ui/views/inspector.rs
+//
+// Pixel inspector readout shown over the canvas in inspect mode.
+
+use cosmic::iced::widget::image::FilterMethod;
+use cosmic::iced::{Alignment, Length};
+use cosmic::widget::{column, container, image, row, text};
+use cosmic::Element;
+
+use crate::ui::model::InspectedPixel;
+use crate::ui::AppMessage;
+use crate::fl;
+
+/// Pixels shown around the inspected one in each direction.
+pub const LOUPE_RADIUS: u32 = 5;
+
+/// Size of each pixel in the loupe.
+pub const LOUPE_ZOOM: u32 = 12;
+
+/// Build the readout card, aligned to the bottom-right of the canvas.
+pub fn view(pixel: Option<&InspectedPixel>) -> Element<'static, AppMessage> {
+    let content: Element<'static, AppMessage> = match pixel {
+        Some(pixel) => {
+            let [r, g, b, a] = pixel.color.0;
+            let [hue, saturation, lightness] = pixel.color.hsl();
+            let [l, lab_a, lab_b] = pixel.color.lab();
+
+            row::with_capacity(2)
+                .spacing(12)
+                .align_y(Alignment::Center)
+                .push(image(pixel.loupe.clone()).filter_method(FilterMethod::Nearest))
+                .push(
+                    column::with_capacity(6)
+                        .spacing(2)
+                        .push(text::heading(fl!(
+                            "inspector-position",
+                            x: pixel.x,
+                            y: pixel.y
+                        )))
+                        .push(text::body(format!("RGBA  {r}, {g}, {b}, {a}")))
+                        .push(text::body(format!("Hex  {}", pixel.color.hex())))
+                        .push(text::body(format!(
+                            "HSL  {hue:.0}°, {saturation:.0} %, {lightness:.0} %"
+                        )))
+                        .push(text::body(format!("Lab  {l:.1}, {lab_a:.1}, {lab_b:.1}")))
+                        .push(text::caption(fl!("inspector-click-to-copy"))),
+                )
+                .into()
+        }
+        None => text::body(fl!("inspector-hint")).into(),
+    };
+
+    container(
+        container(content)
+            .padding(12)
+            .class(cosmic::theme::Container::Card),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .padding(16)
+    .align_x(Alignment::End)
+    .align_y(Alignment::End)
+    .into()
+}
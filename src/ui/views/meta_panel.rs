@@ -4,20 +4,25 @@
 // Metadata and properties panel for document information.
 
 use cosmic::iced::{Alignment, Length};
-use cosmic::widget::{button, column, divider, horizontal_space, icon, row, text};
+use cosmic::widget::{button, checkbox, column, divider, horizontal_space, icon, row, text, text_input};
 use cosmic::Element;
 
 use crate::application::DocumentManager;
+use crate::domain::document::core::content::DocumentContent;
 use crate::domain::document::core::document::Renderable;
+use crate::domain::document::core::metadata::PdfPermissions;
+use crate::domain::document::core::tags::MetaTag;
+use crate::domain::document::operations::metadata_edit::MetadataField;
+use crate::ui::model::MetadataEditor;
 use crate::ui::{AppMessage, AppModel};
 use crate::fl;
 
 /// Build the metadata/properties panel view.
-pub fn view(_model: &AppModel, manager: &DocumentManager) -> Element<'static, AppMessage> {
+pub fn view(model: &AppModel, manager: &DocumentManager) -> Element<'static, AppMessage> {
     let mut content = column::with_capacity(16).spacing(8).padding(12);
 
     // Header with action icons
-    content = content.push(panel_header(manager));
+    content = content.push(panel_header(model, manager));
 
     // Display document metadata if available
     if let Some(meta) = manager.current_metadata() {
@@ -27,6 +32,14 @@ pub fn view(_model: &AppModel, manager: &DocumentManager) -> Element<'static, Ap
             .push(meta_row(fl!("meta-filename"), meta.basic.file_name.clone()))
             .push(meta_row(fl!("meta-format"), meta.basic.format.clone()));
 
+        // The format above is read from the content; flag misleading names.
+        if let Some(ref mismatch) = meta.basic.format_mismatch {
+            content = content.push(meta_row(
+                fl!("meta-extension"),
+                fl!("meta-extension-mismatch", extension: mismatch.extension.clone()),
+            ));
+        }
+
         // Show dimensions - original from metadata, current if transformed
         let original_dims = (meta.basic.width, meta.basic.height);
         let current_dims = if let Some(doc) = manager.current_document() {
@@ -108,6 +121,74 @@ pub fn view(_model: &AppModel, manager: &DocumentManager) -> Element<'static, Ap
             }
         }
 
+        // --- Metadata Editor (while editing) ---
+        if let Some(ref editor) = model.metadata_editor {
+            content = content
+                .push(divider::horizontal::light())
+                .push(metadata_editor(model, editor));
+        }
+
+        // --- PDF Section (if available) ---
+        if let Some(ref pdf) = meta.pdf {
+            content = content
+                .push(divider::horizontal::light())
+                .push(section_header(fl!("meta-section-pdf")));
+
+            let fields = [
+                (fl!("meta-title"), &pdf.title),
+                (fl!("meta-author"), &pdf.author),
+                (fl!("meta-subject"), &pdf.subject),
+                (fl!("meta-keywords"), &pdf.keywords),
+                (fl!("meta-creator"), &pdf.creator),
+                (fl!("meta-producer"), &pdf.producer),
+                (fl!("meta-created"), &pdf.creation_date),
+                (fl!("meta-modified"), &pdf.modification_date),
+                (fl!("meta-pdf-version"), &pdf.version),
+            ];
+            for (label, value) in fields {
+                if let Some(value) = value {
+                    content = content.push(meta_row(label, value.clone()));
+                }
+            }
+
+            let security = if pdf.encrypted {
+                fl!("meta-encrypted")
+            } else {
+                fl!("meta-not-encrypted")
+            };
+            content = content
+                .push(meta_row(fl!("meta-security"), security))
+                .push(meta_row(
+                    fl!("meta-permissions"),
+                    permissions_display(pdf.permissions),
+                ));
+
+            // One row per run of equally sized pages
+            let runs = pdf.page_size_runs();
+            if let [(_, size)] = runs.as_slice() {
+                content = content.push(meta_row(fl!("meta-page-size"), size.display()));
+            } else {
+                for (pages, size) in runs {
+                    let pages = if pages.len() == 1 {
+                        format!("{}", pages.start + 1)
+                    } else {
+                        format!("{}–{}", pages.start + 1, pages.end)
+                    };
+                    content = content.push(meta_row(
+                        fl!("meta-page-size-pages", pages: pages),
+                        size.display(),
+                    ));
+                }
+            }
+        }
+
+        // --- All Metadata (expandable, if available) ---
+        if !meta.tags.is_empty() {
+            content = content
+                .push(divider::horizontal::light())
+                .push(all_metadata(model, &meta.tags));
+        }
+
         // --- File Path (at the bottom, less prominent) ---
         content = content
             .push(divider::horizontal::light())
@@ -131,8 +212,12 @@ pub fn view(_model: &AppModel, manager: &DocumentManager) -> Element<'static, Ap
 // =============================================================================
 
 /// Panel header with title and action buttons.
-fn panel_header(manager: &DocumentManager) -> Element<'static, AppMessage> {
+fn panel_header(model: &AppModel, manager: &DocumentManager) -> Element<'static, AppMessage> {
     let has_doc = manager.current_document().is_some();
+    // Metadata is written back to image files only.
+    let can_edit = model.metadata_editor.is_none()
+        && manager.current_path().is_some()
+        && matches!(manager.current_document(), Some(DocumentContent::Raster(_)));
 
     row::with_capacity(5)
         .spacing(4)
@@ -140,6 +225,12 @@ fn panel_header(manager: &DocumentManager) -> Element<'static, AppMessage> {
         .padding([0, 0, 8, 0])
         .push(text::title4(fl!("panel-properties")))
         .push(horizontal_space().width(Length::Fill))
+        .push(
+            button::icon(icon::from_name("document-edit-symbolic"))
+                .tooltip(fl!("action-edit-metadata"))
+                .padding(4)
+                .on_press_maybe(can_edit.then_some(AppMessage::EditMetadata)),
+        )
         .push(
             button::icon(icon::from_name("image-x-generic-symbolic"))
                 .tooltip(fl!("action-set-wallpaper"))
@@ -172,6 +263,177 @@ fn meta_row_small(label: String, value: String) -> Element<'static, AppMessage>
         .into()
 }
 
+/// Expandable list of every EXIF, XMP and IPTC tag with a filter.
+///
+/// Clicking a tag copies it to the clipboard; the copy button copies all
+/// tags matching the filter.
+fn all_metadata(model: &AppModel, tags: &[MetaTag]) -> Element<'static, AppMessage> {
+    let expanded = model.panels.all_metadata_expanded;
+    let query = &model.panels.metadata_query;
+    let matching: Vec<&MetaTag> = tags.iter().filter(|tag| tag.matches(query)).collect();
+
+    let expander = if expanded {
+        "go-down-symbolic"
+    } else {
+        "go-next-symbolic"
+    };
+    let copy_all = matching
+        .iter()
+        .map(|tag| format!("{} / {}: {}", tag.group.name(), tag.name, tag.value))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut header = row::with_capacity(3)
+        .spacing(4)
+        .align_y(Alignment::Center)
+        .push(
+            button::custom(
+                row::with_capacity(2)
+                    .spacing(4)
+                    .align_y(Alignment::Center)
+                    .push(icon::from_name(expander).size(16))
+                    .push(section_header(fl!("meta-section-all", count: tags.len()))),
+            )
+            .class(cosmic::theme::Button::Text)
+            .padding(0)
+            .on_press(AppMessage::ToggleAllMetadata),
+        )
+        .push(horizontal_space().width(Length::Fill));
+    if expanded {
+        header = header.push(
+            button::icon(icon::from_name("edit-copy-symbolic"))
+                .tooltip(fl!("meta-copy-all"))
+                .padding(4)
+                .on_press_maybe(
+                    (!matching.is_empty()).then_some(AppMessage::CopyToClipboard(copy_all)),
+                ),
+        );
+    }
+
+    let mut section = column::with_capacity(2 + matching.len())
+        .spacing(8)
+        .push(header);
+    if !expanded {
+        return section.into();
+    }
+
+    section = section.push(
+        text_input::search_input(fl!("meta-search-placeholder"), query.clone())
+            .on_input(AppMessage::MetadataQueryChanged),
+    );
+
+    let mut group = None;
+    for tag in matching {
+        if group != Some(tag.group) {
+            group = Some(tag.group);
+            section = section.push(text::heading(tag.group.name()));
+        }
+        section = section.push(
+            button::custom(meta_row(tag.name.clone(), tag.value.clone()))
+                .class(cosmic::theme::Button::Text)
+                .padding(0)
+                .width(Length::Fill)
+                .on_press(AppMessage::CopyToClipboard(format!(
+                    "{}: {}",
+                    tag.name, tag.value
+                ))),
+        );
+    }
+
+    section.into()
+}
+
+/// Input fields for the editable metadata, privacy options and the dry-run
+/// list of changes the next save will write.
+fn metadata_editor(model: &AppModel, editor: &MetadataEditor) -> Element<'static, AppMessage> {
+    let mut section = column::with_capacity(16)
+        .spacing(8)
+        .push(section_header(fl!("meta-section-edit")));
+
+    for field in MetadataField::ALL {
+        let (label, placeholder) = match field {
+            MetadataField::DateTime => (fl!("meta-datetime"), fl!("meta-datetime-placeholder")),
+            MetadataField::Description => (fl!("meta-description"), String::new()),
+            MetadataField::Artist => (fl!("meta-artist"), String::new()),
+            MetadataField::Copyright => (fl!("meta-copyright"), String::new()),
+            MetadataField::Keywords => (fl!("meta-keywords"), fl!("meta-keywords-placeholder")),
+            MetadataField::Gps => (fl!("meta-gps"), fl!("meta-gps-placeholder")),
+        };
+        section = section.push(
+            column::with_capacity(2)
+                .spacing(2)
+                .push(text::caption(format!("{}:", label)))
+                .push(
+                    text_input(placeholder, editor.value(field).to_string())
+                        .on_input(move |value| AppMessage::MetadataFieldChanged(field, value)),
+                ),
+        );
+    }
+
+    section = section
+        .push(text::caption(fl!("meta-privacy")))
+        .push(
+            checkbox(fl!("meta-strip-all"), model.strip_metadata)
+                .on_toggle(AppMessage::SetStripMetadata),
+        )
+        .push(checkbox(fl!("meta-strip-gps"), model.strip_gps).on_toggle(AppMessage::SetStripGps));
+
+    // Dry run: what the next save changes, tag by tag.
+    if let Some(ref changes) = editor.preview {
+        if changes.is_empty() {
+            section = section.push(text::body(fl!("meta-no-changes")));
+        }
+        for change in changes {
+            let old = change.old.clone().unwrap_or_else(|| fl!("meta-value-none"));
+            let new = change.new.clone().unwrap_or_else(|| fl!("meta-value-none"));
+            section = section.push(meta_row(
+                format!("{} / {}", change.group.name(), change.name),
+                format!("{old} → {new}"),
+            ));
+        }
+    }
+
+    section
+        .push(
+            row::with_capacity(3)
+                .spacing(8)
+                .push(
+                    button::standard(fl!("meta-preview"))
+                        .on_press(AppMessage::PreviewMetadataChanges),
+                )
+                .push(horizontal_space().width(Length::Fill))
+                .push(
+                    button::standard(fl!("dialog-cancel")).on_press(AppMessage::CancelMetadataEdit),
+                )
+                .push(button::suggested(fl!("meta-save")).on_press(AppMessage::Save)),
+        )
+        .into()
+}
+
+/// List the operations a PDF allows.
+fn permissions_display(permissions: PdfPermissions) -> String {
+    if permissions.all() {
+        return fl!("meta-permissions-all");
+    }
+
+    let allowed: Vec<String> = [
+        (permissions.print, fl!("meta-permission-print")),
+        (permissions.modify, fl!("meta-permission-modify")),
+        (permissions.copy, fl!("meta-permission-copy")),
+        (permissions.annotate, fl!("meta-permission-annotate")),
+        (permissions.fill_forms, fl!("meta-permission-fill-forms")),
+    ]
+    .into_iter()
+    .filter_map(|(allowed, label)| allowed.then_some(label))
+    .collect();
+
+    if allowed.is_empty() {
+        fl!("meta-permissions-none")
+    } else {
+        allowed.join(", ")
+    }
+}
+
 /// Vertical spacer helper.
 fn vertical_space() -> Element<'static, AppMessage> {
     cosmic::widget::vertical_space()
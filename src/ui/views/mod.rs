@@ -4,21 +4,27 @@
 // View module exports.
 
 pub mod canvas;
+pub mod dialogs;
 pub mod footer;
 pub mod format_panel;
 pub mod header;
+pub mod histogram_panel;
+pub mod inspector;
 pub mod meta_panel;
+pub mod outline_panel;
 pub mod pages_panel;
 pub mod panels;
+pub mod search_bar;
 
 use cosmic::iced::Length;
-use cosmic::widget::container;
+use cosmic::widget::{button, column, container, row};
 use cosmic::{Action, Element};
 
 use crate::ui::model::LeftPanel;
 use crate::ui::{AppMessage, AppModel};
 use crate::application::DocumentManager;
 use crate::config::AppConfig;
+use crate::fl;
 
 /// Main application view (canvas area).
 pub fn view<'a>(
@@ -33,19 +39,60 @@ pub fn view<'a>(
 ///
 /// Shows different panels based on panel state:
 /// - `LeftPanel::Thumbnails`: Page thumbnails (multi-page documents)
+/// - `LeftPanel::Outline`: Table of contents (documents with an outline)
 /// - `None`: Hidden
+///
+/// Documents with an outline get a switcher between both panels on top.
 pub fn nav_bar<'a>(
     model: &'a AppModel,
     manager: &'a DocumentManager,
 ) -> Option<Element<'a, Action<AppMessage>>> {
-    match model.panels.left {
-        None => None,
-        Some(LeftPanel::Thumbnails) => pages_panel::view(model, manager).map(|panel| {
-            container(panel.map(Action::App))
-                .width(Length::Shrink)
-                .height(Length::Fill)
-                .max_width(250)
-                .into()
-        }),
-    }
+    let left = model.panels.left?;
+    let panel = match left {
+        LeftPanel::Thumbnails => pages_panel::view(model, manager)?,
+        LeftPanel::Outline => outline_panel::view(model, manager)?,
+    };
+
+    let has_outline = manager
+        .current_document()
+        .is_some_and(|doc| !doc.outline().is_empty());
+
+    let content: Element<'a, AppMessage> = if has_outline {
+        column::with_capacity(2)
+            .push(panel_switcher(left))
+            .push(panel)
+            .into()
+    } else {
+        panel
+    };
+
+    Some(
+        container(content.map(Action::App))
+            .width(Length::Shrink)
+            .height(Length::Fill)
+            .max_width(250)
+            .into(),
+    )
+}
+
+/// Buttons to switch between page thumbnails and the outline.
+fn panel_switcher<'a>(current: LeftPanel) -> Element<'a, AppMessage> {
+    let tab = |label: String, panel: LeftPanel| {
+        let class = if panel == current {
+            cosmic::theme::Button::Suggested
+        } else {
+            cosmic::theme::Button::Standard
+        };
+        button::text(label)
+            .class(class)
+            .width(Length::Fill)
+            .on_press(AppMessage::SetLeftPanel(panel))
+    };
+
+    row::with_capacity(2)
+        .spacing(4)
+        .padding([8, 8, 0, 8])
+        .push(tab(fl!("nav-panel-pages"), LeftPanel::Thumbnails))
+        .push(tab(fl!("nav-panel-outline"), LeftPanel::Outline))
+        .into()
 }
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/ui/views/outline_panel.rs
+//
+// Outline (table of contents) panel for documents with bookmarks.
+
+/// Indentation per outline level in pixels.
+const INDENT_STEP: f32 = 16.0;
+
+/// Width reserved for the expander button.
+const EXPANDER_WIDTH: f32 = 24.0;
+
+use cosmic::iced::{Alignment, Length};
+use cosmic::widget::{button, column, horizontal_space, icon, row, scrollable, text};
+use cosmic::Element;
+
+use crate::application::DocumentManager;
+use crate::domain::document::core::page::OutlineEntry;
+use crate::ui::{AppMessage, AppModel};
+use crate::fl;
+
+/// Build the outline panel view.
+/// Returns None if the current document has no outline.
+pub fn view<'a>(
+    model: &'a AppModel,
+    manager: &'a DocumentManager,
+) -> Option<Element<'a, AppMessage>> {
+    let doc = manager.current_document()?;
+    let outline = doc.outline();
+
+    if outline.is_empty() {
+        return None;
+    }
+
+    let mut rows = Vec::new();
+    push_entries(&mut rows, model, outline, &mut Vec::new());
+
+    let content = column::with_children(rows)
+        .spacing(2)
+        .padding([12, 8])
+        .width(Length::Fill);
+
+    Some(
+        scrollable(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into(),
+    )
+}
+
+/// Build one row per visible entry, depth-first.
+///
+/// `path` holds the child indices leading to the current level.
+fn push_entries<'a>(
+    rows: &mut Vec<Element<'a, AppMessage>>,
+    model: &AppModel,
+    entries: &'a [OutlineEntry],
+    path: &mut Vec<usize>,
+) {
+    for (index, entry) in entries.iter().enumerate() {
+        path.push(index);
+        let expanded = model.panels.is_outline_expanded(path, entry.open);
+
+        // Expander for entries with children, blank space otherwise.
+        let expander: Element<'a, AppMessage> = if entry.children.is_empty() {
+            horizontal_space()
+                .width(Length::Fixed(EXPANDER_WIDTH))
+                .into()
+        } else {
+            let icon_name = if expanded {
+                "pan-down-symbolic"
+            } else {
+                "pan-end-symbolic"
+            };
+            button::icon(icon::from_name(icon_name))
+                .on_press(AppMessage::ToggleOutlineEntry(path.clone()))
+                .padding(4)
+                .into()
+        };
+
+        let title = if entry.title.trim().is_empty() {
+            fl!("outline-untitled")
+        } else {
+            entry.title.clone()
+        };
+
+        // Entries without a target page are only headings.
+        let title_button = button::custom(text::body(title))
+            .class(cosmic::theme::Button::MenuItem)
+            .padding([4, 8])
+            .width(Length::Fill)
+            .on_press_maybe(entry.page.map(AppMessage::GotoPage));
+
+        #[allow(clippy::cast_precision_loss)]
+        let indent = (path.len() - 1) as f32 * INDENT_STEP;
+
+        let entry_row = row::with_capacity(3)
+            .align_y(Alignment::Center)
+            .push(horizontal_space().width(Length::Fixed(indent)))
+            .push(expander)
+            .push(title_button);
+
+        rows.push(entry_row.into());
+
+        if expanded {
+            push_entries(rows, model, &entry.children, path);
+        }
+        path.pop();
+    }
+}
@@ -6,6 +6,20 @@
 /// Maximum width in pixels for page navigation thumbnails.
 const THUMBNAIL_MAX_WIDTH: f32 = 100.0;
 
+/// Estimated height of a page entry: a portrait thumbnail with its label,
+/// padding and spacing. Used to tell which pages are in view.
+const ESTIMATED_ENTRY_HEIGHT: f32 = THUMBNAIL_MAX_WIDTH * 1.4 + 40.0;
+
+/// Pages counted as in view before the panel reports its size.
+const INITIAL_PAGES: usize = 8;
+
+/// Pages above and below the view whose thumbnails are made ahead of
+/// scrolling.
+const PAGES_AHEAD: usize = 4;
+
+use std::ops::Range;
+
+use cosmic::iced::widget::scrollable::Viewport;
 use cosmic::iced::{Alignment, Length};
 use cosmic::widget::{button, column, container, scrollable, text};
 use cosmic::widget::image as cosmic_image;
@@ -19,7 +33,7 @@ use crate::fl;
 /// Build the page navigation panel view.
 /// Returns None if the current document doesn't support multiple pages.
 pub fn view<'a>(
-    _model: &'a AppModel,
+    model: &'a AppModel,
     manager: &'a DocumentManager,
 ) -> Option<Element<'a, AppMessage>> {
     // Get document and check if it's multi-page
@@ -39,14 +53,19 @@ pub fn view<'a>(
         .align_x(Alignment::Center)
         .width(Length::Fill);
 
-    // Show loading progress if not all thumbnails are ready.
-    if !doc.thumbnails_ready() {
+    // Show loading progress while thumbnails are made.
+    let generating = if doc.thumbnails_on_demand() {
+        model.panels.thumbnails_pending
+    } else {
+        !doc.thumbnails_ready()
+    };
+    if generating {
         let loading_msg = fl!("loading-thumbnails", current: loaded, total: page_count);
         content = content.push(text::caption(loading_msg));
     }
 
-    // Build thumbnail list for pages that are already loaded.
-    for page_index in 0..loaded {
+    // Build the page list; pages without a thumbnail yet show a placeholder.
+    for page_index in 0..page_count {
         let is_current = page_index == current_page;
 
         // Get cached thumbnail handle (read-only access).
@@ -98,6 +117,21 @@ pub fn view<'a>(
         scrollable(content)
             .width(Length::Shrink)
             .height(Length::Fill)
+            .on_scroll(AppMessage::ThumbnailsScrolled)
             .into(),
     )
 }
+
+/// Pages in or near the view of the panel, estimated from its scroll
+/// position.
+#[must_use]
+pub fn pages_in_view(view: Option<&Viewport>, page_count: usize) -> Range<usize> {
+    let Some(view) = view else {
+        return 0..INITIAL_PAGES.min(page_count);
+    };
+    let shown = (view.bounds().height / ESTIMATED_ENTRY_HEIGHT).ceil() as usize;
+    // Keep the end in view when a shorter document keeps the scroll position.
+    let first = ((view.absolute_offset().y / ESTIMATED_ENTRY_HEIGHT) as usize)
+        .min(page_count.saturating_sub(shown));
+    first.saturating_sub(PAGES_AHEAD)..(first + shown + PAGES_AHEAD).min(page_count)
+}
@@ -23,7 +23,7 @@ pub fn view(model: &AppModel, manager: &DocumentManager) -> Element<'static, App
     match model.panels.right.as_ref() {
         Some(RightPanel::Properties) | None => meta_panel::view(model, manager),
         Some(RightPanel::CropTools) => crop_tools_panel(model, manager),
-        Some(RightPanel::TransformTools) => format_panel::view(model),
+        Some(RightPanel::TransformTools) => format_panel::view(model, manager),
     }
 }
 
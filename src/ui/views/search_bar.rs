@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// src/ui/views/search_bar.rs
+//
+// Text search bar and hit list for documents with a text layer.
+
+/// Width of the hit list next to the canvas.
+const HIT_LIST_WIDTH: f32 = 240.0;
+
+use cosmic::iced::{Alignment, Length};
+use cosmic::widget::{button, column, horizontal_space, icon, row, scrollable, text, text_input};
+use cosmic::Element;
+
+use crate::ui::{AppMessage, AppModel};
+use crate::fl;
+
+/// Build the search bar shown above the canvas.
+pub fn view(model: &AppModel) -> Element<'_, AppMessage> {
+    let search = &model.search;
+    let has_hits = search
+        .results
+        .as_ref()
+        .is_some_and(|results| !results.hits().is_empty());
+
+    // "3 of 12" or "No matches" once a query was submitted.
+    let status = match &search.results {
+        Some(results) => match results.current_index() {
+            Some(index) => fl!(
+                "search-hit-position",
+                current: index + 1,
+                total: results.hits().len()
+            ),
+            None => fl!("search-no-results"),
+        },
+        None if search.searching => fl!("search-searching"),
+        None => String::new(),
+    };
+
+    row()
+        .spacing(8)
+        .padding([4, 12])
+        .align_y(Alignment::Center)
+        .push(
+            text_input::search_input(fl!("search-placeholder"), &search.query)
+                .id(search.input_id.clone())
+                .on_input(AppMessage::SearchQueryChanged)
+                .on_submit(|_| AppMessage::SearchSubmit)
+                .width(Length::Fixed(280.0)),
+        )
+        .push(
+            button::icon(icon::from_name("go-up-symbolic"))
+                .on_press_maybe(has_hits.then_some(AppMessage::SearchPrev))
+                .padding(4),
+        )
+        .push(
+            button::icon(icon::from_name("go-down-symbolic"))
+                .on_press_maybe(has_hits.then_some(AppMessage::SearchNext))
+                .padding(4),
+        )
+        .push(text(status))
+        .push(horizontal_space())
+        .push(
+            button::icon(icon::from_name("window-close-symbolic"))
+                .on_press(AppMessage::ToggleSearch)
+                .padding(4),
+        )
+        .into()
+}
+
+/// Build the list of hits with their page numbers.
+/// Returns None if there are no hits to show.
+pub fn hit_list(model: &AppModel) -> Option<Element<'_, AppMessage>> {
+    let results = model.search.results.as_ref()?;
+    if results.hits().is_empty() {
+        return None;
+    }
+
+    let current = results.current_index();
+    let mut content = column::with_capacity(results.hits().len())
+        .spacing(4)
+        .padding([8, 8]);
+
+    for (index, hit) in results.hits().iter().enumerate() {
+        let entry = column::with_capacity(2)
+            .spacing(2)
+            .push(text::caption(fl!("search-hit-page", page: hit.page + 1)))
+            .push(text::body(hit.context.as_str()));
+
+        let class = if current == Some(index) {
+            cosmic::theme::Button::Suggested
+        } else {
+            cosmic::theme::Button::Standard
+        };
+
+        content = content.push(
+            button::custom(entry)
+                .class(class)
+                .padding(4)
+                .width(Length::Fill)
+                .on_press(AppMessage::SelectSearchHit(index)),
+        );
+    }
+
+    Some(
+        scrollable(content)
+            .width(Length::Fixed(HIT_LIST_WIDTH))
+            .height(Length::Fill)
+            .into(),
+    )
+}
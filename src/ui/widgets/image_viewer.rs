@@ -6,14 +6,16 @@
 
 use cosmic::iced::advanced::image as img_renderer;
 use cosmic::iced::advanced::layout;
-use cosmic::iced::advanced::renderer;
+use cosmic::iced::advanced::renderer::{self, Quad, Renderer as QuadRenderer};
 use cosmic::iced::advanced::widget::tree::{self, Tree};
 use cosmic::iced::advanced::widget::Widget;
 use cosmic::iced::advanced::{Clipboard, Layout, Shell};
 use cosmic::iced::event::{self, Event};
 use cosmic::iced::mouse;
 use cosmic::iced::widget::image::FilterMethod;
-use cosmic::iced::{ContentFit, Element, Length, Pixels, Point, Radians, Rectangle, Size, Vector};
+use cosmic::iced::{
+    Color, ContentFit, Element, Length, Pixels, Point, Radians, Rectangle, Size, Vector,
+};
 
 /// Tolerance for scale comparisons in widget state synchronization.
 const SCALE_EPSILON: f32 = 0.0001;
@@ -21,9 +23,27 @@ const SCALE_EPSILON: f32 = 0.0001;
 /// Tolerance for offset comparisons in widget state synchronization.
 const OFFSET_EPSILON: f32 = 0.01;
 
+/// Fill color of text search highlights.
+const HIGHLIGHT_COLOR: Color = Color::from_rgba(1.0, 0.85, 0.0, 0.35);
+
+/// Fill color of the current text search hit.
+const ACTIVE_HIGHLIGHT_COLOR: Color = Color::from_rgba(1.0, 0.5, 0.0, 0.5);
+
+/// Distance the cursor may move between press and release of a link click.
+const CLICK_TOLERANCE: f32 = 4.0;
+
 /// Callback type for notifying viewer state changes (scale, `offset_x`, `offset_y`, `canvas_size`, `image_size`).
 type StateChangeCallback<Message> = Box<dyn Fn(f32, f32, f32, Size, Size) -> Message>;
 
+/// Callback type for clicks on a link (index into the link list).
+type LinkCallback<Message> = Box<dyn Fn(usize) -> Message>;
+
+/// Callback type for the image pixel under the cursor (`None` off the image).
+type PixelHoverCallback<Message> = Box<dyn Fn(Option<(u32, u32)>) -> Message>;
+
+/// Callback type for clicks on an image pixel.
+type PixelClickCallback<Message> = Box<dyn Fn(u32, u32) -> Message>;
+
 /// A frame that displays an image with the ability to zoom in/out and pan.
 #[allow(missing_debug_implementations)]
 pub struct Viewer<Handle, Message> {
@@ -42,6 +62,20 @@ pub struct Viewer<Handle, Message> {
     on_state_change: Option<StateChangeCallback<Message>>,
     /// Disable pan interaction (for crop mode)
     disable_pan: bool,
+    /// Image pixels per logical pixel (for images rendered at zoom resolution)
+    pixel_density: f32,
+    /// Rectangles to highlight, in logical image pixels
+    highlights: Vec<Rectangle>,
+    /// Index of the highlight drawn as the current one
+    active_highlight: Option<usize>,
+    /// Clickable areas, in logical image pixels
+    links: Vec<Rectangle>,
+    /// Callback for clicks on a link
+    on_link: Option<LinkCallback<Message>>,
+    /// Callback for the pixel under the cursor
+    on_pixel_hover: Option<PixelHoverCallback<Message>>,
+    /// Callback for clicks on a pixel
+    on_pixel_click: Option<PixelClickCallback<Message>>,
 }
 
 impl<Handle, Message> Viewer<Handle, Message> {
@@ -60,6 +94,13 @@ impl<Handle, Message> Viewer<Handle, Message> {
             external_state: None,
             on_state_change: None,
             disable_pan: false,
+            pixel_density: 1.0,
+            highlights: Vec::new(),
+            active_highlight: None,
+            links: Vec::new(),
+            on_link: None,
+            on_pixel_hover: None,
+            on_pixel_click: None,
         }
     }
 
@@ -85,6 +126,149 @@ impl<Handle, Message> Viewer<Handle, Message> {
         self
     }
 
+    /// Sets how many image pixels make up one logical pixel.
+    ///
+    /// Documents that re-render at the current zoom (PDF) hand out larger
+    /// images when zoomed in; the density keeps their displayed size stable.
+    ///
+    /// Default is `1.0`
+    pub fn pixel_density(mut self, pixel_density: f32) -> Self {
+        if pixel_density > 0.0 {
+            self.pixel_density = pixel_density;
+        }
+        self
+    }
+
+    /// Highlight areas of the image (text search hits).
+    ///
+    /// Rectangles are in logical image pixels; `active` marks the current one.
+    pub fn highlights(mut self, highlights: Vec<Rectangle>, active: Option<usize>) -> Self {
+        self.highlights = highlights;
+        self.active_highlight = active;
+        self
+    }
+
+    /// Make areas of the image clickable (document links).
+    ///
+    /// Rectangles are in logical image pixels; `f` receives the index of the
+    /// clicked one. A click is a press and release without panning.
+    pub fn links<F>(mut self, links: Vec<Rectangle>, f: F) -> Self
+    where
+        F: 'static + Fn(usize) -> Message,
+    {
+        self.links = links;
+        self.on_link = Some(Box::new(f));
+        self
+    }
+
+    /// Report the image pixel under the cursor whenever it changes
+    /// (inspector), also when zooming or panning moves the image under a
+    /// resting cursor.
+    ///
+    /// `f` receives the pixel coordinates in the image handle, or `None`
+    /// when the cursor leaves the image.
+    pub fn on_pixel_hover<F>(mut self, f: F) -> Self
+    where
+        F: 'static + Fn(Option<(u32, u32)>) -> Message,
+    {
+        self.on_pixel_hover = Some(Box::new(f));
+        self
+    }
+
+    /// Report clicks on the image with the pixel coordinates (inspector).
+    ///
+    /// A click is a press and release without panning.
+    pub fn on_pixel_click<F>(mut self, f: F) -> Self
+    where
+        F: 'static + Fn(u32, u32) -> Message,
+    {
+        self.on_pixel_click = Some(Box::new(f));
+        self
+    }
+
+    /// Index of the link under `position`, if any.
+    fn link_at<Renderer>(
+        &self,
+        renderer: &Renderer,
+        state: &State,
+        bounds: Rectangle,
+        position: Point,
+    ) -> Option<usize>
+    where
+        Renderer: img_renderer::Renderer<Handle = Handle>,
+    {
+        if self.links.is_empty() {
+            return None;
+        }
+
+        let point = self.image_point(renderer, state, bounds, position)?;
+        self.links.iter().position(|link| link.contains(point))
+    }
+
+    /// Image pixel under `position`, if any.
+    fn pixel_at<Renderer>(
+        &self,
+        renderer: &Renderer,
+        state: &State,
+        bounds: Rectangle,
+        position: Point,
+    ) -> Option<(u32, u32)>
+    where
+        Renderer: img_renderer::Renderer<Handle = Handle>,
+    {
+        let point = self.image_point(renderer, state, bounds, position)?;
+        let Size { width, height } = renderer.measure_image(&self.handle);
+        let x = (point.x * self.pixel_density).floor();
+        let y = (point.y * self.pixel_density).floor();
+        (x >= 0.0 && y >= 0.0 && x < width as f32 && y < height as f32)
+            .then_some((x as u32, y as u32))
+    }
+
+    /// Map a position on the widget to logical image pixels, undoing the
+    /// zoom and pan of the current state.
+    fn image_point<Renderer>(
+        &self,
+        renderer: &Renderer,
+        state: &State,
+        bounds: Rectangle,
+        position: Point,
+    ) -> Option<Point>
+    where
+        Renderer: img_renderer::Renderer<Handle = Handle>,
+    {
+        if !bounds.contains(position) {
+            return None;
+        }
+
+        let scaled_size = scaled_image_size(
+            renderer,
+            &self.handle,
+            self.pixel_density,
+            state,
+            bounds.size(),
+            self.content_fit,
+        );
+        let image_size = logical_image_size(renderer, &self.handle, self.pixel_density);
+        if image_size.width <= 0.0 || image_size.height <= 0.0 {
+            return None;
+        }
+
+        // Same placement as in `draw`: centered, then moved by the pan offset
+        let origin = bounds.position()
+            + Vector::new(
+                (bounds.width - scaled_size.width) / 2.0,
+                (bounds.height - scaled_size.height) / 2.0,
+            )
+            - state.current_offset;
+        let scale_x = scaled_size.width / image_size.width;
+        let scale_y = scaled_size.height / image_size.height;
+
+        Some(Point::new(
+            (position.x - origin.x) / scale_x,
+            (position.y - origin.y) / scale_y,
+        ))
+    }
+
     /// Sets the [`FilterMethod`] of the [`Viewer`].
     pub fn filter_method(mut self, filter_method: FilterMethod) -> Self {
         self.filter_method = filter_method;
@@ -196,8 +380,7 @@ where
         renderer: &Renderer,
         limits: &layout::Limits,
     ) -> layout::Node {
-        let image_size = renderer.measure_image(&self.handle);
-        let image_size = Size::new(image_size.width as f32, image_size.height as f32);
+        let image_size = logical_image_size(renderer, &self.handle, self.pixel_density);
 
         let raw_size = limits.resolve(self.width, self.height, image_size);
         let full_size = self.content_fit.fit(image_size, raw_size);
@@ -229,6 +412,20 @@ where
     ) -> event::Status {
         let bounds = layout.bounds();
 
+        // Zooming and panning (also with the keyboard) move the image under
+        // the cursor, so the hovered pixel is checked on every event; a
+        // change of the view is followed by a redraw event.
+        if let Some(ref on_pixel_hover) = self.on_pixel_hover {
+            let state = tree.state.downcast_mut::<State>();
+            let pixel = cursor
+                .position_over(bounds)
+                .and_then(|position| self.pixel_at(renderer, state, bounds, position));
+            if pixel != state.hovered_pixel {
+                state.hovered_pixel = pixel;
+                shell.publish(on_pixel_hover(pixel));
+            }
+        }
+
         match event {
             Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
                 let Some(cursor_position) = cursor.position_over(bounds) else {
@@ -269,6 +466,7 @@ where
                             let scaled_size = scaled_image_size(
                                 renderer,
                                 &self.handle,
+                                self.pixel_density,
                                 state,
                                 bounds.size(),
                                 self.content_fit,
@@ -279,9 +477,8 @@ where
 
                             // Notify state change
                             if let Some(ref on_change) = self.on_state_change {
-                                let image_size = renderer.measure_image(&self.handle);
                                 let image_size =
-                                    Size::new(image_size.width as f32, image_size.height as f32);
+                                    logical_image_size(renderer, &self.handle, self.pixel_density);
                                 shell.publish(on_change(
                                     state.scale,
                                     state.current_offset.x,
@@ -318,14 +515,28 @@ where
 
                 let state = tree.state.downcast_mut::<State>();
 
-                if state.cursor_grabbed_at.is_some() {
+                if let Some(grabbed_at) = state.cursor_grabbed_at {
                     state.cursor_grabbed_at = None;
 
+                    // A release close to the press point is a click, not a pan
+                    if let Some(position) = cursor.position()
+                        && position.distance(grabbed_at) < CLICK_TOLERANCE
+                    {
+                        if let Some(on_link) = &self.on_link
+                            && let Some(index) = self.link_at(renderer, state, bounds, position)
+                        {
+                            shell.publish(on_link(index));
+                        } else if let Some(on_pixel_click) = &self.on_pixel_click
+                            && let Some((x, y)) = self.pixel_at(renderer, state, bounds, position)
+                        {
+                            shell.publish(on_pixel_click(x, y));
+                        }
+                    }
+
                     // Notify final state after drag ends
                     if let Some(ref on_change) = self.on_state_change {
-                        let image_size = renderer.measure_image(&self.handle);
                         let image_size =
-                            Size::new(image_size.width as f32, image_size.height as f32);
+                            logical_image_size(renderer, &self.handle, self.pixel_density);
                         shell.publish(on_change(
                             state.scale,
                             state.current_offset.x,
@@ -351,6 +562,7 @@ where
                     let scaled_size = scaled_image_size(
                         renderer,
                         &self.handle,
+                        self.pixel_density,
                         state,
                         bounds.size(),
                         self.content_fit,
@@ -368,9 +580,8 @@ where
 
                     // Notify state change during pan
                     if let Some(ref on_change) = self.on_state_change {
-                        let image_size = renderer.measure_image(&self.handle);
                         let image_size =
-                            Size::new(image_size.width as f32, image_size.height as f32);
+                            logical_image_size(renderer, &self.handle, self.pixel_density);
                         shell.publish(on_change(
                             state.scale,
                             state.current_offset.x,
@@ -395,14 +606,22 @@ where
         layout: Layout<'_>,
         cursor: mouse::Cursor,
         _viewport: &Rectangle,
-        _renderer: &Renderer,
+        renderer: &Renderer,
     ) -> mouse::Interaction {
         let state = tree.state.downcast_ref::<State>();
         let bounds = layout.bounds();
         let is_mouse_over = cursor.is_over(bounds);
+        let over_link = cursor
+            .position()
+            .and_then(|position| self.link_at(renderer, state, bounds, position))
+            .is_some();
 
         if state.is_cursor_grabbed() {
             mouse::Interaction::Grabbing
+        } else if over_link {
+            mouse::Interaction::Pointer
+        } else if is_mouse_over && self.on_pixel_hover.is_some() {
+            mouse::Interaction::Crosshair
         } else if is_mouse_over {
             mouse::Interaction::Grab
         } else {
@@ -426,6 +645,7 @@ where
         let scaled_size = scaled_image_size(
             renderer,
             &self.handle,
+            self.pixel_density,
             state,
             bounds.size(),
             self.content_fit,
@@ -449,6 +669,17 @@ where
 
         let drawing_bounds = Rectangle::new(bounds.position(), scaled_size);
 
+        // Highlights scale with the image
+        let image_size = logical_image_size(renderer, &self.handle, self.pixel_density);
+        let highlight_scale = if image_size.width > 0.0 && image_size.height > 0.0 {
+            Vector::new(
+                scaled_size.width / image_size.width,
+                scaled_size.height / image_size.height,
+            )
+        } else {
+            Vector::new(0.0, 0.0)
+        };
+
         let render = |renderer: &mut Renderer| {
             renderer.with_translation(translation, |renderer| {
                 renderer.draw_image(
@@ -459,6 +690,27 @@ where
                     1.0,
                     [0.0; 4],
                 );
+
+                for (index, highlight) in self.highlights.iter().enumerate() {
+                    let color = if self.active_highlight == Some(index) {
+                        ACTIVE_HIGHLIGHT_COLOR
+                    } else {
+                        HIGHLIGHT_COLOR
+                    };
+                    let highlight_bounds = Rectangle {
+                        x: drawing_bounds.x + highlight.x * highlight_scale.x,
+                        y: drawing_bounds.y + highlight.y * highlight_scale.y,
+                        width: highlight.width * highlight_scale.x,
+                        height: highlight.height * highlight_scale.y,
+                    };
+                    renderer.fill_quad(
+                        Quad {
+                            bounds: highlight_bounds,
+                            ..Quad::default()
+                        },
+                        color,
+                    );
+                }
             });
         };
 
@@ -473,6 +725,7 @@ pub struct State {
     starting_offset: Vector,
     current_offset: Vector,
     cursor_grabbed_at: Option<Point>,
+    hovered_pixel: Option<(u32, u32)>,
 }
 
 impl Default for State {
@@ -482,6 +735,7 @@ impl Default for State {
             starting_offset: Vector::default(),
             current_offset: Vector::default(),
             cursor_grabbed_at: None,
+            hovered_pixel: None,
         }
     }
 }
@@ -537,6 +791,7 @@ where
 pub fn scaled_image_size<Renderer>(
     renderer: &Renderer,
     handle: &<Renderer as img_renderer::Renderer>::Handle,
+    pixel_density: f32,
     state: &State,
     bounds: Size,
     content_fit: ContentFit,
@@ -544,8 +799,7 @@ pub fn scaled_image_size<Renderer>(
 where
     Renderer: img_renderer::Renderer,
 {
-    let Size { width, height } = renderer.measure_image(handle);
-    let image_size = Size::new(width as f32, height as f32);
+    let image_size = logical_image_size(renderer, handle, pixel_density);
 
     let adjusted_fit = match content_fit {
         ContentFit::None => image_size,
@@ -557,3 +811,16 @@ where
         adjusted_fit.height * state.scale,
     )
 }
+
+/// Measure the image in logical pixels (image pixels divided by the density).
+fn logical_image_size<Renderer>(
+    renderer: &Renderer,
+    handle: &<Renderer as img_renderer::Renderer>::Handle,
+    pixel_density: f32,
+) -> Size
+where
+    Renderer: img_renderer::Renderer,
+{
+    let Size { width, height } = renderer.measure_image(handle);
+    Size::new(width as f32 / pixel_density, height as f32 / pixel_density)
+}